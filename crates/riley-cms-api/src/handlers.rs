@@ -1,15 +1,16 @@
 //! HTTP request handlers for riley-cms-api
 
 use crate::AppState;
-use crate::middleware::AuthStatus;
+use crate::middleware::{AuthStatus, SelectedContent};
 use axum::{
     Extension, Json,
     extract::{Path, Query, State},
-    http::{StatusCode, header},
+    http::{HeaderMap, StatusCode, header},
     response::{IntoResponse, Response},
 };
+use chrono::{DateTime, Utc};
 use futures_util::StreamExt;
-use riley_cms_core::ListOptions;
+use riley_cms_core::{FeedFormat, FeedOptions, ListOptions, RileyCms};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
@@ -22,23 +23,52 @@ pub struct ListQuery {
     pub include_scheduled: bool,
     pub limit: Option<usize>,
     pub offset: Option<usize>,
+    /// Comma-separated tags to filter posts by
+    pub tag: Option<String>,
+    /// Whether posts must have any (default) or all of the `tag` filters
+    #[serde(default)]
+    pub tag_match: TagMatch,
+}
+
+/// How multiple `tag` filters combine
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TagMatch {
+    #[default]
+    Any,
+    All,
 }
 
 impl From<ListQuery> for ListOptions {
     fn from(q: ListQuery) -> Self {
+        let tags: Vec<String> = q
+            .tag
+            .as_deref()
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .map(String::from)
+            .collect();
+        let (tags_any, tags_all) = match q.tag_match {
+            TagMatch::Any => (tags, Vec::new()),
+            TagMatch::All => (Vec::new(), tags),
+        };
         Self {
             include_drafts: q.include_drafts,
             include_scheduled: q.include_scheduled,
             limit: q.limit,
             offset: q.offset,
+            tags_any,
+            tags_all,
         }
     }
 }
 
 /// Error response format
 #[derive(Serialize)]
-struct ErrorResponse {
-    error: String,
+pub(crate) struct ErrorResponse {
+    pub(crate) error: String,
 }
 
 /// Convert internal errors to HTTP responses.
@@ -53,11 +83,15 @@ fn internal_error(err: impl std::fmt::Display) -> Response {
     (StatusCode::INTERNAL_SERVER_ERROR, body).into_response()
 }
 
-/// Add caching headers to response
+/// Add caching headers to response.
+///
+/// Public responses get `Cache-Control`, `ETag` and (when known) `Last-Modified`.
+/// Authenticated responses are never stored by shared caches.
 fn with_cache_headers(
     response: impl IntoResponse,
     state: &AppState,
     etag: &str,
+    last_modified: Option<DateTime<Utc>>,
     is_authenticated: bool,
 ) -> Response {
     let mut response = response.into_response();
@@ -85,11 +119,79 @@ fn with_cache_headers(
             .expect("valid cache-control header"),
         );
         headers.insert(header::ETAG, etag.parse().expect("valid etag header"));
+        if let Some(last_modified) = last_modified {
+            headers.insert(
+                header::LAST_MODIFIED,
+                http_date(last_modified)
+                    .parse()
+                    .expect("valid last-modified header"),
+            );
+        }
     }
 
     response
 }
 
+/// Respond with caching headers, or with `304 Not Modified` if the client's copy is current.
+///
+/// Authenticated responses are `no-store`, so conditional requests only apply to public ones.
+/// List pages, feeds and the sitemap pass no `last_modified`: their items' times say nothing
+/// about deletions, go-lives or reordering, so only their ETag can validate them.
+fn cached_response(
+    request_headers: &HeaderMap,
+    response: impl IntoResponse,
+    state: &AppState,
+    etag: &str,
+    last_modified: Option<DateTime<Utc>>,
+    is_authenticated: bool,
+) -> Response {
+    if !is_authenticated && is_not_modified(request_headers, etag, last_modified) {
+        return with_cache_headers(StatusCode::NOT_MODIFIED, state, etag, last_modified, false);
+    }
+    with_cache_headers(response, state, etag, last_modified, is_authenticated)
+}
+
+/// Evaluate `If-None-Match` / `If-Modified-Since` (RFC 9110 section 13.2.2).
+///
+/// `If-None-Match` takes precedence; `If-Modified-Since` is only consulted when it is absent.
+fn is_not_modified(
+    request_headers: &HeaderMap,
+    etag: &str,
+    last_modified: Option<DateTime<Utc>>,
+) -> bool {
+    if let Some(if_none_match) = request_headers.get(header::IF_NONE_MATCH) {
+        let Ok(if_none_match) = if_none_match.to_str() else {
+            return false;
+        };
+        // Weak comparison: W/"x" matches "x"
+        return if_none_match
+            .split(',')
+            .map(str::trim)
+            .any(|candidate| candidate == "*" || candidate.trim_start_matches("W/") == etag);
+    }
+
+    let (Some(last_modified), Some(if_modified_since)) = (
+        last_modified,
+        request_headers.get(header::IF_MODIFIED_SINCE),
+    ) else {
+        return false;
+    };
+    let Some(since) = if_modified_since
+        .to_str()
+        .ok()
+        .and_then(|v| DateTime::parse_from_rfc2822(v).ok())
+    else {
+        return false;
+    };
+    // HTTP dates have one-second resolution
+    last_modified.timestamp() <= since.timestamp()
+}
+
+/// Format a timestamp as an HTTP date (IMF-fixdate)
+fn http_date(date: DateTime<Utc>) -> String {
+    date.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+}
+
 /// Check if request requires authentication (has include_drafts or include_scheduled)
 fn is_authenticated_request(query: &ListQuery) -> bool {
     query.include_drafts || query.include_scheduled
@@ -125,8 +227,10 @@ fn not_found_response(slug: &str, kind: &str) -> Response {
 /// GET /posts - List all posts
 pub async fn list_posts(
     State(state): State<Arc<AppState>>,
+    content: SelectedContent,
     Extension(auth_status): Extension<AuthStatus>,
     Query(query): Query<ListQuery>,
+    headers: HeaderMap,
 ) -> Response {
     let is_auth_required = is_authenticated_request(&query);
 
@@ -143,9 +247,9 @@ pub async fn list_posts(
 
     let opts: ListOptions = query.into();
 
-    match state.riley_cms.list_posts(&opts).await {
+    match content.list_posts(&opts).await {
         Ok(result) => {
-            let etag = state.riley_cms.content_etag().await;
+            let etag = result.etag;
 
             #[derive(Serialize)]
             struct PostsResponse {
@@ -162,26 +266,47 @@ pub async fn list_posts(
                 offset: result.offset,
             });
 
-            with_cache_headers(response, &state, &etag, is_auth_required)
+            cached_response(&headers, response, &state, &etag, None, is_auth_required)
         }
         Err(e) => internal_error(e),
     }
 }
 
+/// Query parameters for the single post endpoint
+#[derive(Debug, Clone, Deserialize)]
+pub struct PostQuery {
+    /// Include the rendered `content_html` field
+    #[serde(default)]
+    pub include_html: bool,
+}
+
 /// GET /posts/:slug - Get a single post
 pub async fn get_post(
     State(state): State<Arc<AppState>>,
+    content: SelectedContent,
     Extension(auth_status): Extension<AuthStatus>,
     Path(slug): Path<String>,
+    Query(query): Query<PostQuery>,
+    headers: HeaderMap,
 ) -> Response {
-    match state.riley_cms.get_post(&slug).await {
-        Ok(Some(post)) => {
+    match content.get_post(&slug).await {
+        Ok(Some(mut post)) => {
             // Visibility check: drafts/scheduled posts require admin auth
             if !is_content_visible(post.goes_live_at, auth_status) {
                 return not_found_response(&slug, "Post");
             }
-            let etag = state.riley_cms.content_etag().await;
-            with_cache_headers(Json(post), &state, &etag, auth_status == AuthStatus::Admin)
+            if !query.include_html {
+                post.content_html = None;
+            }
+            let (etag, last_modified) = (post.etag.clone(), post.last_modified);
+            cached_response(
+                &headers,
+                Json(post),
+                &state,
+                &etag,
+                last_modified,
+                auth_status == AuthStatus::Admin,
+            )
         }
         Ok(None) => not_found_response(&slug, "Post"),
         Err(e) => internal_error(e),
@@ -191,53 +316,273 @@ pub async fn get_post(
 /// GET /posts/:slug/raw - Get raw MDX content only
 pub async fn get_post_raw(
     State(state): State<Arc<AppState>>,
+    content: SelectedContent,
     Extension(auth_status): Extension<AuthStatus>,
     Path(slug): Path<String>,
+    headers: HeaderMap,
 ) -> Response {
-    match state.riley_cms.get_post(&slug).await {
+    match content.get_post(&slug).await {
         Ok(Some(post)) => {
             // Visibility check: drafts/scheduled posts require admin auth
             if !is_content_visible(post.goes_live_at, auth_status) {
                 return not_found_response(&slug, "Post");
             }
-            let is_admin = auth_status == AuthStatus::Admin;
-            let etag = state.riley_cms.content_etag().await;
-            let mut response = post.content.into_response();
-            let headers = response.headers_mut();
+            let response = (
+                [(header::CONTENT_TYPE, "text/plain; charset=utf-8")],
+                post.content,
+            );
+            cached_response(
+                &headers,
+                response,
+                &state,
+                &post.etag,
+                post.last_modified,
+                auth_status == AuthStatus::Admin,
+            )
+        }
+        Ok(None) => not_found_response(&slug, "Post"),
+        Err(e) => internal_error(e),
+    }
+}
 
-            headers.insert(
-                header::CONTENT_TYPE,
-                "text/plain; charset=utf-8"
-                    .parse()
-                    .expect("valid static header"),
+/// GET /posts/:slug/html - Get content rendered to sanitized HTML
+pub async fn get_post_html(
+    State(state): State<Arc<AppState>>,
+    content: SelectedContent,
+    Extension(auth_status): Extension<AuthStatus>,
+    Path(slug): Path<String>,
+    headers: HeaderMap,
+) -> Response {
+    match content.get_post(&slug).await {
+        Ok(Some(post)) => {
+            // Visibility check: drafts/scheduled posts require admin auth
+            if !is_content_visible(post.goes_live_at, auth_status) {
+                return not_found_response(&slug, "Post");
+            }
+            let response = (
+                [(header::CONTENT_TYPE, "text/html; charset=utf-8")],
+                post.content_html.unwrap_or_default(),
             );
+            cached_response(
+                &headers,
+                response,
+                &state,
+                &post.etag,
+                post.last_modified,
+                auth_status == AuthStatus::Admin,
+            )
+        }
+        Ok(None) => not_found_response(&slug, "Post"),
+        Err(e) => internal_error(e),
+    }
+}
 
-            if is_admin {
-                headers.insert(
-                    header::CACHE_CONTROL,
-                    "private, no-store".parse().expect("valid static header"),
-                );
-            } else {
-                let server = state.config.server.as_ref();
-                let max_age = server.map(|s| s.cache_max_age).unwrap_or(60);
-                let swr = server
-                    .map(|s| s.cache_stale_while_revalidate)
-                    .unwrap_or(300);
-
-                headers.insert(
-                    header::CACHE_CONTROL,
-                    format!(
-                        "public, max-age={}, stale-while-revalidate={}",
-                        max_age, swr
-                    )
-                    .parse()
-                    .expect("valid cache-control header"),
-                );
-                headers.insert(header::ETAG, etag.parse().expect("valid etag header"));
+/// Query parameters for the post history endpoint
+#[derive(Debug, Clone, Deserialize)]
+pub struct HistoryQuery {
+    pub limit: Option<usize>,
+}
+
+/// Maximum number of revisions returned by the history endpoint
+const MAX_HISTORY_LIMIT: usize = 500;
+
+/// GET /posts/:slug/history - Commits touching a post (admin only)
+pub async fn get_post_history(
+    content: SelectedContent,
+    Extension(auth_status): Extension<AuthStatus>,
+    Path(slug): Path<String>,
+    Query(query): Query<HistoryQuery>,
+) -> Response {
+    if auth_status != AuthStatus::Admin {
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(ErrorResponse {
+                error: "Authentication required for post history".to_string(),
+            }),
+        )
+            .into_response();
+    }
+
+    let limit = query.limit.unwrap_or(50).min(MAX_HISTORY_LIMIT);
+
+    match content.post_history(&slug, limit).await {
+        Ok(Some(revisions)) => {
+            #[derive(Serialize)]
+            struct HistoryResponse {
+                slug: String,
+                revisions: Vec<riley_cms_core::Revision>,
+            }
+
+            let mut response = Json(HistoryResponse { slug, revisions }).into_response();
+            response.headers_mut().insert(
+                header::CACHE_CONTROL,
+                "private, no-store".parse().expect("valid static header"),
+            );
+            response
+        }
+        Ok(None) => not_found_response(&slug, "Post"),
+        Err(e) => internal_error(e),
+    }
+}
+
+/// Maximum number of entries returned by the push audit log endpoint
+const MAX_PUSHES_LIMIT: usize = 500;
+
+/// GET /admin/pushes - Accepted git pushes, newest first (admin only)
+pub async fn list_pushes(
+    State(state): State<Arc<AppState>>,
+    Extension(auth_status): Extension<AuthStatus>,
+    Query(query): Query<HistoryQuery>,
+) -> Response {
+    if auth_status != AuthStatus::Admin {
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(ErrorResponse {
+                error: "Authentication required for the push log".to_string(),
+            }),
+        )
+            .into_response();
+    }
+
+    let limit = query.limit.unwrap_or(50).min(MAX_PUSHES_LIMIT);
+
+    match state.riley_cms.list_pushes(limit).await {
+        Ok(pushes) => {
+            #[derive(Serialize)]
+            struct PushesResponse {
+                pushes: Vec<riley_cms_core::PushRecord>,
             }
 
+            let mut response = Json(PushesResponse { pushes }).into_response();
+            response.headers_mut().insert(
+                header::CACHE_CONTROL,
+                "private, no-store".parse().expect("valid static header"),
+            );
             response
         }
+        Err(e) => internal_error(e),
+    }
+}
+
+/// GET /admin/refresh - Refresh worker queue state and last result (admin only)
+pub async fn refresh_status(
+    State(state): State<Arc<AppState>>,
+    Extension(auth_status): Extension<AuthStatus>,
+) -> Response {
+    if auth_status != AuthStatus::Admin {
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(ErrorResponse {
+                error: "Authentication required for refresh status".to_string(),
+            }),
+        )
+            .into_response();
+    }
+
+    let mut response = Json(state.riley_cms.refresh_status()).into_response();
+    response.headers_mut().insert(
+        header::CACHE_CONTROL,
+        "private, no-store".parse().expect("valid static header"),
+    );
+    response
+}
+
+/// Maximum number of entries returned by the webhook delivery log endpoint
+const MAX_DELIVERIES_LIMIT: usize = 500;
+
+/// GET /admin/webhooks/deliveries - Webhook deliveries and their attempts, newest first (admin only)
+pub async fn list_webhook_deliveries(
+    State(state): State<Arc<AppState>>,
+    Extension(auth_status): Extension<AuthStatus>,
+    Query(query): Query<HistoryQuery>,
+) -> Response {
+    if auth_status != AuthStatus::Admin {
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(ErrorResponse {
+                error: "Authentication required for the webhook delivery log".to_string(),
+            }),
+        )
+            .into_response();
+    }
+
+    let limit = query.limit.unwrap_or(50).min(MAX_DELIVERIES_LIMIT);
+
+    match state.riley_cms.list_webhook_deliveries(limit).await {
+        Ok(deliveries) => {
+            #[derive(Serialize)]
+            struct DeliveriesResponse {
+                deliveries: Vec<riley_cms_core::WebhookDelivery>,
+            }
+
+            let mut response = Json(DeliveriesResponse { deliveries }).into_response();
+            response.headers_mut().insert(
+                header::CACHE_CONTROL,
+                "private, no-store".parse().expect("valid static header"),
+            );
+            response
+        }
+        Err(e) => internal_error(e),
+    }
+}
+
+/// POST /admin/webhooks/deliveries/:id/redeliver - Queue a webhook delivery again (admin only)
+pub async fn redeliver_webhook(
+    State(state): State<Arc<AppState>>,
+    Extension(auth_status): Extension<AuthStatus>,
+    Path(id): Path<String>,
+) -> Response {
+    if auth_status != AuthStatus::Admin {
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(ErrorResponse {
+                error: "Authentication required to redeliver webhooks".to_string(),
+            }),
+        )
+            .into_response();
+    }
+
+    match state.riley_cms.redeliver_webhook(&id).await {
+        Ok(Some(delivery)) => (StatusCode::ACCEPTED, Json(delivery)).into_response(),
+        Ok(None) => not_found_response(&id, "Webhook delivery"),
+        Err(e) => internal_error(e),
+    }
+}
+
+/// GET /series/:series/posts/:slug - Get a post within a series
+pub async fn get_series_post(
+    State(state): State<Arc<AppState>>,
+    content: SelectedContent,
+    Extension(auth_status): Extension<AuthStatus>,
+    Path((series_slug, slug)): Path<(String, String)>,
+    Query(query): Query<PostQuery>,
+    headers: HeaderMap,
+) -> Response {
+    // A post inside a hidden series is hidden too
+    match content.get_series(&series_slug).await {
+        Ok(Some(series)) if is_content_visible(series.goes_live_at, auth_status) => {}
+        Ok(_) => return not_found_response(&series_slug, "Series"),
+        Err(e) => return internal_error(e),
+    }
+
+    match content.get_series_post(&series_slug, &slug).await {
+        Ok(Some(mut post)) => {
+            if !is_content_visible(post.goes_live_at, auth_status) {
+                return not_found_response(&slug, "Post");
+            }
+            if !query.include_html {
+                post.content_html = None;
+            }
+            let (etag, last_modified) = (post.etag.clone(), post.last_modified);
+            cached_response(
+                &headers,
+                Json(post),
+                &state,
+                &etag,
+                last_modified,
+                auth_status == AuthStatus::Admin,
+            )
+        }
         Ok(None) => not_found_response(&slug, "Post"),
         Err(e) => internal_error(e),
     }
@@ -246,8 +591,10 @@ pub async fn get_post_raw(
 /// GET /series - List all series
 pub async fn list_series(
     State(state): State<Arc<AppState>>,
+    content: SelectedContent,
     Extension(auth_status): Extension<AuthStatus>,
     Query(query): Query<ListQuery>,
+    headers: HeaderMap,
 ) -> Response {
     let is_auth_required = is_authenticated_request(&query);
 
@@ -264,9 +611,9 @@ pub async fn list_series(
 
     let opts: ListOptions = query.into();
 
-    match state.riley_cms.list_series(&opts).await {
+    match content.list_series(&opts).await {
         Ok(result) => {
-            let etag = state.riley_cms.content_etag().await;
+            let etag = result.etag;
 
             #[derive(Serialize)]
             struct SeriesResponse {
@@ -283,7 +630,7 @@ pub async fn list_series(
                 offset: result.offset,
             });
 
-            with_cache_headers(response, &state, &etag, is_auth_required)
+            cached_response(&headers, response, &state, &etag, None, is_auth_required)
         }
         Err(e) => internal_error(e),
     }
@@ -292,20 +639,24 @@ pub async fn list_series(
 /// GET /series/:slug - Get a single series with posts
 pub async fn get_series(
     State(state): State<Arc<AppState>>,
+    content: SelectedContent,
     Extension(auth_status): Extension<AuthStatus>,
     Path(slug): Path<String>,
+    headers: HeaderMap,
 ) -> Response {
-    match state.riley_cms.get_series(&slug).await {
+    match content.get_series(&slug).await {
         Ok(Some(series)) => {
             // Visibility check: drafts/scheduled series require admin auth
             if !is_content_visible(series.goes_live_at, auth_status) {
                 return not_found_response(&slug, "Series");
             }
-            let etag = state.riley_cms.content_etag().await;
-            with_cache_headers(
+            let (etag, last_modified) = (series.etag.clone(), series.last_modified);
+            cached_response(
+                &headers,
                 Json(series),
                 &state,
                 &etag,
+                last_modified,
                 auth_status == AuthStatus::Admin,
             )
         }
@@ -314,6 +665,293 @@ pub async fn get_series(
     }
 }
 
+/// GET /tags - List tags with counts of visible posts
+pub async fn list_tags(
+    State(state): State<Arc<AppState>>,
+    content: SelectedContent,
+    Extension(auth_status): Extension<AuthStatus>,
+    Query(query): Query<ListQuery>,
+    headers: HeaderMap,
+) -> Response {
+    let is_auth_required = is_authenticated_request(&query);
+
+    if is_auth_required && auth_status != AuthStatus::Admin {
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(ErrorResponse {
+                error: "Authentication required for drafts/scheduled content".to_string(),
+            }),
+        )
+            .into_response();
+    }
+
+    let opts: ListOptions = query.into();
+
+    match content.list_tags(&opts).await {
+        Ok(result) => {
+            let etag = result.etag;
+
+            #[derive(Serialize)]
+            struct TagsResponse {
+                tags: Vec<riley_cms_core::TagSummary>,
+                total: usize,
+                limit: usize,
+                offset: usize,
+            }
+
+            let response = Json(TagsResponse {
+                tags: result.items,
+                total: result.total,
+                limit: result.limit,
+                offset: result.offset,
+            });
+
+            cached_response(&headers, response, &state, &etag, None, is_auth_required)
+        }
+        Err(e) => internal_error(e),
+    }
+}
+
+/// GET /tags/:tag - List visible posts with a tag
+pub async fn get_tag(
+    State(state): State<Arc<AppState>>,
+    content: SelectedContent,
+    Extension(auth_status): Extension<AuthStatus>,
+    Path(tag): Path<String>,
+    Query(query): Query<ListQuery>,
+    headers: HeaderMap,
+) -> Response {
+    let is_auth_required = is_authenticated_request(&query);
+
+    if is_auth_required && auth_status != AuthStatus::Admin {
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(ErrorResponse {
+                error: "Authentication required for drafts/scheduled content".to_string(),
+            }),
+        )
+            .into_response();
+    }
+
+    let opts = ListOptions {
+        tags_any: vec![tag.clone()],
+        tags_all: Vec::new(),
+        ..query.into()
+    };
+
+    match content.list_posts(&opts).await {
+        // A tag without visible posts doesn't exist as far as the client is concerned
+        Ok(result) if result.total == 0 => not_found_response(&tag, "Tag"),
+        Ok(result) => {
+            let etag = result.etag;
+
+            #[derive(Serialize)]
+            struct TagResponse {
+                tag: String,
+                posts: Vec<riley_cms_core::PostSummary>,
+                total: usize,
+                limit: usize,
+                offset: usize,
+            }
+
+            let response = Json(TagResponse {
+                tag,
+                posts: result.items,
+                total: result.total,
+                limit: result.limit,
+                offset: result.offset,
+            });
+
+            cached_response(&headers, response, &state, &etag, None, is_auth_required)
+        }
+        Err(e) => internal_error(e),
+    }
+}
+
+/// Query parameters for the search endpoint
+#[derive(Debug, Clone, Deserialize)]
+pub struct SearchQuery {
+    pub q: Option<String>,
+    #[serde(default)]
+    pub include_drafts: bool,
+    #[serde(default)]
+    pub include_scheduled: bool,
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+}
+
+/// Maximum accepted length of a search query, in bytes
+const MAX_SEARCH_QUERY_LEN: usize = 256;
+
+/// GET /search - Full-text search over posts
+pub async fn search(
+    State(state): State<Arc<AppState>>,
+    content: SelectedContent,
+    Extension(auth_status): Extension<AuthStatus>,
+    Query(query): Query<SearchQuery>,
+    headers: HeaderMap,
+) -> Response {
+    let q = query.q.as_deref().map(str::trim).unwrap_or_default();
+    if q.is_empty() || q.len() > MAX_SEARCH_QUERY_LEN {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: format!(
+                    "Query parameter 'q' must be between 1 and {} bytes",
+                    MAX_SEARCH_QUERY_LEN
+                ),
+            }),
+        )
+            .into_response();
+    }
+
+    let is_auth_required = query.include_drafts || query.include_scheduled;
+    if is_auth_required && auth_status != AuthStatus::Admin {
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(ErrorResponse {
+                error: "Authentication required for drafts/scheduled content".to_string(),
+            }),
+        )
+            .into_response();
+    }
+
+    let opts = ListOptions {
+        include_drafts: query.include_drafts,
+        include_scheduled: query.include_scheduled,
+        limit: query.limit,
+        offset: query.offset,
+        ..Default::default()
+    };
+
+    match content.search(q, &opts).await {
+        Ok(result) => {
+            let etag = result.etag;
+
+            #[derive(Serialize)]
+            struct SearchResponse {
+                results: Vec<riley_cms_core::SearchResult>,
+                total: usize,
+                limit: usize,
+                offset: usize,
+            }
+
+            let response = Json(SearchResponse {
+                results: result.items,
+                total: result.total,
+                limit: result.limit,
+                offset: result.offset,
+            });
+
+            cached_response(&headers, response, &state, &etag, None, is_auth_required)
+        }
+        Err(e) => internal_error(e),
+    }
+}
+
+/// Query parameters for feed endpoints
+#[derive(Debug, Clone, Deserialize)]
+pub struct FeedQuery {
+    /// Only posts in this series
+    pub series: Option<String>,
+    /// Only posts with this tag
+    pub tag: Option<String>,
+}
+
+/// GET /feed.xml - RSS 2.0 feed of live posts
+pub async fn rss_feed(
+    State(state): State<Arc<AppState>>,
+    content: SelectedContent,
+    Query(query): Query<FeedQuery>,
+    headers: HeaderMap,
+) -> Response {
+    feed_response(&state, &content, &headers, FeedFormat::Rss, query).await
+}
+
+/// GET /atom.xml - Atom feed of live posts
+pub async fn atom_feed(
+    State(state): State<Arc<AppState>>,
+    content: SelectedContent,
+    Query(query): Query<FeedQuery>,
+    headers: HeaderMap,
+) -> Response {
+    feed_response(&state, &content, &headers, FeedFormat::Atom, query).await
+}
+
+/// GET /feed.json - JSON Feed of live posts
+pub async fn json_feed(
+    State(state): State<Arc<AppState>>,
+    content: SelectedContent,
+    Query(query): Query<FeedQuery>,
+    headers: HeaderMap,
+) -> Response {
+    feed_response(&state, &content, &headers, FeedFormat::Json, query).await
+}
+
+/// Shared implementation of the feed endpoints.
+///
+/// Feeds only contain live content, so they are publicly cacheable regardless of auth.
+async fn feed_response(
+    state: &AppState,
+    content: &RileyCms,
+    headers: &HeaderMap,
+    format: FeedFormat,
+    query: FeedQuery,
+) -> Response {
+    if state.config.feed.is_none() {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "Feeds are not configured".to_string(),
+            }),
+        )
+            .into_response();
+    }
+
+    let series = query.series.clone();
+    let opts = FeedOptions {
+        series: query.series,
+        tag: query.tag,
+    };
+
+    match content.feed(format, &opts).await {
+        Ok(Some(feed)) => {
+            let response = ([(header::CONTENT_TYPE, format.content_type())], feed.body);
+            cached_response(headers, response, state, &feed.etag, None, false)
+        }
+        Ok(None) => not_found_response(series.as_deref().unwrap_or_default(), "Series"),
+        Err(e) => internal_error(e),
+    }
+}
+
+/// GET /sitemap.xml - Sitemap of live posts and series
+pub async fn sitemap(
+    State(state): State<Arc<AppState>>,
+    content: SelectedContent,
+    headers: HeaderMap,
+) -> Response {
+    if state.config.sitemap.is_none() {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "Sitemap is not configured".to_string(),
+            }),
+        )
+            .into_response();
+    }
+
+    match content.sitemap().await {
+        Ok(sitemap) => {
+            let response = (
+                [(header::CONTENT_TYPE, "application/xml; charset=utf-8")],
+                sitemap.body,
+            );
+            cached_response(&headers, response, &state, &sitemap.etag, None, false)
+        }
+        Err(e) => internal_error(e),
+    }
+}
+
 /// Query parameters for asset list endpoint
 #[derive(Debug, Clone, Deserialize)]
 pub struct AssetListQuery {
@@ -367,76 +1005,128 @@ pub async fn health() -> Response {
 
 // === Git Smart HTTP Handlers ===
 
-use axum::http::HeaderMap;
 use base64::Engine;
-use riley_cms_core::GitBackend;
+use riley_cms_core::{BodyStream, GitBackend, GitScope, ReceivePack, RefUpdate};
 use sha2::{Digest, Sha256};
 use subtle::ConstantTimeEq;
 
+/// Who a git request authenticated as
+#[derive(Debug, Clone)]
+struct GitUser {
+    /// Name recorded in the push audit log
+    name: String,
+    /// Whether the credential may push
+    can_write: bool,
+    /// Whether the credential is the branch protection override token
+    override_protection: bool,
+}
+
 /// Check Basic Auth for Git operations
 ///
-/// Git clients typically use Basic Auth (not Bearer tokens).
-/// Returns true if authentication is valid.
-fn check_git_basic_auth(headers: &HeaderMap, state: &AppState) -> bool {
-    // Get the configured git_token
-    let expected_token = match &state.config.auth {
-        Some(auth) => match &auth.git_token {
-            Some(token_config) => match token_config.resolve() {
-                Ok(token) => {
-                    if token.is_empty() {
-                        tracing::warn!("Git token resolves to empty string. Git auth disabled.");
-                        return false;
-                    }
-                    token
-                }
-                Err(e) => {
-                    tracing::warn!("Failed to resolve git token: {}. Git auth disabled.", e);
-                    return false;
-                }
-            },
-            None => return false, // No git_token configured, deny access
-        },
-        None => return false, // No auth configured, deny access
-    };
-
-    // Parse Basic Auth header
-    let auth_header = match headers.get(header::AUTHORIZATION) {
-        Some(h) => h,
-        None => return false,
-    };
+/// Git clients typically use Basic Auth (not Bearer tokens). A named
+/// credential from `[[auth.git_credentials]]` matches on both username and
+/// password; the shared `git_token` and the branch protection override token
+/// accept any username. Returns `None` if nothing matches.
+fn authenticate_git(headers: &HeaderMap, state: &AppState) -> Option<GitUser> {
+    let (username, password) = basic_auth_credentials(headers)?;
+    let auth = state.config.auth.as_ref();
+
+    for credential in auth
+        .map(|a| a.git_credentials.as_slice())
+        .unwrap_or_default()
+    {
+        if credential.name != username {
+            continue;
+        }
+        match credential.token.resolve() {
+            Ok(token) if token.is_empty() => {
+                tracing::warn!(
+                    "Git credential {:?} resolves to empty string. Credential disabled.",
+                    credential.name
+                );
+            }
+            Ok(token) if tokens_match(&password, &token) => {
+                return Some(GitUser {
+                    name: credential.name.clone(),
+                    can_write: credential.scope == GitScope::ReadWrite,
+                    override_protection: false,
+                });
+            }
+            Ok(_) => {}
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to resolve git credential {:?}: {}. Credential disabled.",
+                    credential.name,
+                    e
+                );
+            }
+        }
+    }
 
-    let auth_str = match auth_header.to_str() {
-        Ok(s) => s,
-        Err(_) => return false,
+    let git_token_matches = match auth.and_then(|a| a.git_token.as_ref()) {
+        Some(token_config) => match token_config.resolve() {
+            Ok(token) if token.is_empty() => {
+                tracing::warn!("Git token resolves to empty string. Git auth disabled.");
+                false
+            }
+            Ok(token) => tokens_match(&password, &token),
+            Err(e) => {
+                tracing::warn!("Failed to resolve git token: {}. Git auth disabled.", e);
+                false
+            }
+        },
+        None => false, // No git_token configured
     };
+    if git_token_matches {
+        return Some(GitUser {
+            name: "git".to_string(),
+            can_write: true,
+            override_protection: false,
+        });
+    }
 
-    // Format: "Basic base64(username:password)"
-    let encoded = match auth_str.strip_prefix("Basic ") {
-        Some(e) => e,
-        None => return false,
-    };
+    is_override_token(&password, state).then(|| GitUser {
+        name: "override".to_string(),
+        can_write: true,
+        override_protection: true,
+    })
+}
 
-    let decoded = match base64::engine::general_purpose::STANDARD.decode(encoded) {
-        Ok(d) => d,
-        Err(_) => return false,
-    };
+fn is_override_token(password: &str, state: &AppState) -> bool {
+    let token = state
+        .config
+        .git
+        .as_ref()
+        .and_then(|g| g.branch_protection.as_ref())
+        .and_then(|p| p.override_token.as_ref());
+    match token.map(|t| t.resolve()) {
+        Some(Ok(token)) if !token.is_empty() => tokens_match(password, &token),
+        Some(Ok(_)) => false,
+        Some(Err(e)) => {
+            tracing::warn!("Failed to resolve branch protection override token: {}", e);
+            false
+        }
+        None => false,
+    }
+}
 
-    let credentials = match String::from_utf8(decoded) {
-        Ok(c) => c,
-        Err(_) => return false,
-    };
+/// Compare tokens by hash, so their length doesn't leak via a timing side-channel
+fn tokens_match(provided: &str, expected: &str) -> bool {
+    let provided_hash = Sha256::digest(provided.as_bytes());
+    let expected_hash = Sha256::digest(expected.as_bytes());
+    provided_hash.ct_eq(&expected_hash).into()
+}
 
-    // Format: "username:password" - we only check password (the token)
-    // Username can be anything (commonly "git" or the actual username)
-    if let Some((_username, password)) = credentials.split_once(':') {
-        // Hash both tokens before comparing to prevent leaking
-        // token length via timing side-channel.
-        let provided_hash = Sha256::digest(password.as_bytes());
-        let expected_hash = Sha256::digest(expected_token.as_bytes());
-        provided_hash.ct_eq(&expected_hash).into()
-    } else {
-        false
-    }
+/// The username and password from a `Basic base64(username:password)` Authorization header
+fn basic_auth_credentials(headers: &HeaderMap) -> Option<(String, String)> {
+    let auth_str = headers.get(header::AUTHORIZATION)?.to_str().ok()?;
+    let encoded = auth_str.strip_prefix("Basic ")?;
+    let decoded = base64::engine::general_purpose::STANDARD
+        .decode(encoded)
+        .ok()?;
+    let credentials = String::from_utf8(decoded).ok()?;
+    let (username, password) = credentials.split_once(':')?;
+    Some((username.to_string(), password.to_string()))
 }
 
 /// Default maximum allowed body size for git operations (100 MB)
@@ -453,6 +1143,119 @@ fn is_valid_git_path(path: &str) -> bool {
         .all(|c| c.is_ascii_alphanumeric() || "-_./=?&+".contains(c))
 }
 
+/// Read a `git-receive-pack` request body into memory, up to `max_body_size`
+async fn buffer_push_body(
+    headers: &HeaderMap,
+    body: axum::body::Body,
+    max_body_size: u64,
+) -> Result<axum::body::Bytes, Response> {
+    // The body has to be parsed, and git only compresses fetch requests
+    let encoding = headers
+        .get(header::CONTENT_ENCODING)
+        .and_then(|h| h.to_str().ok())
+        .unwrap_or("identity");
+    if !encoding.eq_ignore_ascii_case("identity") {
+        return Err((
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            Json(ErrorResponse {
+                error: format!("Unsupported Content-Encoding for push: {}", encoding),
+            }),
+        )
+            .into_response());
+    }
+
+    let limit = usize::try_from(max_body_size).unwrap_or(usize::MAX);
+    axum::body::to_bytes(body, limit).await.map_err(|e| {
+        let too_large = std::error::Error::source(&e)
+            .is_some_and(|source| source.is::<http_body_util::LengthLimitError>());
+        if too_large {
+            (
+                StatusCode::PAYLOAD_TOO_LARGE,
+                Json(ErrorResponse {
+                    error: format!(
+                        "Request body too large (exceeds max {} bytes)",
+                        max_body_size
+                    ),
+                }),
+            )
+                .into_response()
+        } else {
+            tracing::warn!("Failed to read push body: {}", e);
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: "Failed to read request body".to_string(),
+                }),
+            )
+                .into_response()
+        }
+    })
+}
+
+/// Check a push against the push policy and validate its content before git sees it.
+///
+/// Returns the response to send instead of running git-http-backend, if the
+/// push is rejected or can't be checked.
+async fn check_push(
+    state: &AppState,
+    backend: &GitBackend,
+    request: &ReceivePack,
+    body: &axum::body::Bytes,
+    override_protection: bool,
+) -> Option<Response> {
+    if override_protection {
+        tracing::warn!("Push is using the branch protection override token");
+    }
+    let rejection = match backend
+        .check_push(request, body, &state.config, override_protection)
+        .await
+    {
+        Ok(Some(rejection)) => rejection,
+        Ok(None) => return None,
+        Err(e) => {
+            tracing::error!("Failed to validate push: {}", e);
+            return Some(
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ErrorResponse {
+                        error: "Git operation failed".to_string(),
+                    }),
+                )
+                    .into_response(),
+            );
+        }
+    };
+
+    let problems = &rejection.problems;
+    tracing::warn!(
+        "Rejected push ({}) with {} problem(s)",
+        rejection.reason,
+        problems.len()
+    );
+    let mut messages = vec![format!(
+        "riley_cms: push rejected ({}), {} problem(s):",
+        rejection.reason,
+        problems.len()
+    )];
+    messages.extend(problems.iter().map(|p| format!("  {}", p)));
+    let body = request.rejection(&rejection.reason, &messages);
+
+    Some(
+        (
+            StatusCode::OK,
+            [
+                (
+                    header::CONTENT_TYPE,
+                    "application/x-git-receive-pack-result",
+                ),
+                (header::CACHE_CONTROL, "no-cache"),
+            ],
+            body,
+        )
+            .into_response(),
+    )
+}
+
 /// Git Smart HTTP handler
 ///
 /// Handles all Git HTTP protocol requests by proxying to git-http-backend.
@@ -480,13 +1283,30 @@ pub async fn git_handler(
     let uri = request.uri().clone();
 
     // Check Basic Auth before consuming the body
-    if !check_git_basic_auth(&headers, &state) {
+    let Some(user) = authenticate_git(&headers, &state) else {
         return (
             StatusCode::UNAUTHORIZED,
             [(header::WWW_AUTHENTICATE, "Basic realm=\"Git\"")],
             "Authentication required",
         )
             .into_response();
+    };
+
+    // Determine if this is a write operation (push)
+    let is_write_operation = path.contains("git-receive-pack");
+    let wants_push = is_write_operation
+        || uri
+            .query()
+            .is_some_and(|q| q.split('&').any(|p| p == "service=git-receive-pack"));
+    if wants_push && !user.can_write {
+        tracing::warn!("Git credential {:?} is read-only, refusing push", user.name);
+        return (
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse {
+                error: "This git credential is read-only".to_string(),
+            }),
+        )
+            .into_response();
     }
 
     // Get the content repository path and git config
@@ -528,14 +1348,50 @@ pub async fn git_handler(
         .and_then(|h| h.to_str().ok())
         .and_then(|s| s.parse::<u64>().ok());
 
-    // Determine if this is a write operation (push)
-    let is_write_operation = path.contains("git-receive-pack");
+    let body = request.into_body();
 
-    // Convert request body to a stream for incremental processing
-    let body_stream = request
-        .into_body()
-        .into_data_stream()
-        .map(|result| result.map_err(std::io::Error::other));
+    // Pushes are buffered so they can be checked before any ref is updated and
+    // their ref updates audited; everything else is streamed to the CGI process.
+    let mut updates: Vec<RefUpdate> = Vec::new();
+    let (body_stream, content_length): (BodyStream, Option<u64>) =
+        if is_write_operation && method == axum::http::Method::POST {
+            let bytes = match buffer_push_body(&headers, body, max_body_size).await {
+                Ok(bytes) => bytes,
+                Err(response) => return response,
+            };
+            let request = match ReceivePack::parse(&bytes) {
+                Ok(request) => request,
+                Err(e) => {
+                    tracing::warn!("Rejected malformed push: {}", e);
+                    return (
+                        StatusCode::BAD_REQUEST,
+                        Json(ErrorResponse {
+                            error: e.to_string(),
+                        }),
+                    )
+                        .into_response();
+                }
+            };
+            if let Some(response) =
+                check_push(&state, &backend, &request, &bytes, user.override_protection).await
+            {
+                return response;
+            }
+            updates = request.updates;
+            let len = bytes.len() as u64;
+            (
+                Box::pin(futures_util::stream::once(async move { Ok(bytes) })),
+                Some(len),
+            )
+        } else {
+            (
+                Box::pin(
+                    body.into_data_stream()
+                        .map(|result| result.map_err(std::io::Error::other)),
+                ),
+                content_length,
+            )
+        };
 
     // Run the Git CGI backend with streaming
     match backend
@@ -583,44 +1439,23 @@ pub async fn git_handler(
             };
 
             // Always spawn a task to reap the child process (prevents zombies).
-            // Only trigger refresh/webhooks for successful write operations.
+            // Only request a refresh for successful write operations.
             let state_clone = state.clone();
             tokio::spawn(async move {
                 match cgi_response.completion.wait(cgi_timeout).await {
                     Ok(exit_status) => {
                         if is_write_operation && exit_status.success() {
-                            // Update working tree to match pushed content.
-                            // git-http-backend updates the git database but not the
-                            // working tree, so we must checkout explicitly before
-                            // refreshing the content cache.
-                            let repo_path = &state_clone.config.content.repo_path;
-                            match tokio::process::Command::new("git")
-                                .args([
-                                    "-C",
-                                    &repo_path.to_string_lossy(),
-                                    "checkout",
-                                    "-f",
-                                    "HEAD",
-                                ])
-                                .output()
-                                .await
+                            if !updates.is_empty()
+                                && let Err(e) = state_clone
+                                    .riley_cms
+                                    .record_push(&user.name, &updates)
+                                    .await
                             {
-                                Ok(output) if !output.status.success() => {
-                                    tracing::error!(
-                                        "git checkout failed after push: {}",
-                                        String::from_utf8_lossy(&output.stderr)
-                                    );
-                                }
-                                Err(e) => {
-                                    tracing::error!("Failed to run git checkout after push: {}", e);
-                                }
-                                _ => {}
+                                tracing::error!("Failed to record push in audit log: {}", e);
                             }
 
-                            if let Err(e) = state_clone.riley_cms.refresh().await {
-                                tracing::error!("Failed to refresh content after git push: {}", e);
-                            }
-                            state_clone.riley_cms.fire_webhooks().await;
+                            // The refresh worker coalesces bursts of pushes into one reload
+                            state_clone.riley_cms.request_refresh();
                         }
                     }
                     Err(e) => {
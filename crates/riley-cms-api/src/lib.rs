@@ -8,9 +8,9 @@ use axum::{
     extract::DefaultBodyLimit,
     http::{HeaderValue, Method, header},
     middleware::from_fn_with_state,
-    routing::{any, get},
+    routing::{any, get, post},
 };
-use middleware::auth_middleware;
+use middleware::{auth_middleware, preview_middleware, revision_middleware};
 use riley_cms_core::{RileyCms, RileyCmsConfig};
 use std::net::IpAddr;
 use std::net::SocketAddr;
@@ -39,6 +39,8 @@ struct RileyCmsKeyExtractor {
 impl KeyExtractor for RileyCmsKeyExtractor {
     type Key = IpAddr;
 
+    // The error type is dictated by the `KeyExtractor` trait.
+    #[allow(clippy::result_large_err)]
     fn extract<T>(&self, req: &axum::http::Request<T>) -> Result<Self::Key, GovernorError> {
         if self.behind_proxy {
             // Try proxy headers first, fall back to peer IP for internal
@@ -64,21 +66,58 @@ fn api_v1_routes() -> Router<Arc<AppState>> {
         .route("/posts", get(handlers::list_posts))
         .route("/posts/{slug}", get(handlers::get_post))
         .route("/posts/{slug}/raw", get(handlers::get_post_raw))
+        .route("/posts/{slug}/html", get(handlers::get_post_html))
+        .route("/posts/{slug}/history", get(handlers::get_post_history))
         .route("/series", get(handlers::list_series))
         .route("/series/{slug}", get(handlers::get_series))
+        .route(
+            "/series/{series}/posts/{slug}",
+            get(handlers::get_series_post),
+        )
+        .route("/tags", get(handlers::list_tags))
+        .route("/tags/{tag}", get(handlers::get_tag))
+        .route("/search", get(handlers::search))
+        .route("/feed.xml", get(handlers::rss_feed))
+        .route("/atom.xml", get(handlers::atom_feed))
+        .route("/feed.json", get(handlers::json_feed))
+        .route("/sitemap.xml", get(handlers::sitemap))
         .route("/assets", get(handlers::list_assets))
+        .route("/admin/pushes", get(handlers::list_pushes))
+        .route("/admin/refresh", get(handlers::refresh_status))
+        .route(
+            "/admin/webhooks/deliveries",
+            get(handlers::list_webhook_deliveries),
+        )
+        .route(
+            "/admin/webhooks/deliveries/{id}/redeliver",
+            post(handlers::redeliver_webhook),
+        )
 }
 
-/// Build the Axum router with all routes.
+/// Build the Axum router with all routes, including `/health`.
 ///
 /// Note: Rate limiting is applied separately in `serve()` because it requires
 /// real TCP connection info (peer IP) which isn't available in `oneshot` tests.
 pub fn build_router(state: Arc<AppState>) -> Router {
+    Router::new()
+        .route("/health", get(handlers::health))
+        .merge(build_app_router(state))
+}
+
+/// Build the router for everything except `/health`.
+///
+/// `serve()` wraps this in the rate limiter and mounts `/health` outside it.
+fn build_app_router(state: Arc<AppState>) -> Router {
     let cors = build_cors_layer(&state.config);
 
     Router::new()
         // Versioned API routes
-        .nest("/api/v1", api_v1_routes())
+        .nest(
+            "/api/v1",
+            api_v1_routes()
+                .layer(from_fn_with_state(state.clone(), revision_middleware))
+                .layer(from_fn_with_state(state.clone(), preview_middleware)),
+        )
         // Git Smart HTTP routes (uses Basic Auth, not Bearer token)
         .route("/git/{*path}", any(handlers::git_handler))
         // Auth middleware - runs on all routes, sets AuthStatus in extensions
@@ -86,7 +125,7 @@ pub fn build_router(state: Arc<AppState>) -> Router {
         // State and other middleware
         .with_state(state)
         // Disable Axum's default 2MB body limit. The git handler enforces its own
-        // streaming limit (default 100MB), and all other routes are GET-only.
+        // streaming limit (default 100MB), and no other route reads a body.
         .layer(DefaultBodyLimit::disable())
         .layer(cors)
         .layer(SetResponseHeaderLayer::overriding(
@@ -127,8 +166,13 @@ fn build_cors_layer(config: &RileyCmsConfig) -> CorsLayer {
             let origins: Vec<_> = origins.iter().filter_map(|o| o.parse().ok()).collect();
             CorsLayer::new()
                 .allow_origin(origins)
-                .allow_methods([Method::GET, Method::OPTIONS])
-                .allow_headers([header::AUTHORIZATION, header::CONTENT_TYPE])
+                .allow_methods([Method::GET, Method::POST, Method::OPTIONS])
+                .allow_headers([
+                    header::AUTHORIZATION,
+                    header::CONTENT_TYPE,
+                    middleware::CONTENT_REF,
+                ])
+                .expose_headers([middleware::CONTENT_REVISION])
         }
         // Default: deny all cross-origin requests (secure by default)
         None => CorsLayer::new(),
@@ -140,6 +184,7 @@ fn build_cors_layer(config: &RileyCmsConfig) -> CorsLayer {
 /// The server will drain in-flight connections when receiving SIGINT (Ctrl+C)
 /// or SIGTERM (Docker stop / Kubernetes terminate).
 pub async fn serve(riley_cms: RileyCms) -> anyhow::Result<()> {
+    riley_cms.start_background_tasks();
     let config = riley_cms.config().clone();
     let server_config = config.server.clone().unwrap_or_default();
 
@@ -172,7 +217,7 @@ pub async fn serve(riley_cms: RileyCms) -> anyhow::Result<()> {
     // work without proxy headers when behind_proxy is true.
     let app = Router::new()
         .route("/health", get(handlers::health))
-        .merge(build_router(state).layer(governor_layer));
+        .merge(build_app_router(state).layer(governor_layer));
 
     let addr: SocketAddr = format!("{}:{}", server_config.host, server_config.port).parse()?;
 
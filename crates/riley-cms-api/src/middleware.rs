@@ -1,18 +1,25 @@
 //! Middleware for riley-cms-api
 //!
-//! Authentication middleware for protected endpoints.
+//! Authentication middleware for protected endpoints, content revision headers,
+//! and branch preview selection.
 
 use axum::{
-    extract::{Request, State},
-    http::header,
+    Json,
+    extract::{FromRequestParts, Query, Request, State},
+    http::{HeaderName, HeaderValue, StatusCode, header, request::Parts},
     middleware::Next,
-    response::Response,
+    response::{IntoResponse, Response},
 };
+use riley_cms_core::{Error, RileyCms};
+use serde::Deserialize;
 use sha2::{Digest, Sha256};
+use std::convert::Infallible;
+use std::ops::Deref;
 use std::sync::Arc;
 use subtle::ConstantTimeEq;
 
 use crate::AppState;
+use crate::handlers::ErrorResponse;
 
 /// Authentication status for the current request.
 ///
@@ -79,6 +86,139 @@ pub async fn auth_middleware(
     next.run(request).await
 }
 
+/// Header reporting the commit the served content was read from
+pub const CONTENT_REVISION: HeaderName = HeaderName::from_static("x-content-revision");
+
+/// Add `X-Content-Revision` to responses when content is served from a commit
+/// (`content.source = "git"` or `"deploy"`, or a branch preview).
+pub async fn revision_middleware(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let revision = match request.extensions().get::<Preview>() {
+        Some(preview) => preview.riley_cms.content_revision().await,
+        None => state.riley_cms.content_revision().await,
+    };
+    let mut response = next.run(request).await;
+    if let Some(value) = revision.and_then(|r| HeaderValue::from_str(&r).ok()) {
+        response.headers_mut().insert(CONTENT_REVISION, value);
+    }
+    response
+}
+
+/// Header selecting a branch to preview, like the `ref` query parameter
+pub const CONTENT_REF: HeaderName = HeaderName::from_static("x-riley-cms-ref");
+
+/// A branch preview selected for the current request by [`preview_middleware`]
+#[derive(Clone)]
+pub struct Preview {
+    pub branch: String,
+    pub riley_cms: Arc<RileyCms>,
+}
+
+#[derive(Deserialize)]
+struct RefQuery {
+    #[serde(rename = "ref")]
+    git_ref: Option<String>,
+}
+
+/// Select a branch preview with `?ref=<branch>` or `X-Riley-Cms-Ref: <branch>`.
+///
+/// Previews are admin only and limited to `content.preview.branches`. The
+/// selected [`Preview`] is inserted into request extensions, and its responses
+/// are never stored by shared caches.
+pub async fn preview_middleware(
+    State(state): State<Arc<AppState>>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let from_query = Query::<RefQuery>::try_from_uri(request.uri())
+        .ok()
+        .and_then(|Query(q)| q.git_ref);
+    let from_header = request
+        .headers()
+        .get(CONTENT_REF)
+        .and_then(|v| v.to_str().ok())
+        .map(String::from);
+    let Some(branch) = from_query.or(from_header).filter(|b| !b.is_empty()) else {
+        return next.run(request).await;
+    };
+
+    let error = |status: StatusCode, message: String| {
+        (status, Json(ErrorResponse { error: message })).into_response()
+    };
+    if request.extensions().get::<AuthStatus>() != Some(&AuthStatus::Admin) {
+        return error(
+            StatusCode::UNAUTHORIZED,
+            "Authentication required for branch previews".to_string(),
+        );
+    }
+    let riley_cms = match state.riley_cms.preview(&branch).await {
+        Ok(Some(riley_cms)) => riley_cms,
+        Ok(None) => {
+            return error(
+                StatusCode::NOT_FOUND,
+                format!("Branch not found: {}", branch),
+            );
+        }
+        Err(e @ Error::PreviewNotAllowed(_)) => {
+            return error(StatusCode::BAD_REQUEST, e.to_string());
+        }
+        Err(e) => {
+            tracing::error!("Failed to load preview of {}: {}", branch, e);
+            return error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Internal server error".to_string(),
+            );
+        }
+    };
+
+    request
+        .extensions_mut()
+        .insert(Preview { branch, riley_cms });
+    let mut response = next.run(request).await;
+    let headers = response.headers_mut();
+    headers.insert(
+        header::CACHE_CONTROL,
+        HeaderValue::from_static("private, no-store"),
+    );
+    headers.remove(header::ETAG);
+    headers.remove(header::LAST_MODIFIED);
+    response
+}
+
+/// The content a request reads: its branch preview, or the live content
+pub enum SelectedContent {
+    Live(Arc<AppState>),
+    Preview(Arc<RileyCms>),
+}
+
+impl Deref for SelectedContent {
+    type Target = RileyCms;
+
+    fn deref(&self) -> &RileyCms {
+        match self {
+            Self::Live(state) => &state.riley_cms,
+            Self::Preview(riley_cms) => riley_cms,
+        }
+    }
+}
+
+impl FromRequestParts<Arc<AppState>> for SelectedContent {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &Arc<AppState>,
+    ) -> Result<Self, Self::Rejection> {
+        Ok(match parts.extensions.get::<Preview>() {
+            Some(preview) => Self::Preview(preview.riley_cms.clone()),
+            None => Self::Live(state.clone()),
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
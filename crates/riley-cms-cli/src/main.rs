@@ -2,7 +2,7 @@
 
 use anyhow::Result;
 use clap::{Parser, Subcommand};
-use riley_cms_core::{RileyCms, resolve_config};
+use riley_cms_core::{DeliveryStatus, RileyCms, resolve_config};
 use std::path::PathBuf;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
@@ -31,7 +31,7 @@ enum Commands {
         path: PathBuf,
     },
 
-    /// Upload an asset to R2
+    /// Upload an asset to storage
     Upload {
         /// File to upload
         file: PathBuf,
@@ -49,6 +49,31 @@ enum Commands {
 
     /// Validate content structure and configs
     Validate,
+
+    /// Print sitemap.xml for all live posts and series (requires [sitemap] config)
+    Sitemap {
+        /// Write to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Show accepted git pushes from the audit log, newest first
+    Pushes {
+        /// Maximum number of pushes to show
+        #[arg(short = 'n', long, default_value_t = 20)]
+        limit: usize,
+    },
+
+    /// Show webhook deliveries from the delivery log, newest first
+    Webhooks {
+        /// Maximum number of deliveries to show
+        #[arg(short = 'n', long, default_value_t = 20)]
+        limit: usize,
+
+        /// Queue the delivery with this id again instead
+        #[arg(long, value_name = "ID")]
+        redeliver: Option<String>,
+    },
 }
 
 #[derive(Subcommand)]
@@ -67,7 +92,7 @@ enum LsCommands {
         drafts: bool,
     },
 
-    /// List assets in bucket
+    /// List assets in storage
     Assets,
 }
 
@@ -92,6 +117,11 @@ async fn main() -> Result<()> {
         }
         Commands::Ls { what } => cmd_ls(cli.config.as_deref(), what).await,
         Commands::Validate => cmd_validate(cli.config.as_deref()).await,
+        Commands::Sitemap { output } => cmd_sitemap(cli.config.as_deref(), output.as_deref()).await,
+        Commands::Pushes { limit } => cmd_pushes(cli.config.as_deref(), limit).await,
+        Commands::Webhooks { limit, redeliver } => {
+            cmd_webhooks(cli.config.as_deref(), limit, redeliver.as_deref()).await
+        }
     }
 }
 
@@ -154,6 +184,9 @@ content_dir = "content"
 bucket = "your-bucket-name"
 endpoint = "https://your-account.r2.cloudflarestorage.com"
 public_url_base = "https://assets.yourdomain.com"
+# To store assets in a local directory instead of S3/R2:
+# backend = "local"
+# path = "assets"
 
 [server]
 host = "0.0.0.0"
@@ -171,7 +204,7 @@ cors_origins = ["*"]
     println!("  - Created riley_cms.toml (edit with your settings)");
     println!();
     println!("Next steps:");
-    println!("  1. Edit riley_cms.toml with your S3/R2 credentials");
+    println!("  1. Edit riley_cms.toml with your storage settings");
     println!("  2. Run `riley_cms serve` to start the API");
 
     Ok(())
@@ -203,6 +236,7 @@ async fn cmd_ls(config_path: Option<&std::path::Path>, what: LsCommands) -> Resu
                 include_scheduled: drafts,
                 limit: None,
                 offset: None,
+                ..Default::default()
             };
             let result = riley_cms.list_posts(&opts).await?;
 
@@ -226,6 +260,7 @@ async fn cmd_ls(config_path: Option<&std::path::Path>, what: LsCommands) -> Resu
                 include_scheduled: drafts,
                 limit: None,
                 offset: None,
+                ..Default::default()
             };
             let result = riley_cms.list_series(&opts).await?;
 
@@ -281,6 +316,126 @@ async fn cmd_ls(config_path: Option<&std::path::Path>, what: LsCommands) -> Resu
     Ok(())
 }
 
+async fn cmd_sitemap(
+    config_path: Option<&std::path::Path>,
+    output: Option<&std::path::Path>,
+) -> Result<()> {
+    let config = resolve_config(config_path)?;
+    let riley_cms = RileyCms::from_config(config).await?;
+
+    let sitemap = riley_cms.sitemap().await?;
+    match output {
+        Some(path) => {
+            std::fs::write(path, &sitemap.body)?;
+            eprintln!("Wrote {}", path.display());
+        }
+        None => print!("{}", sitemap.body),
+    }
+
+    Ok(())
+}
+
+async fn cmd_pushes(config_path: Option<&std::path::Path>, limit: usize) -> Result<()> {
+    let config = resolve_config(config_path)?;
+    let riley_cms = RileyCms::from_config(config).await?;
+
+    let pushes = riley_cms.list_pushes(limit).await?;
+    if pushes.is_empty() {
+        println!("No pushes recorded.");
+        return Ok(());
+    }
+
+    for push in pushes {
+        let old = push.old_sha.as_deref().map_or("(new)", short_sha);
+        let new = push.new_sha.as_deref().map_or("(deleted)", short_sha);
+        println!(
+            "{}  {}  {} {}..{}",
+            push.pushed_at.format("%Y-%m-%d %H:%M:%S"),
+            push.user,
+            push.refname,
+            old,
+            new
+        );
+        if !push.changed_posts.is_empty() {
+            println!("    {}", push.changed_posts.join(", "));
+        }
+    }
+
+    Ok(())
+}
+
+async fn cmd_webhooks(
+    config_path: Option<&std::path::Path>,
+    limit: usize,
+    redeliver: Option<&str>,
+) -> Result<()> {
+    let config = resolve_config(config_path)?;
+    let riley_cms = RileyCms::from_config(config).await?;
+
+    if let Some(id) = redeliver {
+        let Some(delivery) = riley_cms.redeliver_webhook(id).await? else {
+            anyhow::bail!("No webhook delivery with id {}", id);
+        };
+        println!(
+            "Queued {} to {} (redelivery of {})",
+            delivery.id, delivery.url, id
+        );
+        // Sending is left to the server's delivery task
+        println!("The server will send it.");
+        return Ok(());
+    }
+
+    let deliveries = riley_cms.list_webhook_deliveries(limit).await?;
+    if deliveries.is_empty() {
+        println!("No webhook deliveries recorded.");
+        return Ok(());
+    }
+
+    for delivery in deliveries {
+        let status = match delivery.status {
+            DeliveryStatus::Pending => "pending",
+            DeliveryStatus::Delivered => "delivered",
+            DeliveryStatus::Failed => "failed",
+        };
+        println!(
+            "{}  {}  {:<9}  {}",
+            delivery.created_at.format("%Y-%m-%d %H:%M:%S"),
+            delivery.id,
+            status,
+            delivery.url
+        );
+        for attempt in &delivery.attempts {
+            let code = attempt
+                .status_code
+                .map_or_else(|| "---".to_string(), |c| c.to_string());
+            let error = attempt
+                .error
+                .as_deref()
+                .map(|e| format!("  {}", e))
+                .unwrap_or_default();
+            println!(
+                "    {}  {}  {}ms{}",
+                attempt.at.format("%Y-%m-%d %H:%M:%S"),
+                code,
+                attempt.duration_ms,
+                error
+            );
+        }
+        if let Some(next) = delivery.next_attempt_at {
+            println!("    next attempt {}", next.format("%Y-%m-%d %H:%M:%S"));
+        }
+        if let Some(original) = &delivery.redelivery_of {
+            println!("    redelivery of {}", original);
+        }
+    }
+
+    Ok(())
+}
+
+fn short_sha(sha: &str) -> &str {
+    sha.get(..7).unwrap_or(sha)
+}
+
 async fn cmd_validate(config_path: Option<&std::path::Path>) -> Result<()> {
     let config = resolve_config(config_path)?;
     let riley_cms = RileyCms::from_config(config).await?;
@@ -0,0 +1,105 @@
+//! Push audit log: one JSON [`PushRecord`] per line, appended after each accepted push
+
+use crate::config::RileyCmsConfig;
+use crate::error::Result;
+use crate::git;
+use crate::types::PushRecord;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// File name of the audit log in the repository's git directory
+const DEFAULT_FILE_NAME: &str = "riley-cms-pushes.jsonl";
+
+/// Where the audit log lives: `git.audit_log`, or the repository's git directory
+pub(crate) fn log_path(config: &RileyCmsConfig) -> PathBuf {
+    if let Some(path) = config.git.as_ref().and_then(|g| g.audit_log.clone()) {
+        return path;
+    }
+    git::git_dir(&config.content.repo_path).join(DEFAULT_FILE_NAME)
+}
+
+/// Append records to the log, creating it if needed
+pub(crate) fn append(path: &Path, records: &[PushRecord]) -> Result<()> {
+    if records.is_empty() {
+        return Ok(());
+    }
+    let mut lines = String::new();
+    for record in records {
+        lines.push_str(&serde_json::to_string(record).map_err(std::io::Error::other)?);
+        lines.push('\n');
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    // One write per push, so concurrent appends don't interleave lines
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(lines.as_bytes())?;
+    Ok(())
+}
+
+/// Up to `limit` records, newest first. A missing log is empty; unreadable lines are skipped.
+pub(crate) fn read(path: &Path, limit: usize) -> Result<Vec<PushRecord>> {
+    let data = match fs::read_to_string(path) {
+        Ok(data) => data,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    Ok(data
+        .lines()
+        .rev()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| match serde_json::from_str(line) {
+            Ok(record) => Some(record),
+            Err(e) => {
+                tracing::warn!("Skipping unreadable push audit entry: {}", e);
+                None
+            }
+        })
+        .take(limit)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use tempfile::TempDir;
+
+    fn record(refname: &str) -> PushRecord {
+        PushRecord {
+            pushed_at: Utc::now(),
+            user: "riley".to_string(),
+            refname: refname.to_string(),
+            old_sha: None,
+            new_sha: Some("abc".to_string()),
+            changed_posts: vec!["hello".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_append_and_read_newest_first() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("logs/pushes.jsonl");
+        assert!(read(&path, 10).unwrap().is_empty());
+
+        append(&path, &[record("refs/heads/a"), record("refs/heads/b")]).unwrap();
+        fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap()
+            .write_all(b"not json\n")
+            .unwrap();
+        append(&path, &[record("refs/heads/c")]).unwrap();
+
+        let records = read(&path, 10).unwrap();
+        let refs: Vec<&str> = records.iter().map(|r| r.refname.as_str()).collect();
+        assert_eq!(refs, vec!["refs/heads/c", "refs/heads/b", "refs/heads/a"]);
+        assert_eq!(read(&path, 1).unwrap().len(), 1);
+        assert_eq!(records[0], {
+            let mut expected = record("refs/heads/c");
+            expected.pushed_at = records[0].pushed_at;
+            expected
+        });
+    }
+}
@@ -2,6 +2,7 @@
 
 use crate::error::{Error, Result};
 use serde::Deserialize;
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
 /// Full configuration for riley_cms
@@ -13,6 +14,8 @@ pub struct RileyCmsConfig {
     pub git: Option<GitConfig>,
     pub webhooks: Option<WebhooksConfig>,
     pub auth: Option<AuthConfig>,
+    pub feed: Option<FeedConfig>,
+    pub sitemap: Option<SitemapConfig>,
 }
 
 /// Git configuration
@@ -26,6 +29,53 @@ pub struct GitConfig {
     /// Timeout for git-http-backend CGI process in seconds. Default: 300 (5 minutes).
     #[serde(default = "default_git_cgi_timeout_secs")]
     pub cgi_timeout_secs: u64,
+    /// Validate pushed content and reject pushes that fail `validate()`. Default: true.
+    #[serde(default = "default_validate_pushes")]
+    pub validate_pushes: bool,
+    /// Rules on what a push may change (`[git.push_policy]`). Default: none.
+    pub push_policy: Option<PushPolicyConfig>,
+    /// Rules on which refs a push may update, and how (`[git.branch_protection]`). Default: none.
+    pub branch_protection: Option<BranchProtectionConfig>,
+    /// Where accepted pushes are recorded, as JSON lines.
+    /// Default: `riley-cms-pushes.jsonl` in the repository's git directory.
+    pub audit_log: Option<PathBuf>,
+}
+
+/// Protection of deployed history (`[git.branch_protection]`)
+#[derive(Debug, Clone, Deserialize)]
+pub struct BranchProtectionConfig {
+    /// Refs a push may create, update or delete, as exact names or `*` globs
+    /// (e.g. `["refs/heads/main", "refs/heads/drafts/*"]`). Default: any ref.
+    #[serde(default)]
+    pub pushable_refs: Vec<String>,
+    /// Reject updates that aren't fast-forwards (force pushes). Default: true.
+    #[serde(default = "default_reject_non_fast_forward")]
+    pub reject_non_fast_forward: bool,
+    /// Reject ref deletions. Default: true.
+    #[serde(default = "default_reject_deletions")]
+    pub reject_deletions: bool,
+    /// Used as the git password instead of `git_token`, bypasses these rules
+    /// for deliberate history rewrites. Default: no override.
+    pub override_token: Option<ConfigValue>,
+}
+
+/// Rules enforced on every push (`[git.push_policy]`)
+///
+/// Checked against the files each pushed commit changes, before any ref is updated.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PushPolicyConfig {
+    /// Path prefixes under which files may be added, modified or deleted
+    /// (e.g. `["content/"]`). Default: any path.
+    #[serde(default)]
+    pub allowed_paths: Vec<String>,
+    /// Maximum size in bytes of any pushed file. Default: `content.max_content_file_size`.
+    pub max_file_size: Option<u64>,
+    /// File extensions that may not be pushed (e.g. `["exe", "zip"]`), case-insensitive
+    #[serde(default)]
+    pub banned_extensions: Vec<String>,
+    /// Reject pushes that add or modify symlinks. Default: true.
+    #[serde(default = "default_reject_symlinks")]
+    pub reject_symlinks: bool,
 }
 
 fn default_git_max_body_size() -> u64 {
@@ -36,6 +86,22 @@ fn default_git_cgi_timeout_secs() -> u64 {
     300 // 5 minutes
 }
 
+fn default_validate_pushes() -> bool {
+    true
+}
+
+fn default_reject_symlinks() -> bool {
+    true
+}
+
+fn default_reject_non_fast_forward() -> bool {
+    true
+}
+
+fn default_reject_deletions() -> bool {
+    true
+}
+
 /// Content repository configuration
 #[derive(Debug, Clone, Deserialize)]
 pub struct ContentConfig {
@@ -51,12 +117,175 @@ pub struct ContentConfig {
     /// Default: 100MB.
     #[serde(default = "default_max_total_content_size")]
     pub max_total_content_size: u64,
+    /// Server-side HTML rendering of post content
+    #[serde(default)]
+    pub render: RenderConfig,
+    /// How series posts are addressed. Default: global.
+    #[serde(default)]
+    pub post_addressing: PostAddressing,
+    /// When a refresh may replace the live content cache
+    #[serde(default)]
+    pub refresh: RefreshConfig,
+    /// Where content is read from. Default: worktree.
+    #[serde(default)]
+    pub source: ContentSource,
+    /// Ref whose commit is served when `source = "git"` or `"deploy"`. Default: HEAD.
+    #[serde(default = "default_git_ref")]
+    pub git_ref: String,
+    /// Per-commit deploy directories (`[content.deploy]`), used when `source = "deploy"`
+    #[serde(default)]
+    pub deploy: DeployConfig,
+    /// Branch previews for admin requests (`[content.preview]`)
+    #[serde(default)]
+    pub preview: PreviewConfig,
+}
+
+/// Branch preview configuration (`[content.preview]`)
+#[derive(Debug, Clone, Deserialize)]
+pub struct PreviewConfig {
+    /// Branch name patterns that may be previewed, `*` matching any characters
+    /// (e.g. `"draft/*"`). Default: none, which disables previews.
+    #[serde(default)]
+    pub branches: Vec<String>,
+    /// Maximum number of branch caches held at once; the least recently used
+    /// one is evicted to make room. Default: 4.
+    #[serde(default = "default_preview_max_caches")]
+    pub max_caches: usize,
+    /// Branch caches unused for this many seconds are evicted. Default: 600.
+    #[serde(default = "default_preview_idle_secs")]
+    pub idle_secs: u64,
+}
+
+impl Default for PreviewConfig {
+    fn default() -> Self {
+        Self {
+            branches: Vec::new(),
+            max_caches: default_preview_max_caches(),
+            idle_secs: default_preview_idle_secs(),
+        }
+    }
+}
+
+/// Deploy directory configuration (`[content.deploy]`)
+#[derive(Debug, Clone, Deserialize)]
+pub struct DeployConfig {
+    /// Where commits are checked out, one directory per commit.
+    /// Default: `riley-cms-deploys` in the repository's git directory.
+    pub dir: Option<PathBuf>,
+    /// How many deploy directories to keep, including the live one. Default: 3.
+    #[serde(default = "default_deploy_keep")]
+    pub keep: usize,
+}
+
+impl Default for DeployConfig {
+    fn default() -> Self {
+        Self {
+            dir: None,
+            keep: default_deploy_keep(),
+        }
+    }
+}
+
+/// Where content is read from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ContentSource {
+    /// Files under `repo_path`, checked out after each push
+    #[default]
+    Worktree,
+    /// Trees and blobs of `git_ref`'s commit, read from the object database.
+    /// `repo_path` may be a bare repository.
+    Git,
+    /// `git_ref`'s commit, checked out into a fresh directory of its own that
+    /// is never modified afterwards. `repo_path` may be a bare repository.
+    Deploy,
+}
+
+/// Refresh safety configuration (`[content.refresh]`)
+#[derive(Debug, Clone, Deserialize)]
+pub struct RefreshConfig {
+    /// What to do when a reload looks like a regression. Default: keep-previous-on-error.
+    #[serde(default)]
+    pub policy: RefreshPolicy,
+    /// A reload that loses more than this percentage of the live posts counts
+    /// as a regression. Default: 50. Set to 100 to disable the check.
+    #[serde(default = "default_max_post_drop_percent")]
+    pub max_post_drop_percent: u8,
+    /// How long the refresh worker waits for a burst of pushes to end before
+    /// reloading, in milliseconds. Default: 500.
+    #[serde(default = "default_refresh_debounce_ms")]
+    pub debounce_ms: u64,
+}
+
+impl Default for RefreshConfig {
+    fn default() -> Self {
+        Self {
+            policy: RefreshPolicy::default(),
+            max_post_drop_percent: default_max_post_drop_percent(),
+            debounce_ms: default_refresh_debounce_ms(),
+        }
+    }
+}
+
+/// What `refresh()` does when newly loaded content has load errors or
+/// unexpectedly lost posts
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RefreshPolicy {
+    /// Always swap in the new content, whatever went wrong
+    Replace,
+    /// Keep serving the previous content and log why
+    #[default]
+    KeepPreviousOnError,
+    /// Keep serving the previous content and return an error from `refresh()`
+    Strict,
+}
+
+/// How series posts are addressed by slug
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PostAddressing {
+    /// Every post is reachable at `/posts/{slug}`, so slugs must be unique
+    /// across standalone and series posts. Collisions are reported by
+    /// `validate()`; standalone posts win, then series in slug order.
+    #[default]
+    Global,
+    /// Series posts are only reachable at `/series/{series}/posts/{slug}`, so
+    /// their slugs only need to be unique within the series.
+    Namespaced,
+}
+
+/// HTML rendering configuration (`[content.render]`)
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RenderConfig {
+    /// What to do with JSX component tags in rendered HTML. Default: strip.
+    #[serde(default)]
+    pub jsx: JsxMode,
+}
+
+/// Handling of JSX component tags when rendering MDX to HTML
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JsxMode {
+    /// Remove component tags, keeping their children
+    #[default]
+    Strip,
+    /// Keep components as `<div data-component="Name">` wrappers with their string props
+    Passthrough,
 }
 
 fn default_content_dir() -> String {
     "content".to_string()
 }
 
+fn default_git_ref() -> String {
+    "HEAD".to_string()
+}
+
+fn default_deploy_keep() -> usize {
+    3
+}
+
 fn default_max_content_file_size() -> u64 {
     5 * 1024 * 1024 // 5 MB
 }
@@ -65,19 +294,39 @@ fn default_max_total_content_size() -> u64 {
     100 * 1024 * 1024 // 100 MB
 }
 
+fn default_max_post_drop_percent() -> u8 {
+    50
+}
+
+fn default_refresh_debounce_ms() -> u64 {
+    500
+}
+
+fn default_preview_max_caches() -> usize {
+    4
+}
+
+fn default_preview_idle_secs() -> u64 {
+    600
+}
+
 /// Storage backend configuration
 #[derive(Debug, Clone, Deserialize)]
 pub struct StorageConfig {
+    /// Storage backend: `"s3"` (default) or `"local"`
     #[serde(default = "default_backend")]
     pub backend: String,
-    pub bucket: ConfigValue,
+    /// Bucket name. Required for the `s3` backend.
+    pub bucket: Option<ConfigValue>,
     #[serde(default = "default_region")]
     pub region: String,
     pub endpoint: Option<ConfigValue>,
+    /// Directory assets are written to. Required for the `local` backend.
+    pub path: Option<PathBuf>,
     pub public_url_base: ConfigValue,
 }
 
-/// Resolved storage config with all env vars expanded
+/// Resolved S3 storage config with all env vars expanded
 #[derive(Debug, Clone)]
 pub struct ResolvedStorageConfig {
     pub backend: String,
@@ -88,11 +337,17 @@ pub struct ResolvedStorageConfig {
 }
 
 impl StorageConfig {
-    /// Resolve all env: references in storage config
+    /// Resolve all env: references in an S3 storage config
     pub fn resolve(&self) -> Result<ResolvedStorageConfig> {
+        let bucket = self.bucket.as_ref().ok_or_else(|| {
+            Error::Config(format!(
+                "storage.bucket is required for the {} backend",
+                self.backend
+            ))
+        })?;
         Ok(ResolvedStorageConfig {
             backend: self.backend.clone(),
-            bucket: self.bucket.resolve()?,
+            bucket: bucket.resolve()?,
             region: self.region.clone(),
             endpoint: self.endpoint.as_ref().map(|e| e.resolve()).transpose()?,
             public_url_base: self.public_url_base.resolve()?,
@@ -176,20 +431,209 @@ impl Default for ServerConfig {
 /// Webhook configuration
 #[derive(Debug, Clone, Deserialize)]
 pub struct WebhooksConfig {
+    /// URLs notified of every content update, with the settings of this section.
+    /// Use `endpoints` for per-receiver settings.
     #[serde(default)]
     pub on_content_update: Vec<String>,
+    /// Receivers with their own secret, event filter, headers and retry policy
+    #[serde(default)]
+    pub endpoints: Vec<WebhookEndpointConfig>,
     /// HMAC-SHA256 secret for signing webhook payloads.
-    /// When set, each webhook request includes an `X-Riley-Cms-Signature` header
-    /// with the hex-encoded HMAC-SHA256 of the request body.
+    /// When set, each webhook request includes an `X-Riley-Cms-Timestamp` header
+    /// and an `X-Riley-Cms-Signature` header with the hex-encoded HMAC-SHA256 of
+    /// `<timestamp>.<body>`. Supports `"env:VAR_NAME"` syntax.
+    pub secret: Option<ConfigValue>,
+    /// Secret being rotated out. While set, requests carry a signature made
+    /// with each secret, so receivers can switch to `secret` at their own pace.
+    pub previous_secret: Option<ConfigValue>,
+    /// Allow webhook URLs that resolve to loopback, private or link-local addresses.
+    /// Off by default (SSRF protection). An escape hatch for tests and local
+    /// development, e.g. a receiver on localhost or the same Docker network; don't
+    /// enable it in production unless every configured URL is trusted.
+    #[serde(default)]
+    pub allow_private_ips: bool,
+    /// Delivery queue file. Defaults to `riley-cms-webhooks.jsonl` in the
+    /// repository's git directory.
+    pub queue_path: Option<PathBuf>,
+    /// Attempts per delivery before it is marked failed (default: 10)
+    #[serde(default = "default_webhook_max_attempts")]
+    pub max_attempts: u32,
+    /// Delay before the first retry, doubling for each retry after it (default: 30)
+    #[serde(default = "default_webhook_retry_base_secs")]
+    pub retry_base_secs: u64,
+    /// Finished deliveries kept in the delivery log (default: 500)
+    #[serde(default = "default_webhook_keep_deliveries")]
+    pub keep_deliveries: usize,
+}
+
+impl WebhooksConfig {
+    /// Every receiver: the `on_content_update` URLs followed by `endpoints`,
+    /// with the section's secret and retry policy filled in where unset.
+    ///
+    /// Deliveries refer to their receiver by URL, so a URL configured twice
+    /// is an error.
+    pub fn all_endpoints(&self) -> Result<Vec<WebhookEndpointConfig>> {
+        let endpoints: Vec<WebhookEndpointConfig> = self
+            .on_content_update
+            .iter()
+            .map(|url| WebhookEndpointConfig {
+                url: url.clone(),
+                secret: None,
+                previous_secret: None,
+                events: Vec::new(),
+                headers: BTreeMap::new(),
+                timeout_secs: default_webhook_timeout_secs(),
+                max_attempts: None,
+                retry_base_secs: None,
+            })
+            .chain(self.endpoints.iter().cloned())
+            .map(|endpoint| {
+                // An endpoint with its own secret doesn't inherit the section's
+                // secret being rotated out
+                let (secret, previous_secret) = match endpoint.secret {
+                    Some(secret) => (Some(secret), endpoint.previous_secret),
+                    None => (self.secret.clone(), self.previous_secret.clone()),
+                };
+                WebhookEndpointConfig {
+                    secret,
+                    previous_secret,
+                    max_attempts: endpoint.max_attempts.or(Some(self.max_attempts)),
+                    retry_base_secs: endpoint.retry_base_secs.or(Some(self.retry_base_secs)),
+                    ..endpoint
+                }
+            })
+            .collect();
+        for (i, endpoint) in endpoints.iter().enumerate() {
+            if endpoints[..i].iter().any(|e| e.url == endpoint.url) {
+                return Err(Error::Config(format!(
+                    "webhook URL {} is configured more than once",
+                    endpoint.url
+                )));
+            }
+        }
+        Ok(endpoints)
+    }
+}
+
+/// A webhook receiver (`[[webhooks.endpoints]]`)
+#[derive(Debug, Clone, Deserialize)]
+pub struct WebhookEndpointConfig {
+    pub url: String,
+    /// HMAC-SHA256 secret for this receiver. Defaults to `webhooks.secret`.
     /// Supports `"env:VAR_NAME"` syntax.
     pub secret: Option<ConfigValue>,
+    /// Secret being rotated out for this receiver. Defaults to
+    /// `webhooks.previous_secret` if `secret` is unset.
+    pub previous_secret: Option<ConfigValue>,
+    /// Event types to send, e.g. `"post.published"` or `"series.*"`. An update is
+    /// sent if any of its events matches; empty sends every update.
+    #[serde(default)]
+    pub events: Vec<String>,
+    /// Extra headers for every request, e.g. a deployment protection bypass token.
+    /// Values support `"env:VAR_NAME"` syntax.
+    #[serde(default)]
+    pub headers: BTreeMap<String, ConfigValue>,
+    /// Request timeout (default: 10)
+    #[serde(default = "default_webhook_timeout_secs")]
+    pub timeout_secs: u64,
+    /// Defaults to `webhooks.max_attempts`
+    pub max_attempts: Option<u32>,
+    /// Defaults to `webhooks.retry_base_secs`
+    pub retry_base_secs: Option<u64>,
+}
+
+fn default_webhook_timeout_secs() -> u64 {
+    10
+}
+
+fn default_webhook_max_attempts() -> u32 {
+    10
+}
+
+fn default_webhook_retry_base_secs() -> u64 {
+    30
+}
+
+fn default_webhook_keep_deliveries() -> usize {
+    500
+}
+
+/// RSS / Atom / JSON Feed configuration. Feeds are disabled unless this section is present.
+#[derive(Debug, Clone, Deserialize)]
+pub struct FeedConfig {
+    /// Site title, used as the feed title
+    pub title: String,
+    /// Public URL of the site (e.g. `https://example.com`), used as the feed link
+    pub base_url: String,
+    pub description: Option<String>,
+    /// Author name for the feed and its entries
+    pub author: Option<String>,
+    /// Maximum number of posts per feed. Default: 20.
+    #[serde(default = "default_feed_item_count")]
+    pub item_count: usize,
+    /// URL template for post links. `{base_url}`, `{slug}` and `{series_slug}` are
+    /// substituted. Default: `"{base_url}/{slug}"`.
+    #[serde(default = "default_feed_post_url")]
+    pub post_url: String,
+    /// URL template for posts that belong to a series. Default: same as `post_url`.
+    pub series_post_url: Option<String>,
+}
+
+fn default_feed_item_count() -> usize {
+    20
+}
+
+fn default_feed_post_url() -> String {
+    "{base_url}/{slug}".to_string()
+}
+
+/// sitemap.xml configuration. The sitemap is disabled unless this section is present.
+///
+/// URL templates substitute `{base_url}`, `{slug}` and `{series_slug}`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SitemapConfig {
+    /// Value substituted for `{base_url}` in the templates
+    #[serde(default)]
+    pub base_url: String,
+    /// URL template for standalone posts, e.g. `"https://example.com/{slug}"`
+    pub post_url: String,
+    /// URL template for posts in a series. Default: same as `post_url`.
+    pub series_post_url: Option<String>,
+    /// URL template for series pages (`{slug}` is the series slug).
+    /// Series are left out of the sitemap when unset.
+    pub series_url: Option<String>,
 }
 
 /// Authentication configuration
 #[derive(Debug, Clone, Deserialize)]
 pub struct AuthConfig {
+    /// Shared git password, accepted with any username. Pushes are audited as `git`.
     pub git_token: Option<ConfigValue>,
     pub api_token: Option<ConfigValue>,
+    /// Named git credentials (`[[auth.git_credentials]]`)
+    #[serde(default)]
+    pub git_credentials: Vec<GitCredentialConfig>,
+}
+
+/// A named git credential: the Basic Auth username must be `name` and the password `token`
+#[derive(Debug, Clone, Deserialize)]
+pub struct GitCredentialConfig {
+    pub name: String,
+    pub token: ConfigValue,
+    /// What the credential may do. Default: read-write.
+    #[serde(default)]
+    pub scope: GitScope,
+}
+
+/// Access granted by a git credential
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum GitScope {
+    /// Fetch and clone only
+    Read,
+    /// Fetch, clone and push
+    #[default]
+    ReadWrite,
 }
 
 /// A config value that can be a literal or env var reference
@@ -289,6 +733,17 @@ pub fn resolve_config(explicit_path: Option<&Path>) -> Result<RileyCmsConfig> {
     Err(Error::ConfigNotFound { searched })
 }
 
+#[cfg(test)]
+impl ContentConfig {
+    /// Content in `content/` under `repo_path`, with every other setting at its default
+    pub(crate) fn for_tests(repo_path: &Path) -> Self {
+        Self {
+            repo_path: repo_path.to_path_buf(),
+            ..toml::from_str("repo_path = ''").unwrap()
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -387,6 +842,118 @@ api_token = "env:API_TOKEN"
         assert!(auth.api_token.is_some());
     }
 
+    #[test]
+    fn test_parse_webhook_endpoints() {
+        let toml = r#"
+[content]
+repo_path = "/data/repo"
+
+[storage]
+bucket = "b"
+public_url_base = "https://assets.example.com"
+
+[webhooks]
+on_content_update = ["https://example.com/legacy"]
+secret = "shared"
+previous_secret = "old"
+max_attempts = 5
+
+[[webhooks.endpoints]]
+url = "https://example.com/posts"
+events = ["post.*"]
+headers = { "x-vercel-protection-bypass" = "env:BYPASS" }
+timeout_secs = 3
+retry_base_secs = 60
+
+[[webhooks.endpoints]]
+url = "https://example.com/own-secret"
+secret = "own"
+"#;
+        let config: RileyCmsConfig = toml::from_str(toml).unwrap();
+        let endpoints = config.webhooks.unwrap().all_endpoints().unwrap();
+        let urls: Vec<&str> = endpoints.iter().map(|e| e.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            [
+                "https://example.com/legacy",
+                "https://example.com/posts",
+                "https://example.com/own-secret"
+            ]
+        );
+
+        let legacy = &endpoints[0];
+        assert_eq!(legacy.secret.as_ref().unwrap().resolve().unwrap(), "shared");
+        assert!(legacy.events.is_empty() && legacy.headers.is_empty());
+        assert_eq!(legacy.timeout_secs, 10);
+        assert_eq!(legacy.max_attempts, Some(5));
+        assert_eq!(legacy.retry_base_secs, Some(30));
+
+        let posts = &endpoints[1];
+        assert_eq!(posts.events, ["post.*"]);
+        assert!(posts.headers.contains_key("x-vercel-protection-bypass"));
+        assert_eq!(posts.timeout_secs, 3);
+        assert_eq!(posts.max_attempts, Some(5));
+        assert_eq!(posts.retry_base_secs, Some(60));
+
+        let own = &endpoints[2];
+        assert_eq!(own.secret.as_ref().unwrap().resolve().unwrap(), "own");
+        assert!(own.previous_secret.is_none());
+        assert_eq!(
+            legacy.previous_secret.as_ref().unwrap().resolve().unwrap(),
+            "old"
+        );
+    }
+
+    #[test]
+    fn test_duplicate_webhook_urls_are_rejected() {
+        let config: WebhooksConfig = toml::from_str(
+            r#"
+on_content_update = ["https://example.com/hook"]
+
+[[endpoints]]
+url = "https://example.com/hook"
+events = ["post.*"]
+"#,
+        )
+        .unwrap();
+        let err = config.all_endpoints().unwrap_err();
+        assert!(err.to_string().contains("more than once"), "{}", err);
+    }
+
+    #[test]
+    fn test_parse_git_credentials() {
+        let toml = r#"
+[content]
+repo_path = "/data/repo"
+
+[storage]
+bucket = "b"
+public_url_base = "https://assets.example.com"
+
+[auth]
+[[auth.git_credentials]]
+name = "riley"
+token = "env:RILEY_GIT_TOKEN"
+
+[[auth.git_credentials]]
+name = "ci"
+token = "ci-token"
+scope = "read"
+"#;
+        let config: RileyCmsConfig = toml::from_str(toml).unwrap();
+        let auth = config.auth.unwrap();
+        assert!(auth.git_token.is_none());
+        let scopes: Vec<(&str, GitScope)> = auth
+            .git_credentials
+            .iter()
+            .map(|c| (c.name.as_str(), c.scope))
+            .collect();
+        assert_eq!(
+            scopes,
+            vec![("riley", GitScope::ReadWrite), ("ci", GitScope::Read)]
+        );
+    }
+
     #[test]
     fn test_server_config_defaults() {
         let server = ServerConfig::default();
@@ -427,6 +994,69 @@ public_url_base = "env:TEST_PUBLIC_URL"
         );
     }
 
+    #[test]
+    fn test_parse_local_storage_config() {
+        let toml = r#"
+[content]
+repo_path = "/data/repo"
+
+[storage]
+backend = "local"
+path = "/data/assets"
+public_url_base = "https://assets.example.com"
+"#;
+        let config: RileyCmsConfig = toml::from_str(toml).unwrap();
+        assert_eq!(config.storage.backend, "local");
+        assert_eq!(config.storage.path, Some(PathBuf::from("/data/assets")));
+        assert!(config.storage.bucket.is_none());
+        // S3 resolution requires a bucket
+        assert!(config.storage.resolve().is_err());
+    }
+
+    #[test]
+    fn test_parse_feed_config_defaults() {
+        let toml = r#"
+[content]
+repo_path = "/data/repo"
+
+[storage]
+bucket = "test"
+public_url_base = "https://assets.example.com"
+
+[feed]
+title = "My Blog"
+base_url = "https://example.com"
+"#;
+        let config: RileyCmsConfig = toml::from_str(toml).unwrap();
+        let feed = config.feed.unwrap();
+        assert_eq!(feed.title, "My Blog");
+        assert_eq!(feed.item_count, 20);
+        assert_eq!(feed.post_url, "{base_url}/{slug}");
+        assert!(feed.series_post_url.is_none());
+        assert!(feed.author.is_none());
+    }
+
+    #[test]
+    fn test_parse_sitemap_config() {
+        let toml = r#"
+[content]
+repo_path = "/data/repo"
+
+[storage]
+bucket = "test"
+public_url_base = "https://assets.example.com"
+
+[sitemap]
+post_url = "https://example.com/{slug}"
+series_post_url = "https://example.com/{series_slug}/{slug}"
+"#;
+        let config: RileyCmsConfig = toml::from_str(toml).unwrap();
+        let sitemap = config.sitemap.unwrap();
+        assert_eq!(sitemap.post_url, "https://example.com/{slug}");
+        assert!(sitemap.series_url.is_none());
+        assert!(sitemap.base_url.is_empty());
+    }
+
     #[test]
     fn test_load_config_from_file() {
         let temp_dir = TempDir::new().unwrap();
@@ -1,20 +1,35 @@
 //! Content parsing and caching for riley_cms
 
-use crate::config::ContentConfig;
+use crate::config::{ContentConfig, ContentSource, PostAddressing};
+use crate::deploy;
 use crate::error::{Error, Result};
+use crate::git::{self, DirectoryDates};
+use crate::render;
+use crate::search::{self, SearchIndex};
+use crate::source::{Checkout, EntryKind, GitTree, Source, Worktree};
 use crate::types::*;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
 use sha2::{Digest, Sha256};
 use std::collections::HashMap;
-use std::fs;
 use std::path::Path;
 
 /// In-memory cache of parsed content
 #[derive(Debug)]
 pub struct ContentCache {
+    /// Posts keyed by [`post_key`], so series posts never overwrite each other
     posts: HashMap<String, Post>,
     series: HashMap<String, SeriesData>,
+    /// Slug -> post key for posts reachable at `/posts/{slug}`
+    slugs: HashMap<String, String>,
+    /// Problems found while loading, also reported by `validate()`
+    diagnostics: Vec<LoadDiagnostic>,
     etag: String,
+    search: SearchIndex,
+    /// Commit the content was read from, if known
+    revision: Option<String>,
+    /// Distinct `goes_live_at` times of posts and series, ascending
+    schedule: Vec<DateTime<Utc>>,
 }
 
 /// Internal series data with owned posts
@@ -22,121 +37,205 @@ pub struct ContentCache {
 struct SeriesData {
     slug: String,
     config: SeriesConfig,
-    post_slugs: Vec<String>,
+    /// Keys into `ContentCache::posts`, in series order
+    post_keys: Vec<String>,
+    /// Filled in once all posts are loaded (see [`ContentCache::compute_series_etags`])
+    etag: String,
+    last_modified: Option<DateTime<Utc>>,
 }
 
 impl ContentCache {
-    /// Load content from disk into cache
+    /// Load content into cache from the working tree, a deploy directory or
+    /// a commit, depending on `config.source`
     pub fn load(config: &ContentConfig) -> Result<Self> {
-        let content_path = config.repo_path.join(&config.content_dir);
+        match config.source {
+            ContentSource::Worktree => {
+                let dates = directory_dates(config, None);
+                Self::load_from(&Worktree, &config.repo_path, config, &dates, None)
+            }
+            ContentSource::Deploy => match deploy::checkout(config)? {
+                Some(deployment) => {
+                    let dates = directory_dates(config, Some(&deployment.commit));
+                    let cache = Self::load_from(
+                        &Checkout::new(&deployment.path, &dates),
+                        &deployment.path,
+                        config,
+                        &dates,
+                        Some(deployment.commit.clone()),
+                    )?;
+                    deploy::prune(config, &deployment.path);
+                    Ok(cache)
+                }
+                None => {
+                    tracing::warn!("{} does not point to a commit yet", config.git_ref);
+                    Ok(Self::empty(None))
+                }
+            },
+            ContentSource::Git => {
+                match GitTree::read(
+                    &config.repo_path,
+                    &config.git_ref,
+                    &config.content_dir,
+                    config.max_content_file_size,
+                )? {
+                    Some(tree) => {
+                        let revision = Some(tree.commit().to_string());
+                        Self::load_from(&tree, Path::new(""), config, tree.dates(), revision)
+                    }
+                    None => {
+                        tracing::warn!("{} does not point to a commit yet", config.git_ref);
+                        Ok(Self::empty(None))
+                    }
+                }
+            }
+        }
+    }
 
-        if !content_path.exists() {
-            return Ok(Self {
-                posts: HashMap::new(),
-                series: HashMap::new(),
-                etag: Self::compute_etag(&HashMap::new(), &HashMap::new()),
-            });
+    fn empty(revision: Option<String>) -> Self {
+        Self {
+            posts: HashMap::new(),
+            series: HashMap::new(),
+            slugs: HashMap::new(),
+            diagnostics: Vec::new(),
+            etag: Self::compute_etag(&HashMap::new(), &HashMap::new()),
+            search: SearchIndex::default(),
+            revision,
+            schedule: Vec::new(),
+        }
+    }
+
+    /// Load the content directory under `root` from `source`
+    fn load_from(
+        source: &dyn Source,
+        root: &Path,
+        config: &ContentConfig,
+        dates: &DirectoryDates,
+        revision: Option<String>,
+    ) -> Result<Self> {
+        let content_path = root.join(&config.content_dir);
+
+        if !source.exists(&content_path) {
+            return Ok(Self::empty(revision));
         }
 
         let mut posts = HashMap::new();
         let mut series = HashMap::new();
-        let mut errors = 0u32;
+        let mut diagnostics = Vec::new();
         let mut total_bytes: u64 = 0;
 
         // Iterate through content directory
-        for entry in fs::read_dir(&content_path)? {
+        for entry in source.read_dir(&content_path)? {
             let entry = match entry {
                 Ok(e) => e,
                 Err(e) => {
-                    tracing::warn!("Failed to read directory entry: {}", e);
-                    errors += 1;
+                    diagnostics.push(LoadDiagnostic {
+                        kind: DiagnosticKind::Io,
+                        path: String::new(),
+                        message: format!("Failed to read directory entry: {}", e),
+                    });
                     continue;
                 }
             };
-            let path = entry.path();
+            let path = content_path.join(&entry.name);
 
-            // Security: DirEntry::file_type() does NOT follow symlinks,
-            // preventing symlink traversal attacks (e.g., content.mdx -> /etc/passwd).
+            // Security: entry kinds do NOT follow symlinks, preventing symlink
+            // traversal attacks (e.g., content.mdx -> /etc/passwd).
             // Note: A theoretical TOCTOU race exists between this check and subsequent
             // file reads, but it requires local filesystem access during the microsecond
             // window and is not exploitable via the git push interface alone.
-            let file_type = match entry.file_type() {
-                Ok(ft) => ft,
+            let kind = match entry.kind {
+                Ok(kind) => kind,
                 Err(e) => {
-                    tracing::warn!("Failed to get file type for {:?}: {}", path, e);
-                    errors += 1;
+                    diagnostics.push(LoadDiagnostic {
+                        kind: DiagnosticKind::Io,
+                        path: entry.name.to_string_lossy().into_owned(),
+                        message: format!("Failed to get file type: {}", e),
+                    });
                     continue;
                 }
             };
-            if file_type.is_symlink() {
+            if kind == EntryKind::Symlink {
                 tracing::warn!(
                     "Security: Skipping symlink in content directory: {:?}",
                     path
                 );
                 continue;
             }
-            if !file_type.is_dir() {
+            if kind != EntryKind::Dir {
                 continue;
             }
 
-            let slug = match path.file_name().and_then(|n| n.to_str()) {
+            let slug = match entry.name.to_str() {
                 Some(s) => s.to_string(),
                 None => {
-                    tracing::warn!("Skipping directory with invalid name: {:?}", path);
-                    errors += 1;
+                    diagnostics.push(LoadDiagnostic {
+                        kind: DiagnosticKind::Parse,
+                        path: entry.name.to_string_lossy().into_owned(),
+                        message: "Directory name is not valid UTF-8".to_string(),
+                    });
                     continue;
                 }
             };
 
             // Check total content size limit before loading more
             if total_bytes > config.max_total_content_size {
-                tracing::error!(
-                    "Total content size ({} bytes) exceeds limit ({} bytes). \
-                     Skipping remaining content.",
-                    total_bytes,
-                    config.max_total_content_size
-                );
+                diagnostics.push(LoadDiagnostic {
+                    kind: DiagnosticKind::SizeLimit,
+                    path: slug,
+                    message: format!(
+                        "Total content size ({} bytes) exceeds limit ({} bytes); \
+                         skipping this and all remaining content",
+                        total_bytes, config.max_total_content_size
+                    ),
+                });
                 break;
             }
 
             // Check if this is a series (has series.toml)
             let series_toml = path.join("series.toml");
-            if series_toml.exists() {
-                match Self::load_series(&path, &slug, config.max_content_file_size) {
+            if source.exists(&series_toml) {
+                match Self::load_series(source, &path, &slug, config, &mut diagnostics) {
                     Ok((series_data, series_posts)) => {
                         for post in &series_posts {
                             total_bytes += post.content.len() as u64;
                         }
                         series.insert(slug.clone(), series_data);
                         for post in series_posts {
-                            posts.insert(post.slug.clone(), post);
+                            posts.insert(post_key(Some(&slug), &post.slug), post);
                         }
                     }
-                    Err(e) => {
-                        tracing::error!("Failed to load series '{}': {}", slug, e);
-                        errors += 1;
-                    }
+                    Err(e) => diagnostics.push(load_failure(slug, &e)),
                 }
             } else {
                 // Check if this is a post (has config.toml + content.mdx)
                 let config_toml = path.join("config.toml");
                 let content_mdx = path.join("content.mdx");
 
-                if config_toml.exists() && content_mdx.exists() {
-                    match Self::load_post(&path, &slug, None, config.max_content_file_size) {
+                if source.exists(&config_toml) && source.exists(&content_mdx) {
+                    match Self::load_post(source, &path, &slug, None, config) {
                         Ok(post) => {
                             total_bytes += post.content.len() as u64;
                             posts.insert(slug, post);
                         }
-                        Err(e) => {
-                            tracing::error!("Failed to load post '{}': {}", slug, e);
-                            errors += 1;
-                        }
+                        Err(e) => diagnostics.push(load_failure(slug, &e)),
                     }
                 }
             }
         }
 
+        Self::apply_git_dates(&mut posts, config, dates);
+
+        let (slugs, collisions) = Self::index_slugs(&posts, config.post_addressing);
+        diagnostics.extend(collisions);
+
+        let errors = diagnostics.iter().filter(|d| d.is_error()).count();
+        for diagnostic in &diagnostics {
+            if diagnostic.is_error() {
+                tracing::error!("{}: {}", diagnostic.path, diagnostic.message);
+            } else {
+                tracing::warn!("{}: {}", diagnostic.path, diagnostic.message);
+            }
+        }
         if errors > 0 {
             tracing::warn!(
                 "Content loaded with {} error(s): {} posts, {} series",
@@ -153,34 +252,115 @@ impl ContentCache {
         }
 
         let etag = Self::compute_etag(&posts, &series);
+        let search = SearchIndex::build(&posts);
+        let mut schedule: Vec<DateTime<Utc>> = posts
+            .values()
+            .filter_map(|post| post.goes_live_at)
+            .chain(series.values().filter_map(|s| s.config.goes_live_at))
+            .collect();
+        schedule.sort();
+        schedule.dedup();
 
-        Ok(Self {
+        let mut cache = Self {
             posts,
             series,
+            slugs,
+            diagnostics,
             etag,
-        })
+            search,
+            revision,
+            schedule,
+        };
+        cache.compute_series_etags();
+        Ok(cache)
+    }
+
+    /// Map slugs to the posts reachable at `/posts/{slug}`, reporting collisions.
+    ///
+    /// Standalone posts claim their slug first, then series posts in key order,
+    /// so the outcome doesn't depend on directory iteration order. Colliding
+    /// posts stay loaded (and listed within their series) but aren't reachable
+    /// by bare slug.
+    fn index_slugs(
+        posts: &HashMap<String, Post>,
+        addressing: PostAddressing,
+    ) -> (HashMap<String, String>, Vec<LoadDiagnostic>) {
+        let mut keys: Vec<&String> = posts.keys().collect();
+        keys.sort_by_key(|key| (posts[*key].series_slug.is_some(), *key));
+
+        let mut slugs: HashMap<String, String> = HashMap::new();
+        let mut diagnostics = Vec::new();
+        for key in keys {
+            let post = &posts[key];
+            if post.series_slug.is_some() && addressing == PostAddressing::Namespaced {
+                continue;
+            }
+            match slugs.get(&post.slug) {
+                Some(winner) => diagnostics.push(LoadDiagnostic {
+                    kind: DiagnosticKind::SlugCollision,
+                    path: key.clone(),
+                    message: format!(
+                        "Slug '{}' is already used by '{}'; this post is not reachable at /posts/{}",
+                        post.slug, winner, post.slug
+                    ),
+                }),
+                None => {
+                    slugs.insert(post.slug.clone(), key.clone());
+                }
+            }
+        }
+        (slugs, diagnostics)
+    }
+
+    /// Fill in `created_at`/`updated_at` from the git history of each post's directory
+    fn apply_git_dates(
+        posts: &mut HashMap<String, Post>,
+        config: &ContentConfig,
+        dates: &DirectoryDates,
+    ) {
+        for post in posts.values_mut() {
+            if let Some(&(created, updated)) = dates.get(&post_dir(&config.content_dir, post)) {
+                post.created_at = Some(created);
+                post.updated_at = Some(updated);
+                post.etag = etag_for(&*post);
+            }
+        }
+    }
+
+    /// Compute each series' ETag from its rendered representation.
+    ///
+    /// Series embed summaries of their posts, so this runs after every post is loaded.
+    fn compute_series_etags(&mut self) {
+        let etags: Vec<(String, String)> = self
+            .series
+            .values()
+            .map(|data| (data.slug.clone(), etag_for(&self.build_series(data))))
+            .collect();
+        for (slug, etag) in etags {
+            if let Some(data) = self.series.get_mut(&slug) {
+                data.etag = etag;
+            }
+        }
     }
 
     /// Read a file to string, rejecting files larger than max_size.
-    fn read_file_bounded(path: &Path, max_size: u64) -> Result<String> {
-        let meta = fs::metadata(path)?;
-        if meta.len() > max_size {
+    fn read_file_bounded(source: &dyn Source, path: &Path, max_size: u64) -> Result<String> {
+        let len = source.len(path)?;
+        if len > max_size {
             return Err(Error::Content {
                 path: path.to_path_buf(),
                 message: format!(
                     "File size {} bytes exceeds limit of {} bytes",
-                    meta.len(),
-                    max_size
+                    len, max_size
                 ),
             });
         }
-        Ok(fs::read_to_string(path)?)
+        Ok(source.read_to_string(path)?)
     }
 
     /// Check that a file is not a symlink (prevents traversal attacks).
-    fn reject_symlink(path: &Path) -> Result<()> {
-        let meta = fs::symlink_metadata(path)?;
-        if meta.file_type().is_symlink() {
+    fn reject_symlink(source: &dyn Source, path: &Path) -> Result<()> {
+        if source.is_symlink(path)? {
             return Err(Error::Content {
                 path: path.to_path_buf(),
                 message: "Symlinks are not allowed in content directories".to_string(),
@@ -191,27 +371,32 @@ impl ContentCache {
 
     /// Load a single post from a directory
     fn load_post(
+        source: &dyn Source,
         path: &Path,
         slug: &str,
         series_slug: Option<&str>,
-        max_file_size: u64,
+        content_config: &ContentConfig,
     ) -> Result<Post> {
+        let max_file_size = content_config.max_content_file_size;
         let config_path = path.join("config.toml");
         let content_path = path.join("content.mdx");
 
         // Security: reject symlinked files to prevent reading arbitrary system files
-        Self::reject_symlink(&config_path)?;
-        Self::reject_symlink(&content_path)?;
+        Self::reject_symlink(source, &config_path)?;
+        Self::reject_symlink(source, &content_path)?;
 
-        let config_str = Self::read_file_bounded(&config_path, max_file_size)?;
+        let config_str = Self::read_file_bounded(source, &config_path, max_file_size)?;
         let config: PostConfig = toml::from_str(&config_str).map_err(|e| Error::Content {
             path: config_path.clone(),
             message: e.to_string(),
         })?;
 
-        let content = Self::read_file_bounded(&content_path, max_file_size)?;
+        let content = Self::read_file_bounded(source, &content_path, max_file_size)?;
+        let rendered = render::render(&content, &content_config.render);
+        let word_count = search::word_count(&content);
+        let last_modified = newest_mtime(source, &[&config_path, &content_path]);
 
-        Ok(Post {
+        let mut post = Post {
             slug: slug.to_string(),
             title: config.title,
             subtitle: config.subtitle,
@@ -221,57 +406,93 @@ impl ContentCache {
             goes_live_at: config.goes_live_at,
             series_slug: series_slug.map(String::from),
             content,
+            content_html: Some(rendered.html),
+            toc: rendered.toc,
+            word_count,
+            reading_time_minutes: render::reading_time_minutes(word_count),
+            created_at: None,
+            updated_at: None,
             order: config.order,
-        })
+            etag: String::new(),
+            last_modified,
+        };
+        post.etag = etag_for(&post);
+        Ok(post)
     }
 
-    /// Load a series and its posts
-    fn load_series(path: &Path, slug: &str, max_file_size: u64) -> Result<(SeriesData, Vec<Post>)> {
+    /// Load a series and its posts.
+    ///
+    /// A post that fails to load is skipped and recorded in `diagnostics`; only
+    /// a missing or invalid `series.toml` fails the whole series.
+    fn load_series(
+        source: &dyn Source,
+        path: &Path,
+        slug: &str,
+        content_config: &ContentConfig,
+        diagnostics: &mut Vec<LoadDiagnostic>,
+    ) -> Result<(SeriesData, Vec<Post>)> {
+        let max_file_size = content_config.max_content_file_size;
         let series_toml = path.join("series.toml");
-        Self::reject_symlink(&series_toml)?;
-        let series_str = Self::read_file_bounded(&series_toml, max_file_size)?;
+        Self::reject_symlink(source, &series_toml)?;
+        let series_str = Self::read_file_bounded(source, &series_toml, max_file_size)?;
         let config: SeriesConfig = toml::from_str(&series_str).map_err(|e| Error::Content {
             path: series_toml.clone(),
             message: e.to_string(),
         })?;
 
         let mut posts = Vec::new();
-        let mut post_slugs = Vec::new();
 
         // Load posts within the series
-        for entry in fs::read_dir(path)? {
-            let entry = entry?;
-            let post_path = entry.path();
+        for entry in source.read_dir(path)? {
+            let entry = match entry {
+                Ok(e) => e,
+                Err(e) => {
+                    diagnostics.push(load_failure(slug.to_string(), &e.into()));
+                    continue;
+                }
+            };
+            let post_path = path.join(&entry.name);
+            let entry_path = format!("{}/{}", slug, entry.name.to_string_lossy());
 
             // Security: reject symlinks to prevent traversal attacks
-            let file_type = entry.file_type()?;
-            if file_type.is_symlink() {
+            let kind = match entry.kind {
+                Ok(kind) => kind,
+                Err(e) => {
+                    diagnostics.push(load_failure(entry_path, &e.into()));
+                    continue;
+                }
+            };
+            if kind == EntryKind::Symlink {
                 tracing::warn!(
                     "Security: Skipping symlink in series directory: {:?}",
                     post_path
                 );
                 continue;
             }
-            if !file_type.is_dir() {
+            if kind != EntryKind::Dir {
                 continue;
             }
 
-            let post_slug = post_path
-                .file_name()
-                .and_then(|n| n.to_str())
-                .ok_or_else(|| Error::Content {
-                    path: post_path.clone(),
-                    message: "Invalid directory name".to_string(),
-                })?
-                .to_string();
+            let post_slug = match entry.name.to_str() {
+                Some(s) => s.to_string(),
+                None => {
+                    diagnostics.push(LoadDiagnostic {
+                        kind: DiagnosticKind::Parse,
+                        path: entry_path,
+                        message: "Directory name is not valid UTF-8".to_string(),
+                    });
+                    continue;
+                }
+            };
 
             let config_toml = post_path.join("config.toml");
             let content_mdx = post_path.join("content.mdx");
 
-            if config_toml.exists() && content_mdx.exists() {
-                let post = Self::load_post(&post_path, &post_slug, Some(slug), max_file_size)?;
-                post_slugs.push(post_slug);
-                posts.push(post);
+            if source.exists(&config_toml) && source.exists(&content_mdx) {
+                match Self::load_post(source, &post_path, &post_slug, Some(slug), content_config) {
+                    Ok(post) => posts.push(post),
+                    Err(e) => diagnostics.push(load_failure(entry_path, &e)),
+                }
             }
         }
 
@@ -287,12 +508,23 @@ impl ContentCache {
             }
         });
 
-        post_slugs = posts.iter().map(|p| p.slug.clone()).collect();
+        let post_keys = posts
+            .iter()
+            .map(|p| post_key(Some(slug), &p.slug))
+            .collect();
+
+        let last_modified = posts
+            .iter()
+            .filter_map(|p| p.last_modified)
+            .chain(newest_mtime(source, &[&series_toml]))
+            .max();
 
         let series_data = SeriesData {
             slug: slug.to_string(),
             config,
-            post_slugs,
+            post_keys,
+            etag: String::new(),
+            last_modified,
         };
 
         Ok((series_data, posts))
@@ -332,9 +564,27 @@ impl ContentCache {
         format!("\"{}\"", hex::encode(result))
     }
 
-    /// Get ETag for HTTP caching
+    /// Get ETag for HTTP caching.
+    ///
+    /// Changes when content changes, and when scheduled content goes live.
     pub fn etag(&self) -> String {
-        self.etag.clone()
+        self.visibility_etag(&self.etag)
+    }
+
+    /// `etag` combined with the latest `goes_live_at` that has passed, so an
+    /// ETag changes when the set of live content does
+    fn visibility_etag(&self, etag: &str) -> String {
+        let now = Utc::now();
+        match self.schedule.partition_point(|t| *t <= now) {
+            0 => etag.to_string(),
+            passed => etag_for(&(etag, self.schedule[passed - 1])),
+        }
+    }
+
+    /// The first `goes_live_at` of a post or series after `after`
+    pub fn next_scheduled(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let next = self.schedule.partition_point(|t| *t <= after);
+        self.schedule.get(next).copied()
     }
 
     /// Maximum number of items returned in a single list request.
@@ -350,6 +600,7 @@ impl ContentCache {
             .posts
             .values()
             .filter(|post| Self::is_visible(post.goes_live_at, opts, &now))
+            .filter(|post| Self::matches_tags(post, opts))
             .collect();
 
         // Sort by goes_live_at descending (newest first), drafts at end
@@ -361,24 +612,133 @@ impl ContentCache {
         });
 
         let total = filtered.len();
-        let items: Vec<PostSummary> = filtered
+        let page: Vec<&Post> = filtered.into_iter().skip(offset).take(limit).collect();
+        let etag = list_etag(page.iter().map(|p| p.etag.as_str()), total, limit, offset);
+        let items: Vec<PostSummary> = page.into_iter().map(|p| p.into()).collect();
+
+        Ok(ListResult {
+            items,
+            total,
+            limit,
+            offset,
+            etag,
+        })
+    }
+
+    /// Search posts, best match first.
+    ///
+    /// Visibility follows the same rules as [`list_posts`](Self::list_posts).
+    pub fn search(&self, query: &str, opts: &ListOptions) -> Result<ListResult<SearchResult>> {
+        let now = Utc::now();
+        let limit = opts.limit.unwrap_or(50).min(Self::MAX_PAGE_SIZE);
+        let offset = opts.offset.unwrap_or(0);
+
+        let hits: Vec<(&str, &Post, f32)> = self
+            .search
+            .search(query)
+            .into_iter()
+            .filter_map(|(key, score)| self.posts.get(key).map(|post| (key, post, score)))
+            .filter(|(_, post, _)| Self::is_visible(post.goes_live_at, opts, &now))
+            .filter(|(_, post, _)| Self::matches_tags(post, opts))
+            .collect();
+
+        let total = hits.len();
+        let page: Vec<(&str, &Post, f32)> = hits.into_iter().skip(offset).take(limit).collect();
+        let etag = list_etag(
+            page.iter().map(|(_, p, _)| p.etag.as_str()),
+            total,
+            limit,
+            offset,
+        );
+        let items: Vec<SearchResult> = page
+            .into_iter()
+            .map(|(key, post, score)| SearchResult {
+                snippet: self
+                    .search
+                    .snippet(key, query)
+                    .unwrap_or_else(|| search::snippet_from_start(&post.preview_text, query)),
+                post: post.into(),
+                score,
+            })
+            .collect();
+
+        Ok(ListResult {
+            items,
+            total,
+            limit,
+            offset,
+            etag,
+        })
+    }
+
+    /// List tags used by visible posts, most used first.
+    ///
+    /// Tags are grouped case-insensitively; each is reported with the spelling
+    /// of the first post (by slug) that uses it. Tags only used by drafts or
+    /// scheduled posts are omitted unless `opts` includes them.
+    pub fn list_tags(&self, opts: &ListOptions) -> Result<ListResult<TagSummary>> {
+        let now = Utc::now();
+        let limit = opts.limit.unwrap_or(50).min(Self::MAX_PAGE_SIZE);
+        let offset = opts.offset.unwrap_or(0);
+
+        let mut posts: Vec<&Post> = self
+            .posts
+            .values()
+            .filter(|post| Self::is_visible(post.goes_live_at, opts, &now))
+            .collect();
+        posts.sort_by(|a, b| a.slug.cmp(&b.slug));
+
+        // Lowercased tag -> (display spelling, count)
+        let mut counts: HashMap<String, (String, usize)> = HashMap::new();
+        for post in posts {
+            let mut seen: Vec<String> = Vec::new();
+            for tag in post.tags.iter().flatten() {
+                let key = tag_key(tag);
+                if seen.contains(&key) {
+                    continue;
+                }
+                let entry = counts
+                    .entry(key.clone())
+                    .or_insert_with(|| (tag.clone(), 0));
+                entry.1 += 1;
+                seen.push(key);
+            }
+        }
+
+        let mut tags: Vec<_> = counts.into_values().collect();
+        tags.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+        let total = tags.len();
+        let page: Vec<_> = tags.into_iter().skip(offset).take(limit).collect();
+        let items: Vec<TagSummary> = page
             .into_iter()
-            .skip(offset)
-            .take(limit)
-            .map(|p| p.into())
+            .map(|(tag, post_count)| TagSummary { tag, post_count })
             .collect();
+        let etag = etag_for(&(&items, total, limit, offset));
 
         Ok(ListResult {
             items,
             total,
             limit,
             offset,
+            etag,
         })
     }
 
-    /// Get a single post by slug
+    /// Get a single post by slug.
+    ///
+    /// Only posts reachable at `/posts/{slug}` are found; see [`PostAddressing`].
     pub fn get_post(&self, slug: &str) -> Result<Option<Post>> {
-        Ok(self.posts.get(slug).cloned())
+        Ok(self
+            .slugs
+            .get(slug)
+            .and_then(|key| self.posts.get(key))
+            .cloned())
+    }
+
+    /// Get a post by its series and slug, regardless of [`PostAddressing`]
+    pub fn get_series_post(&self, series_slug: &str, slug: &str) -> Result<Option<Post>> {
+        Ok(self.posts.get(&post_key(Some(series_slug), slug)).cloned())
     }
 
     /// List series with filtering and pagination
@@ -404,17 +764,17 @@ impl ContentCache {
         );
 
         let total = filtered.len();
-        let items: Vec<SeriesSummary> = filtered
+        let page: Vec<&SeriesData> = filtered.into_iter().skip(offset).take(limit).collect();
+        let etag = list_etag(page.iter().map(|s| s.etag.as_str()), total, limit, offset);
+        let items: Vec<SeriesSummary> = page
             .into_iter()
-            .skip(offset)
-            .take(limit)
             .map(|s| SeriesSummary {
                 slug: s.slug.clone(),
                 title: s.config.title.clone(),
                 description: s.config.description.clone(),
                 preview_image: s.config.preview_image.clone(),
                 goes_live_at: s.config.goes_live_at,
-                post_count: s.post_slugs.len(),
+                post_count: s.post_keys.len(),
             })
             .collect();
 
@@ -423,6 +783,7 @@ impl ContentCache {
             total,
             limit,
             offset,
+            etag,
         })
     }
 
@@ -433,11 +794,19 @@ impl ContentCache {
             None => return Ok(None),
         };
 
+        let mut series = self.build_series(series_data);
+        // Clients filter the series' posts by visibility
+        series.etag = self.visibility_etag(&series_data.etag);
+        Ok(Some(series))
+    }
+
+    /// Assemble the public [`Series`] representation (without its ETag)
+    fn build_series(&self, series_data: &SeriesData) -> Series {
         let posts: Vec<SeriesPostSummary> = series_data
-            .post_slugs
+            .post_keys
             .iter()
-            .filter_map(|post_slug| {
-                self.posts.get(post_slug).map(|post| SeriesPostSummary {
+            .filter_map(|post_key| {
+                self.posts.get(post_key).map(|post| SeriesPostSummary {
                     slug: post.slug.clone(),
                     title: post.title.clone(),
                     subtitle: post.subtitle.clone(),
@@ -446,18 +815,86 @@ impl ContentCache {
                     tags: post.tags.clone(),
                     goes_live_at: post.goes_live_at,
                     order: post.order,
+                    word_count: post.word_count,
+                    reading_time_minutes: post.reading_time_minutes,
                 })
             })
             .collect();
 
-        Ok(Some(Series {
+        Series {
             slug: series_data.slug.clone(),
             title: series_data.config.title.clone(),
             description: series_data.config.description.clone(),
             preview_image: series_data.config.preview_image.clone(),
             goes_live_at: series_data.config.goes_live_at,
             posts,
-        }))
+            etag: String::new(),
+            last_modified: series_data.last_modified,
+        }
+    }
+
+    /// Posts that are publicly live, newest first.
+    ///
+    /// Unlike [`list_posts`](Self::list_posts), posts inside a series that is not
+    /// itself live are excluded. Used for syndication (feeds, sitemap).
+    pub(crate) fn published_posts(&self) -> Vec<&Post> {
+        self.published_posts_at(Utc::now())
+    }
+
+    /// Posts that are publicly live at `now`, newest first
+    fn published_posts_at(&self, now: DateTime<Utc>) -> Vec<&Post> {
+        let opts = ListOptions::default();
+
+        let mut posts: Vec<&Post> = self
+            .posts
+            .values()
+            .filter(|post| Self::is_visible(post.goes_live_at, &opts, &now))
+            .filter(|post| {
+                post.series_slug.as_deref().is_none_or(|slug| {
+                    self.series
+                        .get(slug)
+                        .is_some_and(|s| Self::is_visible(s.config.goes_live_at, &opts, &now))
+                })
+            })
+            .collect();
+
+        posts.sort_by(|a, b| {
+            b.goes_live_at
+                .cmp(&a.goes_live_at)
+                .then_with(|| a.slug.cmp(&b.slug))
+        });
+        posts
+    }
+
+    /// Get a series by slug only if it is publicly live
+    pub(crate) fn published_series(&self, slug: &str) -> Option<Series> {
+        self.published_series_at(slug, Utc::now())
+    }
+
+    /// Get a series by slug only if it is publicly live at `now`
+    fn published_series_at(&self, slug: &str, now: DateTime<Utc>) -> Option<Series> {
+        let data = self.series.get(slug)?;
+        if !Self::is_visible(data.config.goes_live_at, &ListOptions::default(), &now) {
+            return None;
+        }
+        let mut series = self.build_series(data);
+        series.etag = data.etag.clone();
+        Some(series)
+    }
+
+    /// All publicly live series, sorted by slug
+    pub(crate) fn all_published_series(&self) -> Vec<Series> {
+        self.all_published_series_at(Utc::now())
+    }
+
+    /// All series publicly live at `now`, sorted by slug
+    fn all_published_series_at(&self, now: DateTime<Utc>) -> Vec<Series> {
+        let mut slugs: Vec<&String> = self.series.keys().collect();
+        slugs.sort();
+        slugs
+            .into_iter()
+            .filter_map(|slug| self.published_series_at(slug, now))
+            .collect()
     }
 
     /// Check if content is visible based on goes_live_at and options
@@ -473,9 +910,155 @@ impl ContentCache {
         }
     }
 
+    /// Check a post against the tag filters in `opts`
+    fn matches_tags(post: &Post, opts: &ListOptions) -> bool {
+        let has_tag = |wanted: &String| {
+            let wanted = tag_key(wanted);
+            post.tags.iter().flatten().any(|tag| tag_key(tag) == wanted)
+        };
+        (opts.tags_any.is_empty() || opts.tags_any.iter().any(has_tag))
+            && opts.tags_all.iter().all(has_tag)
+    }
+
+    /// Commit the content was read from (`content.source = "git"` or `"deploy"` only)
+    pub fn revision(&self) -> Option<&str> {
+        self.revision.as_deref()
+    }
+
+    /// Problems found while loading, in the order they were found
+    pub fn diagnostics(&self) -> &[LoadDiagnostic] {
+        &self.diagnostics
+    }
+
+    /// Number of loaded posts, including series posts
+    pub fn post_count(&self) -> usize {
+        self.posts.len()
+    }
+
+    /// Why this cache shouldn't replace `previous` under `config.refresh`, if it shouldn't.
+    ///
+    /// A reload is a regression if it has load errors at paths `previous`
+    /// loaded cleanly, or lost more than `max_post_drop_percent` of the
+    /// previous posts. Content that was already broken in `previous` isn't
+    /// served either way, so it doesn't block unrelated edits.
+    pub fn regression_from(
+        &self,
+        previous: &ContentCache,
+        config: &ContentConfig,
+    ) -> Option<String> {
+        let errors: Vec<&LoadDiagnostic> = self
+            .diagnostics
+            .iter()
+            .filter(|d| d.is_error())
+            .filter(|d| {
+                !previous
+                    .diagnostics
+                    .iter()
+                    .any(|p| p.is_error() && p.path == d.path)
+            })
+            .collect();
+        if let Some(first) = errors.first() {
+            return Some(format!(
+                "{} new load error(s), first at '{}': {}",
+                errors.len(),
+                first.path,
+                first.message
+            ));
+        }
+
+        let before = previous.post_count();
+        let after = self.post_count();
+        let max_drop = usize::from(config.refresh.max_post_drop_percent.min(100));
+        if after < before && (before - after) * 100 > before * max_drop {
+            return Some(format!(
+                "post count dropped from {} to {} (more than {}%)",
+                before, after, max_drop
+            ));
+        }
+        None
+    }
+
+    /// Publicly live posts and series that changed since `previous`.
+    ///
+    /// A post or series is updated when its ETag changed. Becoming or ceasing
+    /// to be live (e.g. a draft getting a `goes_live_at`) counts as created or
+    /// deleted.
+    pub fn changes_from(&self, previous: &ContentCache) -> ContentChanges {
+        let now = Utc::now();
+        ContentChanges {
+            old_sha: previous.revision.clone(),
+            new_sha: self.revision.clone(),
+            posts: slug_changes(
+                previous.published_post_etags(now),
+                self.published_post_etags(now),
+            ),
+            series: slug_changes(
+                previous.published_series_etags(now),
+                self.published_series_etags(now),
+            ),
+        }
+    }
+
+    /// Posts and series that went live after `from`, up to and including `to`.
+    ///
+    /// A live series with a post that went live counts as updated.
+    pub fn changes_between(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> ContentChanges {
+        let mut changes = ContentChanges {
+            old_sha: self.revision.clone(),
+            new_sha: self.revision.clone(),
+            posts: slug_changes(
+                self.published_post_etags(from),
+                self.published_post_etags(to),
+            ),
+            series: slug_changes(
+                self.published_series_etags(from),
+                self.published_series_etags(to),
+            ),
+        };
+        let live_series = self.published_series_etags(from);
+        for key in &changes.posts.created {
+            if let Some((series_slug, _)) = key.split_once('/')
+                && live_series.contains_key(series_slug)
+                && !changes.series.updated.iter().any(|s| s == series_slug)
+            {
+                changes.series.updated.push(series_slug.to_string());
+            }
+        }
+        changes.series.updated.sort();
+        changes
+    }
+
+    /// ETags of posts publicly live at `now`, by post key
+    fn published_post_etags(&self, now: DateTime<Utc>) -> HashMap<String, String> {
+        self.published_posts_at(now)
+            .into_iter()
+            .map(|post| {
+                (
+                    post_key(post.series_slug.as_deref(), &post.slug),
+                    post.etag.clone(),
+                )
+            })
+            .collect()
+    }
+
+    /// ETags of series publicly live at `now`, by slug
+    fn published_series_etags(&self, now: DateTime<Utc>) -> HashMap<String, String> {
+        self.all_published_series_at(now)
+            .into_iter()
+            .map(|series| (series.slug, series.etag))
+            .collect()
+    }
+
     /// Validate content structure
     pub fn validate(&self) -> Vec<ValidationError> {
-        let mut errors = Vec::new();
+        let mut errors: Vec<ValidationError> = self
+            .diagnostics
+            .iter()
+            .map(|d| ValidationError {
+                path: d.path.clone(),
+                message: d.message.clone(),
+            })
+            .collect();
 
         for (slug, post) in &self.posts {
             if post.title.is_empty() {
@@ -511,18 +1094,123 @@ impl ContentCache {
     }
 }
 
+/// The form tags are compared in, so `Rust` and `rust` (or `Ärger` and
+/// `ärger`) are the same tag everywhere
+pub(crate) fn tag_key(tag: &str) -> String {
+    tag.to_lowercase()
+}
+
+/// Compute a quoted strong ETag from a value's serialized representation.
+///
+/// Hashing what the API actually serves means any change to a served field
+/// changes the ETag, and nothing else does.
+fn etag_for<T: Serialize>(value: &T) -> String {
+    let bytes = serde_json::to_vec(value).unwrap_or_default();
+    format!("\"{}\"", hex::encode(Sha256::digest(&bytes)))
+}
+
+/// Commit dates of the directories under the content directory, up to `rev` (default: HEAD)
+fn directory_dates(config: &ContentConfig, rev: Option<&str>) -> DirectoryDates {
+    git::directory_dates(&config.repo_path, config.content_dir.trim_matches('/'), rev)
+        .unwrap_or_else(|e| {
+            tracing::warn!("Failed to read git history for post dates: {}", e);
+            DirectoryDates::new()
+        })
+}
+
+/// Diagnostic for content that failed to load
+fn load_failure(path: String, error: &Error) -> LoadDiagnostic {
+    let kind = match error {
+        Error::Io(_) => DiagnosticKind::Io,
+        _ => DiagnosticKind::Parse,
+    };
+    LoadDiagnostic {
+        kind,
+        path,
+        message: error.to_string(),
+    }
+}
+
+/// Keys created, updated (different ETag) and deleted between `old` and `new`
+fn slug_changes(old: HashMap<String, String>, new: HashMap<String, String>) -> SlugChanges {
+    let mut changes = SlugChanges::default();
+    for (key, etag) in &new {
+        match old.get(key) {
+            None => changes.created.push(key.clone()),
+            Some(old_etag) if old_etag != etag => changes.updated.push(key.clone()),
+            Some(_) => {}
+        }
+    }
+    changes.deleted = old
+        .into_keys()
+        .filter(|key| !new.contains_key(key))
+        .collect();
+    changes.created.sort();
+    changes.updated.sort();
+    changes.deleted.sort();
+    changes
+}
+
+/// Key of a post in [`ContentCache`]: `slug`, or `series/slug` for series posts.
+///
+/// Slugs are directory names, so they never contain `/` and keys can't collide.
+fn post_key(series_slug: Option<&str>, slug: &str) -> String {
+    match series_slug {
+        Some(series_slug) => format!("{}/{}", series_slug, slug),
+        None => slug.to_string(),
+    }
+}
+
+/// Path of a post's directory relative to the repository root, `/`-separated
+pub(crate) fn post_dir(content_dir: &str, post: &Post) -> String {
+    let prefix = content_dir.trim_matches('/');
+    let mut parts: Vec<&str> = Vec::with_capacity(3);
+    if !prefix.is_empty() && prefix != "." {
+        parts.push(prefix);
+    }
+    if let Some(ref series_slug) = post.series_slug {
+        parts.push(series_slug);
+    }
+    parts.push(&post.slug);
+    parts.join("/")
+}
+
+/// Compute a quoted strong ETag for a rendered response body (feeds, sitemap)
+pub(crate) fn body_etag(body: &str) -> String {
+    format!("\"{}\"", hex::encode(Sha256::digest(body.as_bytes())))
+}
+
+/// Compute the ETag of a list page from its items' ETags and pagination parameters.
+fn list_etag<'a>(
+    item_etags: impl Iterator<Item = &'a str>,
+    total: usize,
+    limit: usize,
+    offset: usize,
+) -> String {
+    let mut hasher = Sha256::new();
+    for value in [total, limit, offset] {
+        hasher.update((value as u64).to_le_bytes());
+    }
+    // ETags are fixed-length, so no length prefix is needed
+    for etag in item_etags {
+        hasher.update(etag.as_bytes());
+    }
+    format!("\"{}\"", hex::encode(hasher.finalize()))
+}
+
+/// Newest modification time among `paths`, skipping files whose mtime is unavailable
+fn newest_mtime(source: &dyn Source, paths: &[&Path]) -> Option<DateTime<Utc>> {
+    paths.iter().filter_map(|p| source.modified(p)).max()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::fs;
     use tempfile::TempDir;
 
     fn create_content_config(temp_dir: &TempDir) -> ContentConfig {
-        ContentConfig {
-            repo_path: temp_dir.path().to_path_buf(),
-            content_dir: "content".to_string(),
-            max_content_file_size: 5 * 1024 * 1024,
-            max_total_content_size: 100 * 1024 * 1024,
-        }
+        ContentConfig::for_tests(temp_dir.path())
     }
 
     fn create_post_files(dir: &Path, title: &str, preview: &str, content: &str) {
@@ -559,6 +1247,17 @@ preview_text = "Preview"
         fs::write(dir.join("content.mdx"), "# Content").unwrap();
     }
 
+    fn create_post_with_tags(dir: &Path, goes_live_at: Option<&str>, tags: &[&str]) {
+        create_post_with_date(dir, "Tagged", goes_live_at);
+        let tags: Vec<String> = tags.iter().map(|t| format!("\"{}\"", t)).collect();
+        let config = fs::read_to_string(dir.join("config.toml")).unwrap();
+        fs::write(
+            dir.join("config.toml"),
+            format!("{}tags = [{}]\n", config, tags.join(", ")),
+        )
+        .unwrap();
+    }
+
     #[test]
     fn test_load_empty_content() {
         let temp_dir = TempDir::new().unwrap();
@@ -642,7 +1341,7 @@ order = 2
         assert_eq!(series.posts[1].slug, "part-two");
 
         // Check posts have series_slug set
-        let post = cache.posts.get("part-one").unwrap();
+        let post = cache.get_post("part-one").unwrap().unwrap();
         assert_eq!(post.series_slug, Some("my-series".to_string()));
     }
 
@@ -817,6 +1516,90 @@ order = {}
         assert_ne!(etag1, etag2);
     }
 
+    #[test]
+    fn test_post_etags_are_per_resource() {
+        let temp_dir = TempDir::new().unwrap();
+        let content_dir = temp_dir.path().join("content");
+        create_post_files(&content_dir.join("post-a"), "A", "Preview", "A v1");
+        create_post_files(&content_dir.join("post-b"), "B", "Preview", "B v1");
+
+        let config = create_content_config(&temp_dir);
+        let cache1 = ContentCache::load(&config).unwrap();
+        let a1 = cache1.get_post("post-a").unwrap().unwrap();
+        let b1 = cache1.get_post("post-b").unwrap().unwrap();
+        assert!(a1.etag.starts_with('"') && a1.etag.ends_with('"'));
+        assert!(a1.last_modified.is_some());
+
+        fs::write(content_dir.join("post-a/content.mdx"), "A v2").unwrap();
+        let cache2 = ContentCache::load(&config).unwrap();
+        let a2 = cache2.get_post("post-a").unwrap().unwrap();
+        let b2 = cache2.get_post("post-b").unwrap().unwrap();
+
+        assert_ne!(a1.etag, a2.etag);
+        assert_eq!(b1.etag, b2.etag);
+    }
+
+    #[test]
+    fn test_series_etag_tracks_its_posts() {
+        let temp_dir = TempDir::new().unwrap();
+        let content_dir = temp_dir.path().join("content");
+        let series_dir = content_dir.join("series");
+        fs::create_dir_all(&series_dir).unwrap();
+        fs::write(series_dir.join("series.toml"), "title = \"Series\"").unwrap();
+        create_post_files(&series_dir.join("part-1"), "Part 1", "Preview", "One");
+        create_post_files(&content_dir.join("other"), "Other", "Preview", "Other");
+
+        let config = create_content_config(&temp_dir);
+        let etag1 = ContentCache::load(&config)
+            .unwrap()
+            .get_series("series")
+            .unwrap()
+            .unwrap()
+            .etag;
+
+        // Unrelated post: series ETag unchanged
+        fs::write(content_dir.join("other/content.mdx"), "Changed").unwrap();
+        let etag2 = ContentCache::load(&config)
+            .unwrap()
+            .get_series("series")
+            .unwrap()
+            .unwrap()
+            .etag;
+        assert_eq!(etag1, etag2);
+
+        // Post title shown in the series: series ETag changes
+        create_post_files(&series_dir.join("part-1"), "Part One", "Preview", "One");
+        let etag3 = ContentCache::load(&config)
+            .unwrap()
+            .get_series("series")
+            .unwrap()
+            .unwrap()
+            .etag;
+        assert_ne!(etag2, etag3);
+    }
+
+    #[test]
+    fn test_list_etag_depends_on_page() {
+        let temp_dir = TempDir::new().unwrap();
+        let content_dir = temp_dir.path().join("content");
+        create_post_with_date(&content_dir.join("a"), "A", Some("2024-01-01T00:00:00Z"));
+        create_post_with_date(&content_dir.join("b"), "B", Some("2024-02-01T00:00:00Z"));
+
+        let cache = ContentCache::load(&create_content_config(&temp_dir)).unwrap();
+        let page = |offset| {
+            cache
+                .list_posts(&ListOptions {
+                    limit: Some(1),
+                    offset: Some(offset),
+                    ..Default::default()
+                })
+                .unwrap()
+        };
+
+        assert_eq!(page(0).etag, page(0).etag);
+        assert_ne!(page(0).etag, page(1).etag);
+    }
+
     #[test]
     fn test_validation_empty_title() {
         let temp_dir = TempDir::new().unwrap();
@@ -949,5 +1732,262 @@ preview_text = "Preview"
 
         // Post with symlinked content.mdx should not be loaded
         assert!(!cache.posts.contains_key("evil-post"));
+        assert_eq!(cache.diagnostics().len(), 1);
+        assert_eq!(cache.diagnostics()[0].kind, DiagnosticKind::Parse);
+        assert_eq!(cache.diagnostics()[0].path, "evil-post");
+    }
+
+    #[test]
+    fn test_bad_series_post_only_skips_that_post() {
+        let temp_dir = TempDir::new().unwrap();
+        let series_dir = temp_dir.path().join("content/my-series");
+        fs::create_dir_all(&series_dir).unwrap();
+        fs::write(series_dir.join("series.toml"), "title = \"Series\"").unwrap();
+        create_post_files(&series_dir.join("good"), "Good", "P", "# Good");
+        create_post_files(&series_dir.join("bad"), "Bad", "P", "# Bad");
+        fs::write(series_dir.join("bad/config.toml"), "title = ").unwrap();
+
+        let cache = ContentCache::load(&create_content_config(&temp_dir)).unwrap();
+
+        assert_eq!(
+            cache.get_series("my-series").unwrap().unwrap().posts.len(),
+            1
+        );
+        assert_eq!(cache.diagnostics().len(), 1);
+        assert_eq!(cache.diagnostics()[0].path, "my-series/bad");
+        assert!(cache.diagnostics()[0].is_error());
+        assert_eq!(cache.validate()[0].path, "my-series/bad");
+    }
+
+    #[test]
+    fn test_total_size_limit_is_reported() {
+        let temp_dir = TempDir::new().unwrap();
+        let content_dir = temp_dir.path().join("content");
+        for slug in ["a", "b", "c"] {
+            create_post_files(&content_dir.join(slug), slug, "P", "0123456789");
+        }
+
+        let config = ContentConfig {
+            max_total_content_size: 5,
+            ..create_content_config(&temp_dir)
+        };
+        let cache = ContentCache::load(&config).unwrap();
+
+        // The first post exceeds the limit, so everything after it is skipped
+        assert_eq!(cache.post_count(), 1);
+        assert_eq!(cache.diagnostics().len(), 1);
+        assert_eq!(cache.diagnostics()[0].kind, DiagnosticKind::SizeLimit);
+    }
+
+    #[test]
+    fn test_regression_from() {
+        let temp_dir = TempDir::new().unwrap();
+        let content_dir = temp_dir.path().join("content");
+        for slug in ["a", "b", "c", "d"] {
+            create_post_files(&content_dir.join(slug), slug, "P", "# Hi");
+        }
+        let config = create_content_config(&temp_dir);
+        let previous = ContentCache::load(&config).unwrap();
+
+        fs::remove_dir_all(content_dir.join("a")).unwrap();
+        fs::remove_dir_all(content_dir.join("b")).unwrap();
+        let half = ContentCache::load(&config).unwrap();
+        assert_eq!(half.regression_from(&previous, &config), None);
+
+        fs::remove_dir_all(content_dir.join("c")).unwrap();
+        let quarter = ContentCache::load(&config).unwrap();
+        let reason = quarter.regression_from(&previous, &config).unwrap();
+        assert!(reason.contains("from 4 to 1"), "{}", reason);
+
+        let mut lenient = config.clone();
+        lenient.refresh.max_post_drop_percent = 100;
+        assert_eq!(quarter.regression_from(&previous, &lenient), None);
+
+        fs::write(content_dir.join("d/config.toml"), "title = ").unwrap();
+        let broken = ContentCache::load(&lenient).unwrap();
+        let reason = broken.regression_from(&previous, &lenient).unwrap();
+        assert!(
+            reason.contains("1 new load error(s), first at 'd'"),
+            "{}",
+            reason
+        );
+
+        // Already broken before, so only new errors count
+        fs::write(content_dir.join("d/config.toml"), "title = [").unwrap();
+        let still_broken = ContentCache::load(&lenient).unwrap();
+        assert_eq!(still_broken.regression_from(&broken, &lenient), None);
+    }
+
+    #[test]
+    fn test_tag_filters() {
+        let temp_dir = TempDir::new().unwrap();
+        let content_dir = temp_dir.path().join("content");
+        let live = Some("2020-01-01T00:00:00Z");
+        create_post_with_tags(&content_dir.join("both"), live, &["rust", "web"]);
+        create_post_with_tags(&content_dir.join("rust-only"), live, &["Rust"]);
+        create_post_with_tags(&content_dir.join("untagged"), live, &[]);
+        create_post_with_tags(&content_dir.join("umlaut"), live, &["Ärger"]);
+
+        let cache = ContentCache::load(&create_content_config(&temp_dir)).unwrap();
+        let slugs = |opts: ListOptions| {
+            let mut slugs: Vec<String> = cache
+                .list_posts(&opts)
+                .unwrap()
+                .items
+                .into_iter()
+                .map(|p| p.slug)
+                .collect();
+            slugs.sort();
+            slugs
+        };
+
+        let any = ListOptions {
+            tags_any: vec!["RUST".to_string(), "web".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(slugs(any), vec!["both", "rust-only"]);
+
+        let all = ListOptions {
+            tags_all: vec!["rust".to_string(), "web".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(slugs(all), vec!["both"]);
+
+        // Non-ASCII tags match case-insensitively, as `list_tags` groups them
+        let umlaut = ListOptions {
+            tags_any: vec!["ärger".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(slugs(umlaut), vec!["umlaut"]);
+    }
+
+    #[test]
+    fn test_list_tags_counts_only_visible_posts() {
+        let temp_dir = TempDir::new().unwrap();
+        let content_dir = temp_dir.path().join("content");
+        let live = Some("2020-01-01T00:00:00Z");
+        create_post_with_tags(&content_dir.join("a"), live, &["Rust", "web"]);
+        create_post_with_tags(&content_dir.join("b"), live, &["rust"]);
+        create_post_with_tags(&content_dir.join("draft"), None, &["rust", "secret"]);
+
+        let cache = ContentCache::load(&create_content_config(&temp_dir)).unwrap();
+
+        let result = cache.list_tags(&ListOptions::default()).unwrap();
+        let tags: Vec<(&str, usize)> = result
+            .items
+            .iter()
+            .map(|t| (t.tag.as_str(), t.post_count))
+            .collect();
+        assert_eq!(tags, vec![("Rust", 2), ("web", 1)]);
+
+        let opts = ListOptions {
+            include_drafts: true,
+            ..Default::default()
+        };
+        let result = cache.list_tags(&opts).unwrap();
+        assert_eq!(result.total, 3);
+        assert_eq!(result.items[0].post_count, 3);
+        assert_ne!(
+            result.etag,
+            cache.list_tags(&ListOptions::default()).unwrap().etag
+        );
+    }
+
+    #[test]
+    fn test_toc_word_count_and_reading_time() {
+        let temp_dir = TempDir::new().unwrap();
+        let words = "word ".repeat(250);
+        create_post_files(
+            &temp_dir.path().join("content/long-post"),
+            "Long",
+            "Preview",
+            &format!("# Intro\n\n{}\n\n## Details\n", words),
+        );
+
+        let cache = ContentCache::load(&create_content_config(&temp_dir)).unwrap();
+        let post = cache.get_post("long-post").unwrap().unwrap();
+
+        let anchors: Vec<&str> = post.toc.iter().map(|e| e.anchor.as_str()).collect();
+        assert_eq!(anchors, vec!["intro", "details"]);
+        assert_eq!(post.toc[1].level, 2);
+        assert_eq!(post.word_count, 252);
+        assert_eq!(post.reading_time_minutes, 2);
+
+        let summary: PostSummary = (&post).into();
+        assert_eq!(summary.word_count, 252);
+        assert_eq!(summary.reading_time_minutes, 2);
+    }
+
+    fn create_colliding_content(temp_dir: &TempDir) {
+        let content_dir = temp_dir.path().join("content");
+        create_post_files(&content_dir.join("intro"), "Standalone", "P", "# Hi");
+        for series in ["alpha", "beta"] {
+            let series_dir = content_dir.join(series);
+            fs::create_dir_all(&series_dir).unwrap();
+            fs::write(series_dir.join("series.toml"), "title = \"Series\"").unwrap();
+            create_post_files(&series_dir.join("intro"), series, "P", "# Hi");
+        }
+    }
+
+    #[test]
+    fn test_slug_collisions_are_reported() {
+        let temp_dir = TempDir::new().unwrap();
+        create_colliding_content(&temp_dir);
+
+        let cache = ContentCache::load(&create_content_config(&temp_dir)).unwrap();
+
+        // Nothing is overwritten, and the standalone post owns the bare slug
+        assert_eq!(cache.posts.len(), 3);
+        assert_eq!(
+            cache.get_post("intro").unwrap().unwrap().title,
+            "Standalone"
+        );
+        assert_eq!(
+            cache
+                .get_series_post("beta", "intro")
+                .unwrap()
+                .unwrap()
+                .title,
+            "beta"
+        );
+        assert_eq!(cache.get_series("alpha").unwrap().unwrap().posts.len(), 1);
+
+        assert!(
+            cache
+                .diagnostics()
+                .iter()
+                .all(|d| d.kind == DiagnosticKind::SlugCollision && !d.is_error())
+        );
+        let errors = cache.validate();
+        let paths: Vec<&str> = errors.iter().map(|e| e.path.as_str()).collect();
+        assert_eq!(paths, vec!["alpha/intro", "beta/intro"]);
+        assert!(errors[0].message.contains("already used by 'intro'"));
+    }
+
+    #[test]
+    fn test_namespaced_addressing_allows_shared_slugs() {
+        let temp_dir = TempDir::new().unwrap();
+        create_colliding_content(&temp_dir);
+
+        let config = ContentConfig {
+            post_addressing: PostAddressing::Namespaced,
+            ..create_content_config(&temp_dir)
+        };
+        let cache = ContentCache::load(&config).unwrap();
+
+        assert!(cache.validate().is_empty());
+        assert_eq!(
+            cache.get_post("intro").unwrap().unwrap().title,
+            "Standalone"
+        );
+        assert_eq!(
+            cache
+                .get_series_post("alpha", "intro")
+                .unwrap()
+                .unwrap()
+                .title,
+            "alpha"
+        );
+        assert!(cache.get_series_post("alpha", "missing").unwrap().is_none());
     }
 }
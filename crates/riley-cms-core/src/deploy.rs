@@ -0,0 +1,143 @@
+//! Per-commit deploy directories (`content.source = "deploy"`)
+//!
+//! Each commit is checked out once into `<deploy dir>/<sha>`: into a scratch
+//! directory first, then renamed into place, so a deploy directory is either
+//! absent or complete and never changes afterwards. A refresh loads from the
+//! directory of the commit it deploys, so it can't see a mix of two commits.
+
+use crate::config::ContentConfig;
+use crate::error::{Error, Result};
+use crate::git;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// Name of the deploy directory in the repository's git directory
+const DEFAULT_DIR_NAME: &str = "riley-cms-deploys";
+
+/// Prefix of scratch directories that haven't been renamed into place yet
+const STAGING_PREFIX: &str = ".staging-";
+
+/// A commit checked out into its deploy directory
+#[derive(Debug)]
+pub(crate) struct Deployment {
+    pub commit: String,
+    pub path: PathBuf,
+}
+
+/// Where deploy directories live: `content.deploy.dir`, or the repository's git directory
+pub(crate) fn deploy_dir(config: &ContentConfig) -> PathBuf {
+    config
+        .deploy
+        .dir
+        .clone()
+        .unwrap_or_else(|| git::git_dir(&config.repo_path).join(DEFAULT_DIR_NAME))
+}
+
+/// Check out `config.git_ref`'s commit into its deploy directory, reusing it if it exists.
+///
+/// Returns `None` if the ref doesn't resolve to a commit, e.g. in a repository
+/// without commits.
+pub(crate) fn checkout(config: &ContentConfig) -> Result<Option<Deployment>> {
+    let commit = match run(git(&config.repo_path)
+        .args(["rev-parse", "--verify", "--quiet"])
+        .arg(format!("{}^{{commit}}", config.git_ref)))
+    {
+        Ok(out) => String::from_utf8_lossy(&out).trim().to_string(),
+        Err(_) => return Ok(None),
+    };
+
+    let dir = deploy_dir(config);
+    let path = dir.join(&commit);
+    if path.is_dir() {
+        return Ok(Some(Deployment { commit, path }));
+    }
+
+    fs::create_dir_all(&dir)?;
+    let staging = tempfile::Builder::new()
+        .prefix(&format!("{}{}-", STAGING_PREFIX, commit))
+        .tempdir_in(&dir)?;
+    let tree = staging.path().join("tree");
+    fs::create_dir(&tree)?;
+    let index = staging.path().join("index");
+    run(git(&config.repo_path)
+        .env("GIT_INDEX_FILE", &index)
+        .args(["read-tree", &commit]))?;
+    run(git(&config.repo_path)
+        .env("GIT_INDEX_FILE", &index)
+        .arg("--work-tree")
+        .arg(&tree)
+        .args(["checkout-index", "--all"]))?;
+
+    match fs::rename(&tree, &path) {
+        Ok(()) => {}
+        // A concurrent refresh deployed the same commit first
+        Err(_) if path.is_dir() => {}
+        Err(e) => return Err(e.into()),
+    }
+    tracing::info!("Deployed {} to {}", commit, path.display());
+    Ok(Some(Deployment { commit, path }))
+}
+
+/// Remove all but the `content.deploy.keep` most recent deploy directories.
+///
+/// `live` (the directory just loaded) is always kept. Leftover scratch
+/// directories are left to their owners.
+pub(crate) fn prune(config: &ContentConfig, live: &Path) {
+    let dir = deploy_dir(config);
+    let entries = match fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            tracing::warn!("Failed to list deploy directory {}: {}", dir.display(), e);
+            return;
+        }
+    };
+
+    let mut deploys: Vec<(std::time::SystemTime, PathBuf)> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| {
+            !entry
+                .file_name()
+                .to_string_lossy()
+                .starts_with(STAGING_PREFIX)
+        })
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+        .filter(|entry| entry.path() != live)
+        .filter_map(|entry| {
+            let modified = entry.metadata().and_then(|m| m.modified()).ok()?;
+            Some((modified, entry.path()))
+        })
+        .collect();
+    // Newest first; the live directory takes one of the `keep` slots
+    deploys.sort_by_key(|d| std::cmp::Reverse(d.0));
+    for (_, path) in deploys
+        .into_iter()
+        .skip(config.deploy.keep.saturating_sub(1))
+    {
+        match fs::remove_dir_all(&path) {
+            Ok(()) => tracing::info!("Pruned deploy {}", path.display()),
+            Err(e) => tracing::warn!("Failed to prune deploy {}: {}", path.display(), e),
+        }
+    }
+}
+
+/// A git command in `repo_path`
+fn git(repo_path: &Path) -> Command {
+    let mut command = Command::new("git");
+    command.arg("-C").arg(repo_path).stdin(Stdio::null());
+    command
+}
+
+/// Run a git command to completion, returning its stdout
+fn run(command: &mut Command) -> Result<Vec<u8>> {
+    let output = command
+        .output()
+        .map_err(|e| Error::Git(format!("Failed to run git: {}", e)))?;
+    if !output.status.success() {
+        return Err(Error::Git(format!(
+            "git failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(output.stdout)
+}
@@ -31,6 +31,15 @@ pub enum Error {
     #[error("Series not found: {0}")]
     SeriesNotFound(String),
 
+    #[error("Refresh rejected: {0}")]
+    RefreshRejected(String),
+
+    #[error("Branch is not configured for preview: {0}")]
+    PreviewNotAllowed(String),
+
+    #[error("Invalid webhook signature: {0}")]
+    InvalidWebhookSignature(String),
+
     #[error("Storage error: {0}")]
     Storage(String),
 
@@ -0,0 +1,475 @@
+//! RSS 2.0, Atom and JSON Feed generation
+//!
+//! Feeds only ever contain live posts. Posts inside a series that is not live
+//! yet are left out as well, so a feed never leaks unpublished content.
+
+use crate::config::FeedConfig;
+use crate::content::{ContentCache, body_etag, tag_key};
+use crate::links;
+use crate::types::Post;
+use chrono::{DateTime, SecondsFormat, Utc};
+use std::fmt::Write;
+
+/// Syndication format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeedFormat {
+    /// RSS 2.0
+    Rss,
+    /// Atom 1.0
+    Atom,
+    /// JSON Feed 1.1
+    Json,
+}
+
+impl FeedFormat {
+    /// `Content-Type` header value for this format
+    pub fn content_type(self) -> &'static str {
+        match self {
+            FeedFormat::Rss => "application/rss+xml; charset=utf-8",
+            FeedFormat::Atom => "application/atom+xml; charset=utf-8",
+            FeedFormat::Json => "application/feed+json; charset=utf-8",
+        }
+    }
+}
+
+/// Which posts a feed covers. Both filters may be combined.
+#[derive(Debug, Clone, Default)]
+pub struct FeedOptions {
+    /// Only posts in this series
+    pub series: Option<String>,
+    /// Only posts with this tag (case-insensitive)
+    pub tag: Option<String>,
+}
+
+/// A rendered feed
+#[derive(Debug, Clone)]
+pub struct Feed {
+    pub format: FeedFormat,
+    pub body: String,
+    /// Quoted strong ETag of `body`
+    pub etag: String,
+}
+
+/// Feed-level metadata shared by all formats
+struct Channel<'a> {
+    title: String,
+    link: &'a str,
+    description: Option<&'a str>,
+    author: Option<&'a str>,
+    updated: DateTime<Utc>,
+}
+
+/// A single feed entry
+struct Item<'a> {
+    post: &'a Post,
+    url: String,
+    published: DateTime<Utc>,
+    updated: DateTime<Utc>,
+}
+
+/// Build a feed from the cache.
+///
+/// Returns `None` if `opts.series` names a series that does not exist or is not live.
+pub(crate) fn build(
+    cache: &ContentCache,
+    config: &FeedConfig,
+    format: FeedFormat,
+    opts: &FeedOptions,
+) -> Option<Feed> {
+    let mut title = config.title.clone();
+    if let Some(ref series_slug) = opts.series {
+        let series = cache.published_series(series_slug)?;
+        title = format!("{}: {}", title, series.title);
+    }
+    if let Some(ref tag) = opts.tag {
+        title = format!("{}: {}", title, tag);
+    }
+
+    let items: Vec<Item> = cache
+        .published_posts()
+        .into_iter()
+        .filter(|post| {
+            opts.series
+                .as_deref()
+                .is_none_or(|s| post.series_slug.as_deref() == Some(s))
+        })
+        .filter(|post| {
+            opts.tag.as_deref().is_none_or(|tag| {
+                let wanted = tag_key(tag);
+                post.tags
+                    .as_ref()
+                    .is_some_and(|tags| tags.iter().any(|t| tag_key(t) == wanted))
+            })
+        })
+        .take(config.item_count)
+        .map(|post| item_for(config, post))
+        .collect();
+
+    let link = config.base_url.trim_end_matches('/');
+    let channel = Channel {
+        title,
+        link,
+        description: config.description.as_deref(),
+        author: config.author.as_deref(),
+        // Derived from the items (not the clock) so unchanged feeds keep their ETag
+        updated: items
+            .iter()
+            .map(|i| i.updated)
+            .max()
+            .unwrap_or(DateTime::UNIX_EPOCH),
+    };
+
+    let body = match format {
+        FeedFormat::Rss => render_rss(&channel, &items),
+        FeedFormat::Atom => render_atom(&channel, &items),
+        FeedFormat::Json => render_json(&channel, &items),
+    };
+    let etag = body_etag(&body);
+
+    Some(Feed { format, body, etag })
+}
+
+fn item_for<'a>(config: &FeedConfig, post: &'a Post) -> Item<'a> {
+    let template = match post.series_slug {
+        Some(_) => config
+            .series_post_url
+            .as_deref()
+            .unwrap_or(&config.post_url),
+        None => &config.post_url,
+    };
+    // published_posts() only returns posts with a goes_live_at
+    let published = post.goes_live_at.unwrap_or(DateTime::UNIX_EPOCH);
+    Item {
+        url: links::expand(
+            template,
+            &config.base_url,
+            &post.slug,
+            post.series_slug.as_deref(),
+        ),
+        published,
+        updated: post.last_modified.map_or(published, |m| m.max(published)),
+        post,
+    }
+}
+
+fn render_rss(channel: &Channel, items: &[Item]) -> String {
+    let mut out = String::new();
+    out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str(
+        "<rss version=\"2.0\" xmlns:dc=\"http://purl.org/dc/elements/1.1/\">\n<channel>\n",
+    );
+    let _ = writeln!(out, "<title>{}</title>", escape(&channel.title));
+    let _ = writeln!(out, "<link>{}</link>", escape(channel.link));
+    let _ = writeln!(
+        out,
+        "<description>{}</description>",
+        escape(channel.description.unwrap_or(&channel.title))
+    );
+    let _ = writeln!(
+        out,
+        "<lastBuildDate>{}</lastBuildDate>",
+        channel.updated.to_rfc2822()
+    );
+
+    for item in items {
+        let post = item.post;
+        out.push_str("<item>\n");
+        let _ = writeln!(out, "<title>{}</title>", escape(&post.title));
+        let _ = writeln!(out, "<link>{}</link>", escape(&item.url));
+        let _ = writeln!(
+            out,
+            "<guid isPermaLink=\"true\">{}</guid>",
+            escape(&item.url)
+        );
+        let _ = writeln!(
+            out,
+            "<description>{}</description>",
+            escape(&post.preview_text)
+        );
+        let _ = writeln!(out, "<pubDate>{}</pubDate>", item.published.to_rfc2822());
+        if let Some(author) = channel.author {
+            let _ = writeln!(out, "<dc:creator>{}</dc:creator>", escape(author));
+        }
+        for tag in post.tags.iter().flatten() {
+            let _ = writeln!(out, "<category>{}</category>", escape(tag));
+        }
+        out.push_str("</item>\n");
+    }
+
+    out.push_str("</channel>\n</rss>\n");
+    out
+}
+
+fn render_atom(channel: &Channel, items: &[Item]) -> String {
+    let mut out = String::new();
+    out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+    let _ = writeln!(out, "<title>{}</title>", escape(&channel.title));
+    if let Some(description) = channel.description {
+        let _ = writeln!(out, "<subtitle>{}</subtitle>", escape(description));
+    }
+    let _ = writeln!(out, "<id>{}/</id>", escape(channel.link));
+    let _ = writeln!(out, "<link href=\"{}\"/>", escape(channel.link));
+    let _ = writeln!(out, "<updated>{}</updated>", rfc3339(channel.updated));
+    // Atom requires an author on the feed when entries don't carry their own
+    let _ = writeln!(
+        out,
+        "<author><name>{}</name></author>",
+        escape(channel.author.unwrap_or(&channel.title))
+    );
+
+    for item in items {
+        let post = item.post;
+        out.push_str("<entry>\n");
+        let _ = writeln!(out, "<title>{}</title>", escape(&post.title));
+        let _ = writeln!(out, "<id>{}</id>", escape(&item.url));
+        let _ = writeln!(
+            out,
+            "<link rel=\"alternate\" href=\"{}\"/>",
+            escape(&item.url)
+        );
+        let _ = writeln!(out, "<published>{}</published>", rfc3339(item.published));
+        let _ = writeln!(out, "<updated>{}</updated>", rfc3339(item.updated));
+        let _ = writeln!(out, "<summary>{}</summary>", escape(&post.preview_text));
+        for tag in post.tags.iter().flatten() {
+            let _ = writeln!(out, "<category term=\"{}\"/>", escape(tag));
+        }
+        out.push_str("</entry>\n");
+    }
+
+    out.push_str("</feed>\n");
+    out
+}
+
+fn render_json(channel: &Channel, items: &[Item]) -> String {
+    let items: Vec<serde_json::Value> = items
+        .iter()
+        .map(|item| {
+            let post = item.post;
+            let mut value = serde_json::json!({
+                "id": item.url,
+                "url": item.url,
+                "title": post.title,
+                "summary": post.preview_text,
+                "content_text": post.preview_text,
+                "date_published": rfc3339(item.published),
+                "date_modified": rfc3339(item.updated),
+            });
+            if let Some(ref tags) = post.tags {
+                value["tags"] = serde_json::json!(tags);
+            }
+            if let Some(ref image) = post.preview_image {
+                value["image"] = serde_json::json!(image);
+            }
+            value
+        })
+        .collect();
+
+    let mut feed = serde_json::json!({
+        "version": "https://jsonfeed.org/version/1.1",
+        "title": channel.title,
+        "home_page_url": channel.link,
+        "items": items,
+    });
+    if let Some(description) = channel.description {
+        feed["description"] = serde_json::json!(description);
+    }
+    if let Some(author) = channel.author {
+        feed["authors"] = serde_json::json!([{ "name": author }]);
+    }
+
+    serde_json::to_string_pretty(&feed).unwrap_or_default()
+}
+
+fn rfc3339(date: DateTime<Utc>) -> String {
+    date.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// Escape text for XML element content and attribute values
+pub(crate) fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            // Control characters other than tab/newline/CR are not allowed in XML 1.0
+            c if c.is_control() && !matches!(c, '\t' | '\n' | '\r') => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ContentConfig;
+    use riley_cms_testing::{PostFixture, SeriesFixture, TestContent};
+
+    fn feed_config() -> FeedConfig {
+        FeedConfig {
+            title: "Blog".to_string(),
+            base_url: "https://example.com/".to_string(),
+            description: Some("All the posts".to_string()),
+            author: Some("Riley".to_string()),
+            item_count: 20,
+            post_url: "{base_url}/posts/{slug}".to_string(),
+            series_post_url: Some("{base_url}/{series_slug}/{slug}".to_string()),
+        }
+    }
+
+    fn load(content: &TestContent) -> ContentCache {
+        ContentCache::load(&ContentConfig::for_tests(content.path())).unwrap()
+    }
+
+    fn fixture() -> TestContent {
+        TestContent::new()
+            .with_post(
+                PostFixture::new("old", "Old & <Busted>")
+                    .goes_live_at("2024-01-01T00:00:00Z")
+                    .tags(&["rust"]),
+            )
+            .with_post(
+                PostFixture::new("new", "New")
+                    .goes_live_at("2024-06-01T00:00:00Z")
+                    .tags(&["Web"]),
+            )
+            .with_post(PostFixture::new("draft", "Draft").tags(&["rust"]))
+            .with_post(
+                PostFixture::new("scheduled", "Scheduled")
+                    .goes_live_at("2099-01-01T00:00:00Z")
+                    .tags(&["rust"]),
+            )
+            .with_series(
+                SeriesFixture::new("live-series", "Live Series")
+                    .goes_live_at("2024-01-01T00:00:00Z")
+                    .post(
+                        PostFixture::new("part-1", "Part 1").goes_live_at("2024-03-01T00:00:00Z"),
+                    ),
+            )
+            .with_series(
+                SeriesFixture::new("draft-series", "Draft Series").post(
+                    PostFixture::new("secret", "Secret")
+                        .goes_live_at("2024-03-01T00:00:00Z")
+                        .tags(&["rust"]),
+                ),
+            )
+    }
+
+    #[test]
+    fn test_rss_contains_only_live_posts_newest_first() {
+        let content = fixture();
+        let cache = load(&content);
+        let feed = build(
+            &cache,
+            &feed_config(),
+            FeedFormat::Rss,
+            &FeedOptions::default(),
+        )
+        .unwrap();
+
+        let body = &feed.body;
+        assert!(body.contains("<title>Blog</title>"));
+        assert!(body.contains("<link>https://example.com/posts/new</link>"));
+        assert!(body.contains("<link>https://example.com/live-series/part-1</link>"));
+        assert!(body.contains("Old &amp; &lt;Busted&gt;"));
+        assert!(!body.contains("Draft"));
+        assert!(!body.contains("Scheduled"));
+        assert!(!body.contains("Secret"));
+
+        let new = body.find("posts/new").unwrap();
+        let part = body.find("live-series/part-1").unwrap();
+        let old = body.find("posts/old").unwrap();
+        assert!(new < part && part < old);
+    }
+
+    #[test]
+    fn test_atom_and_json_formats() {
+        let content = fixture();
+        let cache = load(&content);
+        let config = feed_config();
+
+        let atom = build(&cache, &config, FeedFormat::Atom, &FeedOptions::default()).unwrap();
+        assert!(
+            atom.body
+                .contains("<feed xmlns=\"http://www.w3.org/2005/Atom\">")
+        );
+        assert!(atom.body.contains("<author><name>Riley</name></author>"));
+        assert!(
+            atom.body
+                .contains("<published>2024-06-01T00:00:00Z</published>")
+        );
+
+        let json = build(&cache, &config, FeedFormat::Json, &FeedOptions::default()).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json.body).unwrap();
+        assert_eq!(value["version"], "https://jsonfeed.org/version/1.1");
+        assert_eq!(value["items"].as_array().unwrap().len(), 3);
+        assert_eq!(value["items"][0]["url"], "https://example.com/posts/new");
+        assert_eq!(value["authors"][0]["name"], "Riley");
+    }
+
+    #[test]
+    fn test_series_and_tag_filters() {
+        let content = fixture();
+        let cache = load(&content);
+        let config = feed_config();
+
+        let series = build(
+            &cache,
+            &config,
+            FeedFormat::Json,
+            &FeedOptions {
+                series: Some("live-series".to_string()),
+                tag: None,
+            },
+        )
+        .unwrap();
+        let value: serde_json::Value = serde_json::from_str(&series.body).unwrap();
+        assert_eq!(value["title"], "Blog: Live Series");
+        assert_eq!(value["items"].as_array().unwrap().len(), 1);
+
+        // Series that are not live have no feed
+        let hidden = FeedOptions {
+            series: Some("draft-series".to_string()),
+            tag: None,
+        };
+        assert!(build(&cache, &config, FeedFormat::Rss, &hidden).is_none());
+
+        let tagged = build(
+            &cache,
+            &config,
+            FeedFormat::Json,
+            &FeedOptions {
+                series: None,
+                tag: Some("web".to_string()),
+            },
+        )
+        .unwrap();
+        let value: serde_json::Value = serde_json::from_str(&tagged.body).unwrap();
+        assert_eq!(value["items"].as_array().unwrap().len(), 1);
+        assert_eq!(value["items"][0]["title"], "New");
+    }
+
+    #[test]
+    fn test_item_count_and_stable_etag() {
+        let content = fixture();
+        let cache = load(&content);
+        let config = FeedConfig {
+            item_count: 1,
+            ..feed_config()
+        };
+
+        let a = build(&cache, &config, FeedFormat::Rss, &FeedOptions::default()).unwrap();
+        let b = build(&cache, &config, FeedFormat::Rss, &FeedOptions::default()).unwrap();
+        assert_eq!(a.body.matches("<item>").count(), 1);
+        assert_eq!(a.etag, b.etag);
+    }
+
+    #[test]
+    fn test_escape_strips_invalid_control_chars() {
+        assert_eq!(escape("a\u{1}b\"'"), "ab&quot;&apos;");
+    }
+}
@@ -8,7 +8,9 @@
 //! CGI output back to the client, avoiding buffering large payloads in memory.
 
 use crate::error::{Error, Result};
+use crate::types::Revision;
 use bytes::Bytes;
+use chrono::{DateTime, Utc};
 use futures_util::StreamExt;
 use std::collections::HashMap;
 use std::io;
@@ -328,10 +330,150 @@ impl GitBackend {
         })
     }
 
+    /// Path to the repository
+    pub(crate) fn repo_path(&self) -> &Path {
+        &self.repo_path
+    }
+
     /// Check if the repository exists and is a valid Git repository
     pub fn is_valid_repo(&self) -> bool {
         self.repo_path.join(".git").exists() || self.repo_path.join("HEAD").exists()
     }
+
+    /// List up to `limit` commits touching `path` (relative to the repository root), newest first.
+    ///
+    /// History is read up to `rev`, or `HEAD` if `None`.
+    pub async fn history(
+        &self,
+        path: &str,
+        rev: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<Revision>> {
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(&self.repo_path)
+            .arg("log")
+            .arg(format!("--max-count={}", limit))
+            .arg(format!("--format={}", REVISION_FORMAT))
+            .args(rev)
+            .arg("--")
+            .arg(path)
+            .output()
+            .await
+            .map_err(|e| Error::Git(format!("Failed to run git log: {}", e)))?;
+
+        if !output.status.success() {
+            return Err(Error::Git(format!(
+                "git log failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        Ok(parse_revisions(&String::from_utf8_lossy(&output.stdout)))
+    }
+}
+
+/// The git directory of a repository: `.git` of a working tree, or a bare repository itself
+pub(crate) fn git_dir(repo_path: &Path) -> std::path::PathBuf {
+    let dot_git = repo_path.join(".git");
+    if dot_git.is_dir() {
+        dot_git
+    } else {
+        repo_path.to_path_buf()
+    }
+}
+
+// === History ===
+
+/// `git log` format for [`Revision`]s: record separator, then unit-separated fields
+const REVISION_FORMAT: &str = "%x1e%H%x1f%an%x1f%aI%x1f%s";
+
+fn parse_revisions(log: &str) -> Vec<Revision> {
+    log.split('\x1e')
+        .filter_map(|record| {
+            let mut fields = record.trim_end_matches('\n').split('\x1f');
+            let sha = fields.next()?.to_string();
+            let author = fields.next()?.to_string();
+            let date = DateTime::parse_from_rfc3339(fields.next()?)
+                .ok()?
+                .with_timezone(&Utc);
+            let message = fields.next()?.to_string();
+            Some(Revision {
+                sha,
+                author,
+                date,
+                message,
+            })
+        })
+        .collect()
+}
+
+/// `(first commit, latest commit)` dates of each directory, keyed by repo-relative path
+pub(crate) type DirectoryDates = HashMap<String, (DateTime<Utc>, DateTime<Utc>)>;
+
+/// First and last commit dates of every directory with committed files under `path`.
+///
+/// Keys are `/`-separated paths relative to the repository root. History is
+/// read up to `rev`, or `HEAD` of the working tree if `None`, in which case
+/// an empty map is returned if `repo_path` is not a git working tree. Runs a
+/// single `git log` over the whole history, so call it once per content load.
+pub(crate) fn directory_dates(
+    repo_path: &Path,
+    path: &str,
+    rev: Option<&str>,
+) -> Result<DirectoryDates> {
+    let mut dates = DirectoryDates::new();
+    if rev.is_none() && !repo_path.join(".git").exists() {
+        return Ok(dates);
+    }
+
+    let output = std::process::Command::new("git")
+        .arg("-C")
+        .arg(repo_path)
+        .args([
+            "-c",
+            "core.quotepath=off",
+            "log",
+            "--format=%x1e%aI",
+            "--name-only",
+        ])
+        .args(rev)
+        .arg("--")
+        .arg(if path.is_empty() { "." } else { path })
+        .output()
+        .map_err(|e| Error::Git(format!("Failed to run git log: {}", e)))?;
+
+    if !output.status.success() {
+        // A repository without commits has no history yet
+        tracing::debug!(
+            "git log failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+        return Ok(dates);
+    }
+
+    // Commits are listed newest first
+    for record in String::from_utf8_lossy(&output.stdout).split('\x1e') {
+        let mut lines = record.lines();
+        let Some(date) = lines
+            .next()
+            .and_then(|d| DateTime::parse_from_rfc3339(d).ok())
+            .map(|d| d.with_timezone(&Utc))
+        else {
+            continue;
+        };
+        for file in lines.filter(|l| !l.is_empty()) {
+            let mut dir = file;
+            while let Some((parent, _)) = dir.rsplit_once('/') {
+                dates
+                    .entry(parent.to_string())
+                    .and_modify(|(created, _)| *created = date)
+                    .or_insert((date, date));
+                dir = parent;
+            }
+        }
+    }
+
+    Ok(dates)
 }
 
 /// Read CGI headers from a buffered reader.
@@ -552,6 +694,18 @@ fn parse_cgi_response(data: &[u8]) -> Result<GitCgiResponse> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_revisions() {
+        let log = "\x1eabc123\x1fRiley\x1f2024-03-01T12:00:00+02:00\x1fFix typo | again\n\x1edef456\x1fSam\x1f2024-01-01T00:00:00Z\x1fInitial\n";
+        let revisions = parse_revisions(log);
+        assert_eq!(revisions.len(), 2);
+        assert_eq!(revisions[0].sha, "abc123");
+        assert_eq!(revisions[0].author, "Riley");
+        assert_eq!(revisions[0].message, "Fix typo | again");
+        assert_eq!(revisions[0].date.to_rfc3339(), "2024-03-01T10:00:00+00:00");
+        assert_eq!(revisions[1].sha, "def456");
+    }
+
     #[test]
     fn test_parse_cgi_response_basic() {
         let data = b"Content-Type: application/x-git-upload-pack-advertisement\r\n\r\nHello";
@@ -8,7 +8,7 @@
 //! ## Features
 //!
 //! - **Content Management**: Parse and query posts and series from a Git-based content directory
-//! - **S3/R2 Storage**: Upload and list assets from S3-compatible storage
+//! - **Pluggable Storage**: Upload and list assets in S3/R2 or a local directory
 //! - **In-Memory Caching**: Fast content access with cache refresh on demand
 //! - **Visibility Control**: Support for drafts, scheduled posts, and live content
 //!
@@ -67,28 +67,52 @@
 //! - `Some(past_date)` → Live (always visible)
 //! - `Some(future_date)` → Scheduled (only visible with `include_scheduled`)
 
+mod audit;
 mod config;
 mod content;
+mod deploy;
 mod error;
+mod feed;
 pub mod git;
+mod links;
+mod mdx;
+mod preview;
+mod push;
+mod render;
+mod scheduler;
+mod search;
 mod security;
+mod sitemap;
+mod source;
 mod storage;
 mod types;
-
-pub use config::{Config, GitConfig, RileyCmsConfig, resolve_config};
+mod webhooks;
+mod worker;
+
+pub use config::{
+    BranchProtectionConfig, Config, ContentSource, DeployConfig, FeedConfig, GitConfig,
+    GitCredentialConfig, GitScope, JsxMode, PostAddressing, PreviewConfig, PushPolicyConfig,
+    RefreshConfig, RefreshPolicy, RenderConfig, RileyCmsConfig, SitemapConfig,
+    WebhookEndpointConfig, WebhooksConfig, resolve_config,
+};
 pub use content::ContentCache;
 pub use error::{Error, Result};
+pub use feed::{Feed, FeedFormat, FeedOptions};
 pub use git::{BodyStream, GitBackend, GitCgiCompletion, GitCgiHeaders, GitCgiStreamResponse};
-pub use storage::Storage;
+pub use push::{PushRejection, ReceivePack, RefUpdate};
+pub use sitemap::Sitemap;
+pub use storage::{LocalStorage, MemoryStorage, S3Storage, Storage};
 pub use types::*;
+pub use webhooks::{WEBHOOK_SIGNATURE_HEADER, WEBHOOK_TIMESTAMP_HEADER, verify_webhook_signature};
 
 use chrono::Utc;
-use hmac::{Hmac, Mac};
-use sha2::Sha256;
-use std::net::ToSocketAddrs;
+use preview::Previews;
+use scheduler::Scheduler;
 use std::path::Path;
 use std::sync::Arc;
 use tokio::sync::RwLock;
+use webhooks::{DeliveryWorker, WebhookQueue};
+use worker::{RefreshWorker, Refresher};
 
 /// Main entry point for riley_cms functionality.
 ///
@@ -117,20 +141,37 @@ use tokio::sync::RwLock;
 pub struct RileyCms {
     config: RileyCmsConfig,
     cache: Arc<RwLock<ContentCache>>,
-    storage: Storage,
+    storage: Arc<dyn Storage>,
+    refresher: Refresher,
+    worker: RefreshWorker,
+    previews: Previews,
+    webhooks: Arc<WebhookQueue>,
+    _scheduler: Scheduler,
+    /// Started by [`start_background_tasks`](Self::start_background_tasks)
+    deliveries: std::sync::Mutex<Option<DeliveryWorker>>,
 }
 
 impl RileyCms {
     /// Create a new RileyCms instance from configuration.
     ///
     /// This loads content from disk into an in-memory cache and initializes
-    /// the S3 storage client.
+    /// the storage backend selected by `storage.backend`.
     ///
     /// # Errors
     ///
-    /// Returns an error if content cannot be loaded or S3 configuration is invalid.
+    /// Returns an error if content cannot be loaded or the storage configuration is invalid.
     pub async fn from_config(config: RileyCmsConfig) -> Result<Self> {
-        let storage = Storage::new(&config.storage).await?;
+        let storage = storage::from_config(&config.storage).await?;
+        Self::with_storage(config, storage).await
+    }
+
+    /// Create a new RileyCms instance with an explicit storage backend.
+    ///
+    /// The `[storage]` config section is ignored. Useful for embedding riley_cms
+    /// with a custom [`Storage`] implementation, or for tests that want to inspect
+    /// a [`MemoryStorage`] after the fact.
+    pub async fn with_storage(config: RileyCmsConfig, storage: Arc<dyn Storage>) -> Result<Self> {
+        let webhooks = Arc::new(WebhookQueue::new(&config)?);
 
         // Clone content config to move into the blocking task closure
         let content_config = config.content.clone();
@@ -140,13 +181,43 @@ impl RileyCms {
             .await
             .map_err(|e| Error::Io(std::io::Error::other(e)))??;
 
+        let cache = Arc::new(RwLock::new(cache));
+        let refresher = Refresher {
+            config: config.clone(),
+            cache: cache.clone(),
+            lock: Arc::new(tokio::sync::Mutex::new(())),
+            reloaded: Arc::new(tokio::sync::Notify::new()),
+            webhooks: webhooks.clone(),
+        };
+        let worker = RefreshWorker::spawn(refresher.clone()).await;
+        let scheduler =
+            Scheduler::spawn(cache.clone(), refresher.reloaded.clone(), webhooks.clone());
+        let previews = Previews::new(&config.content.preview);
         Ok(Self {
             config,
-            cache: Arc::new(RwLock::new(cache)),
+            cache,
             storage,
+            refresher,
+            worker,
+            previews,
+            webhooks,
+            _scheduler: scheduler,
+            deliveries: std::sync::Mutex::new(None),
         })
     }
 
+    /// Start sending queued webhook deliveries in the background.
+    ///
+    /// Long-running servers call this once; until then deliveries are only
+    /// queued, so one-shot commands leave sending to the server. Calling it
+    /// again, or without `[webhooks]` configured, does nothing.
+    pub fn start_background_tasks(&self) {
+        let mut deliveries = self.deliveries.lock().unwrap_or_else(|e| e.into_inner());
+        if deliveries.is_none() && self.config.webhooks.is_some() {
+            *deliveries = Some(DeliveryWorker::spawn(self.webhooks.clone()));
+        }
+    }
+
     /// List posts with filtering and pagination.
     ///
     /// By default, only live posts (with `goes_live_at` in the past) are returned.
@@ -158,6 +229,124 @@ impl RileyCms {
         cache.list_posts(opts)
     }
 
+    /// Full-text search over posts.
+    ///
+    /// Matches title, subtitle, tags, preview text and body. Every query term
+    /// must match; the last term also matches as a prefix. Results are ranked
+    /// by relevance and respect the same visibility rules as [`list_posts`](Self::list_posts).
+    pub async fn search(
+        &self,
+        query: &str,
+        opts: &ListOptions,
+    ) -> Result<ListResult<SearchResult>> {
+        let cache = self.cache.read().await;
+        cache.search(query, opts)
+    }
+
+    /// Get a post within a series.
+    ///
+    /// Works in either [`PostAddressing`] mode; in `namespaced` mode it is the
+    /// only way to fetch a series post. Like [`get_post`](Self::get_post), this
+    /// ignores visibility.
+    pub async fn get_series_post(&self, series_slug: &str, slug: &str) -> Result<Option<Post>> {
+        let cache = self.cache.read().await;
+        cache.get_series_post(series_slug, slug)
+    }
+
+    /// List up to `limit` commits touching a post's directory, newest first.
+    ///
+    /// Returns `None` if no post with the given slug exists, and an empty list
+    /// if the content repository isn't a git repository. History is read from
+    /// the commit the content was loaded from (see
+    /// [`content_revision`](Self::content_revision)), or `HEAD` of the working
+    /// tree. Like [`get_post`](Self::get_post), this ignores visibility.
+    pub async fn post_history(&self, slug: &str, limit: usize) -> Result<Option<Vec<Revision>>> {
+        let (dir, revision) = {
+            let cache = self.cache.read().await;
+            match cache.get_post(slug)? {
+                Some(post) => (
+                    content::post_dir(&self.config.content.content_dir, &post),
+                    cache.revision().map(String::from),
+                ),
+                None => return Ok(None),
+            }
+        };
+        let backend = GitBackend::new(&self.config.content.repo_path);
+        if !backend.is_valid_repo() {
+            return Ok(Some(Vec::new()));
+        }
+        backend
+            .history(&dir, revision.as_deref(), limit)
+            .await
+            .map(Some)
+    }
+
+    /// Append the ref updates of an accepted push to the push audit log.
+    ///
+    /// Call after `git-receive-pack` finishes. Updates the repository didn't
+    /// apply (its ref doesn't point where the push asked) are left out.
+    /// Returns the records written.
+    pub async fn record_push(&self, user: &str, updates: &[RefUpdate]) -> Result<Vec<PushRecord>> {
+        let backend = GitBackend::new(&self.config.content.repo_path);
+        let mut records = Vec::new();
+        for update in updates {
+            let target = backend.ref_target(&update.refname).await;
+            let applied = if update.is_delete() {
+                target.is_none()
+            } else {
+                target.as_deref() == Some(update.new.as_str())
+            };
+            if !applied {
+                continue;
+            }
+            let old_sha = (!update.is_create()).then(|| update.old.clone());
+            let new_sha = (!update.is_delete()).then(|| update.new.clone());
+            let changed_posts = backend
+                .changed_posts(
+                    old_sha.as_deref(),
+                    new_sha.as_deref(),
+                    &self.config.content.content_dir,
+                )
+                .await
+                .unwrap_or_else(|e| {
+                    tracing::warn!("Failed to list posts changed by {}: {}", update.refname, e);
+                    Vec::new()
+                });
+            records.push(PushRecord {
+                pushed_at: Utc::now(),
+                user: user.to_string(),
+                refname: update.refname.clone(),
+                old_sha,
+                new_sha,
+                changed_posts,
+            });
+        }
+
+        let path = audit::log_path(&self.config);
+        let written = records.clone();
+        tokio::task::spawn_blocking(move || audit::append(&path, &written))
+            .await
+            .map_err(|e| Error::Io(std::io::Error::other(e)))??;
+        Ok(records)
+    }
+
+    /// Up to `limit` entries from the push audit log, newest first
+    pub async fn list_pushes(&self, limit: usize) -> Result<Vec<PushRecord>> {
+        let path = audit::log_path(&self.config);
+        tokio::task::spawn_blocking(move || audit::read(&path, limit))
+            .await
+            .map_err(|e| Error::Io(std::io::Error::other(e)))?
+    }
+
+    /// List tags with the number of visible posts using each.
+    ///
+    /// Visibility follows [`ListOptions`], so tags used only by drafts or
+    /// scheduled posts are hidden unless those are included.
+    pub async fn list_tags(&self, opts: &ListOptions) -> Result<ListResult<TagSummary>> {
+        let cache = self.cache.read().await;
+        cache.list_tags(opts)
+    }
+
     /// Get a single post by its slug.
     ///
     /// Returns `None` if no post with the given slug exists.
@@ -185,6 +374,37 @@ impl RileyCms {
         cache.get_series(slug)
     }
 
+    /// Render an RSS, Atom or JSON feed of live posts.
+    ///
+    /// Returns `None` if `opts.series` names a series that does not exist or is not live.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Config`] if no `[feed]` section is configured.
+    pub async fn feed(&self, format: FeedFormat, opts: &FeedOptions) -> Result<Option<Feed>> {
+        let feed_config = self
+            .config
+            .feed
+            .as_ref()
+            .ok_or_else(|| Error::Config("Feeds are not configured ([feed] section)".into()))?;
+        let cache = self.cache.read().await;
+        Ok(feed::build(&cache, feed_config, format, opts))
+    }
+
+    /// Render a sitemap of all live posts and series.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Config`] if no `[sitemap]` section is configured.
+    pub async fn sitemap(&self) -> Result<Sitemap> {
+        let sitemap_config =
+            self.config.sitemap.as_ref().ok_or_else(|| {
+                Error::Config("Sitemap is not configured ([sitemap] section)".into())
+            })?;
+        let cache = self.cache.read().await;
+        Ok(sitemap::build(&cache, sitemap_config))
+    }
+
     /// Validate content structure and return any errors.
     ///
     /// Checks for common issues like empty titles, missing content, etc.
@@ -193,20 +413,20 @@ impl RileyCms {
         Ok(cache.validate())
     }
 
-    /// List assets in the S3/R2 storage bucket with pagination.
+    /// List assets in the storage backend with pagination.
     ///
-    /// Uses cursor-based pagination via S3 continuation tokens.
+    /// Uses cursor-based pagination via opaque continuation tokens.
     /// Defaults to 100 assets per page, capped at 1000.
     pub async fn list_assets(&self, opts: &AssetListOptions) -> Result<AssetListResult> {
         self.storage.list_assets(opts).await
     }
 
-    /// Upload a file to the storage bucket.
+    /// Upload a file to the storage backend.
     ///
     /// # Arguments
     ///
     /// * `path` - Local file path to upload
-    /// * `dest` - Optional destination path in bucket (defaults to filename)
+    /// * `dest` - Optional destination prefix (defaults to the bucket/directory root)
     pub async fn upload_asset(&self, path: &Path, dest: Option<&str>) -> Result<Asset> {
         self.storage.upload_asset(path, dest).await
     }
@@ -215,219 +435,108 @@ impl RileyCms {
     ///
     /// Call this after content has been updated (e.g., after a git push)
     /// to reload the in-memory cache.
-    pub async fn refresh(&self) -> Result<()> {
-        // Clone the config to move into the blocking task closure
-        let content_config = self.config.content.clone();
+    ///
+    /// If the reload had load errors or lost too many posts, `[content.refresh]`
+    /// decides what happens: `replace` swaps it in anyway,
+    /// `keep-previous-on-error` (the default) keeps serving the previous
+    /// content and returns [`RefreshOutcome::KeptPrevious`], and `strict` keeps
+    /// the previous content and returns [`Error::RefreshRejected`].
+    ///
+    /// Refreshes never overlap: a call waits for any running refresh, including
+    /// one started by the refresh worker, to finish first.
+    pub async fn refresh(&self) -> Result<RefreshOutcome> {
+        self.refresher.refresh().await
+    }
 
-        // Offload blocking filesystem I/O to a dedicated thread pool
-        let new_cache = tokio::task::spawn_blocking(move || ContentCache::load(&content_config))
-            .await
-            .map_err(|e| Error::Io(std::io::Error::other(e)))??;
+    /// Queue a refresh on the background refresh worker and return immediately.
+    ///
+    /// The worker waits until no request has arrived for
+    /// `content.refresh.debounce_ms`, then runs once for the whole burst: it
+    /// checks out `HEAD` (when `content.source` is `"worktree"`), refreshes,
+    /// and fires webhooks if the live content changed. Used after git pushes.
+    pub fn request_refresh(&self) {
+        self.worker.request();
+    }
 
-        let mut cache = self.cache.write().await;
-        *cache = new_cache;
-        Ok(())
+    /// Queue state and last result of the refresh worker
+    pub fn refresh_status(&self) -> RefreshStatus {
+        self.worker.status()
+    }
+
+    /// Content of `branch`, for previewing it before it is merged.
+    ///
+    /// The branch must match a `content.preview.branches` pattern, or this
+    /// returns [`Error::PreviewNotAllowed`]. Returns `None` if there is no such
+    /// branch. The returned instance reads the branch's current commit and is
+    /// cached until the branch moves or the cache is evicted.
+    pub async fn preview(&self, branch: &str) -> Result<Option<Arc<RileyCms>>> {
+        self.previews.get(&self.config, &self.storage, branch).await
+    }
+
+    /// Commit the live content was read from, when `content.source` is `"git"` or `"deploy"`
+    pub async fn content_revision(&self) -> Option<String> {
+        let cache = self.cache.read().await;
+        cache.revision().map(String::from)
+    }
+
+    /// Problems found the last time content was loaded into the live cache
+    pub async fn load_diagnostics(&self) -> Vec<LoadDiagnostic> {
+        let cache = self.cache.read().await;
+        cache.diagnostics().to_vec()
     }
 
     /// Get an ETag representing the current content state.
     ///
-    /// This is a hash of all content, suitable for HTTP caching headers.
-    /// The ETag changes when any content is modified.
+    /// This is a hash of all content; it changes when anything is modified,
+    /// and when scheduled content goes live.
+    /// For HTTP caching prefer the per-resource `etag` on [`Post`], [`Series`]
+    /// and [`ListResult`].
     pub async fn content_etag(&self) -> String {
         let cache = self.cache.read().await;
         cache.etag()
     }
 
-    /// Fire webhooks after content update.
+    /// Queue webhooks after content update.
     ///
-    /// Each webhook is validated and sent atomically: DNS is resolved once,
+    /// The payload describes `changes`, e.g. from [`ContentCache::changes_from`]:
+    /// the old and new commit, the created, updated and deleted post and series
+    /// slugs, and one event (`post.published`, `series.updated`, ...) per change.
+    ///
+    /// One delivery per webhook endpoint whose `events` filter matches is
+    /// written to the delivery queue, which survives restarts, and sent in the
+    /// background with the endpoint's extra headers.
+    /// Each attempt is validated and sent atomically: DNS is resolved once,
     /// checked against private/internal IP ranges, and the connection is pinned
     /// to the validated IP (preventing DNS rebinding/TOCTOU attacks).
     ///
-    /// If a `secret` is configured in `[webhooks]`, signs each request body with
-    /// HMAC-SHA256 and includes the hex signature in the `X-Riley-Cms-Signature` header.
-    /// Retries up to 3 times with exponential backoff on network errors or 5xx responses.
-    pub async fn fire_webhooks(&self) {
-        if let Some(ref webhooks) = self.config.webhooks {
-            // Resolve webhook secret once (if configured)
-            let secret = if let Some(ref secret_config) = webhooks.secret {
-                match secret_config.resolve() {
-                    Ok(v) if v.is_empty() => {
-                        tracing::error!(
-                            "Webhook secret resolves to empty string. Skipping webhook delivery."
-                        );
-                        return;
-                    }
-                    Ok(v) => Some(v),
-                    Err(e) => {
-                        tracing::error!(
-                            "Failed to resolve webhook secret: {}. Skipping webhook delivery.",
-                            e
-                        );
-                        return;
-                    }
-                }
-            } else {
-                None
-            };
-
-            for url in &webhooks.on_content_update {
-                let url = url.clone();
-                let secret = secret.clone();
-                tokio::spawn(async move {
-                    send_webhook(&url, secret.as_deref()).await;
-                });
-            }
-        }
+    /// If the endpoint or `[webhooks]` has a `secret`, signs `<timestamp>.<body>`
+    /// with HMAC-SHA256 under it and any `previous_secret`, and sends the
+    /// timestamp and signatures in the `X-Riley-Cms-Timestamp` and
+    /// `X-Riley-Cms-Signature` headers (see [`verify_webhook_signature`]).
+    /// Network errors and 5xx responses are retried with exponential backoff,
+    /// starting at the endpoint's `retry_base_secs`, for up to `max_attempts` attempts.
+    pub async fn fire_webhooks(&self, changes: &ContentChanges) {
+        self.webhooks.enqueue(changes).await;
     }
 
-    /// Get a reference to the config.
-    pub fn config(&self) -> &RileyCmsConfig {
-        &self.config
+    /// Up to `limit` webhook deliveries from the delivery log, newest first.
+    ///
+    /// Pending deliveries are included, along with the most recent
+    /// `webhooks.keep_deliveries` finished ones.
+    pub async fn list_webhook_deliveries(&self, limit: usize) -> Result<Vec<WebhookDelivery>> {
+        self.webhooks.list(limit).await
     }
-}
-
-/// Maximum number of retry attempts for webhook delivery.
-const WEBHOOK_MAX_RETRIES: u32 = 3;
 
-/// Send a single webhook with SSRF protection, optional HMAC signing, and retry.
-///
-/// Resolves DNS once, validates all IPs against private ranges, then pins the
-/// connection to the validated IP using `reqwest::ClientBuilder::resolve()`.
-/// This prevents DNS rebinding (TOCTOU) attacks where DNS changes between
-/// validation and the actual connection.
-///
-/// Retries on network errors or 5xx responses. Does not retry on 4xx (client errors)
-/// since those indicate a problem with the receiver's configuration, not a transient issue.
-async fn send_webhook(url: &str, secret: Option<&str>) {
-    // 1. Parse URL and validate scheme
-    let parsed = match reqwest::Url::parse(url) {
-        Ok(u) => u,
-        Err(e) => {
-            tracing::warn!("Skipping webhook {}: invalid URL: {}", url, e);
-            return;
-        }
-    };
-
-    let scheme = parsed.scheme();
-    if scheme != "http" && scheme != "https" {
-        tracing::warn!("Skipping webhook {}: unsupported scheme: {}", url, scheme);
-        return;
+    /// Queue a new delivery with the same URL and payload as delivery `id`.
+    ///
+    /// The payload gets a fresh timestamp and signature when it is sent.
+    /// Returns the new delivery, or `None` if there is no delivery `id`.
+    pub async fn redeliver_webhook(&self, id: &str) -> Result<Option<WebhookDelivery>> {
+        self.webhooks.redeliver(id).await
     }
 
-    let host = match parsed.host_str() {
-        Some(h) => h.to_string(),
-        None => {
-            tracing::warn!("Skipping webhook {}: missing host", url);
-            return;
-        }
-    };
-    let port = parsed.port_or_known_default().unwrap_or(443);
-
-    // 2. Resolve DNS once and validate all IPs
-    let addr_str = format!("{}:{}", host, port);
-    let addrs: Vec<std::net::SocketAddr> = match addr_str.to_socket_addrs() {
-        Ok(a) => a.collect(),
-        Err(e) => {
-            tracing::warn!("Skipping webhook {}: DNS resolution failed: {}", url, e);
-            return;
-        }
-    };
-
-    // 3. Find a safe (non-private) IP address to connect to
-    let safe_addr = match addrs.into_iter().find(|a| security::is_safe_ip(&a.ip())) {
-        Some(a) => a,
-        None => {
-            tracing::warn!(
-                "Skipping webhook {}: all resolved IPs are private/internal",
-                url
-            );
-            return;
-        }
-    };
-
-    // 4. Build client pinned to the validated IP (prevents DNS rebinding)
-    //    Redirects disabled to prevent SSRF bypass via 302 to internal IPs.
-    let client = reqwest::Client::builder()
-        .resolve(&host, safe_addr)
-        .redirect(reqwest::redirect::Policy::none())
-        .timeout(std::time::Duration::from_secs(10))
-        .build()
-        .unwrap_or_else(|_| reqwest::Client::new());
-
-    // Include a timestamp in the payload to prevent replay attacks.
-    // Each webhook delivery gets a unique signature since the body changes.
-    let body = serde_json::json!({
-        "event": "content_update",
-        "timestamp": Utc::now().timestamp()
-    })
-    .to_string();
-
-    // Compute HMAC signature if secret is configured
-    let signature = match secret {
-        Some(s) => {
-            let mut mac = match Hmac::<Sha256>::new_from_slice(s.as_bytes()) {
-                Ok(m) => m,
-                Err(e) => {
-                    tracing::error!("Invalid webhook secret key: {}. Skipping webhook.", e);
-                    return;
-                }
-            };
-            mac.update(body.as_bytes());
-            Some(hex::encode(mac.finalize().into_bytes()))
-        }
-        None => None,
-    };
-
-    for attempt in 0..WEBHOOK_MAX_RETRIES {
-        let mut request = client
-            .post(url)
-            .header("Content-Type", "application/json")
-            .body(body.clone());
-
-        if let Some(ref sig) = signature {
-            request = request.header("X-Riley-Cms-Signature", format!("sha256={}", sig));
-        }
-
-        match request.send().await {
-            Ok(response) if response.status().is_success() => return,
-            Ok(response) if response.status().is_client_error() => {
-                tracing::warn!(
-                    "Webhook {} returned {} (not retrying)",
-                    url,
-                    response.status()
-                );
-                return;
-            }
-            Ok(response) => {
-                tracing::warn!(
-                    "Webhook {} returned {} (attempt {}/{})",
-                    url,
-                    response.status(),
-                    attempt + 1,
-                    WEBHOOK_MAX_RETRIES
-                );
-            }
-            Err(e) => {
-                tracing::warn!(
-                    "Webhook {} failed: {} (attempt {}/{})",
-                    url,
-                    e,
-                    attempt + 1,
-                    WEBHOOK_MAX_RETRIES
-                );
-            }
-        }
-
-        // Exponential backoff: 1s, 2s, 4s
-        if attempt < WEBHOOK_MAX_RETRIES - 1 {
-            tokio::time::sleep(std::time::Duration::from_secs(1 << attempt)).await;
-        }
+    /// Get a reference to the config.
+    pub fn config(&self) -> &RileyCmsConfig {
+        &self.config
     }
-
-    tracing::error!(
-        "Webhook {} failed after {} attempts",
-        url,
-        WEBHOOK_MAX_RETRIES
-    );
 }
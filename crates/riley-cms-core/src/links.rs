@@ -0,0 +1,48 @@
+//! Public URL templates for posts and series
+
+/// Expand a URL template.
+///
+/// Supported placeholders: `{base_url}` (trailing slash trimmed), `{slug}` and
+/// `{series_slug}` (empty for standalone posts).
+pub(crate) fn expand(
+    template: &str,
+    base_url: &str,
+    slug: &str,
+    series_slug: Option<&str>,
+) -> String {
+    template
+        .replace("{base_url}", base_url.trim_end_matches('/'))
+        .replace("{series_slug}", series_slug.unwrap_or_default())
+        .replace("{slug}", slug)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_all_placeholders() {
+        assert_eq!(
+            expand(
+                "{base_url}/{series_slug}/{slug}",
+                "https://example.com/",
+                "part-one",
+                Some("rust")
+            ),
+            "https://example.com/rust/part-one"
+        );
+    }
+
+    #[test]
+    fn test_expand_without_series() {
+        assert_eq!(
+            expand(
+                "{base_url}/posts/{slug}",
+                "https://example.com",
+                "hello",
+                None
+            ),
+            "https://example.com/posts/hello"
+        );
+    }
+}
@@ -0,0 +1,294 @@
+//! MDX preprocessing shared by search indexing and HTML rendering
+//!
+//! MDX is Markdown plus ESM `import`/`export` lines, JSX components and
+//! `{expressions}`. [`preprocess`] reduces it to CommonMark (optionally keeping
+//! JSX component tags as HTML) so it can be handed to a Markdown parser.
+//! Fenced code blocks and inline code spans are left untouched.
+
+use crate::config::JsxMode;
+
+/// Output of [`preprocess`]
+#[derive(Debug, Default)]
+pub(crate) struct Preprocessed {
+    pub markdown: String,
+    /// Components kept by [`JsxMode::Passthrough`], as `(name, lowercased prop names)`
+    pub components: Vec<(String, Vec<String>)>,
+}
+
+/// Reduce MDX to Markdown.
+///
+/// ESM lines and `{expressions}` (including expression-valued props) are
+/// removed. JSX fragments are always removed; component tags are removed or
+/// kept according to `jsx`. Kept components become
+/// `<div data-component="Name" data-prop="...">` wrappers, never tags named
+/// after the component. Self-closing components are expanded to an open and
+/// close tag so HTML parsers don't treat them as unclosed.
+pub(crate) fn preprocess(source: &str, jsx: JsxMode) -> Preprocessed {
+    let mut out = Preprocessed {
+        markdown: String::with_capacity(source.len()),
+        components: Vec::new(),
+    };
+    let mut in_fence = false;
+    // Nesting depth of the `{expression}` being skipped
+    let mut depth = 0usize;
+    // JSX tag being collected, which may span lines
+    let mut tag: Option<String> = None;
+    let mut quote: Option<char> = None;
+
+    for line in source.lines() {
+        let idle = depth == 0 && tag.is_none();
+        let trimmed = line.trim_start();
+        if idle && (trimmed.starts_with("```") || trimmed.starts_with("~~~")) {
+            in_fence = !in_fence;
+            out.markdown.push_str(line);
+            out.markdown.push('\n');
+            continue;
+        }
+        if in_fence {
+            out.markdown.push_str(line);
+            out.markdown.push('\n');
+            continue;
+        }
+        if idle && (line.starts_with("import ") || line.starts_with("export ")) {
+            continue;
+        }
+
+        let chars: Vec<char> = line.chars().collect();
+        let mut in_code = false;
+        for (i, &c) in chars.iter().enumerate() {
+            if depth > 0 {
+                match c {
+                    '{' => depth += 1,
+                    '}' => depth -= 1,
+                    _ => {}
+                }
+                continue;
+            }
+
+            if let Some(ref mut buf) = tag {
+                if let Some(q) = quote {
+                    buf.push(c);
+                    if c == q {
+                        quote = None;
+                    }
+                    continue;
+                }
+                match c {
+                    '"' | '\'' => {
+                        quote = Some(c);
+                        buf.push(c);
+                    }
+                    // Expression-valued prop: drop the `name=` along with the value
+                    '{' => {
+                        depth = 1;
+                        if buf.ends_with('=') {
+                            buf.pop();
+                            while buf.ends_with(is_name_char) {
+                                buf.pop();
+                            }
+                        }
+                    }
+                    '>' => {
+                        buf.push('>');
+                        let finished = tag.take().unwrap_or_default();
+                        emit_tag(&finished, jsx, &mut out);
+                    }
+                    _ => buf.push(c),
+                }
+                continue;
+            }
+
+            if in_code {
+                out.markdown.push(c);
+                if c == '`' {
+                    in_code = false;
+                }
+                continue;
+            }
+
+            match c {
+                '`' => {
+                    in_code = true;
+                    out.markdown.push(c);
+                }
+                '{' => depth = 1,
+                '<' if starts_jsx_tag(&chars[i + 1..]) => tag = Some("<".to_string()),
+                _ => out.markdown.push(c),
+            }
+        }
+
+        match tag {
+            Some(ref mut buf) => buf.push(' '),
+            None => out.markdown.push('\n'),
+        }
+    }
+
+    out
+}
+
+/// Whether the text after a `<` begins a JSX component tag or fragment
+fn starts_jsx_tag(rest: &[char]) -> bool {
+    match rest {
+        ['>', ..] | ['/', '>', ..] => true,
+        ['/', c, ..] | [c, ..] => c.is_ascii_uppercase(),
+        [] => false,
+    }
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '-' | '_' | ':' | '.')
+}
+
+/// Write a complete JSX tag (`<Name ...>`, `</Name>` or `<Name ... />`) to the output
+fn emit_tag(tag: &str, jsx: JsxMode, out: &mut Preprocessed) {
+    if jsx == JsxMode::Strip {
+        out.markdown.push(' ');
+        return;
+    }
+
+    let closing = tag.starts_with("</");
+    let inner = tag
+        .trim_start_matches('<')
+        .trim_start_matches('/')
+        .trim_end_matches('>');
+    let self_closing = inner.ends_with('/');
+    let inner = inner.trim_end_matches('/').trim();
+    let name_len = inner.find(|c| !is_name_char(c)).unwrap_or(inner.len());
+    let (name, attrs) = inner.split_at(name_len);
+
+    // Fragments carry no markup
+    if name.is_empty() {
+        return;
+    }
+
+    if closing {
+        out.markdown.push_str("</div>");
+        return;
+    }
+
+    // Handlers and inline styles have no meaning outside the component
+    let props: Vec<_> = attributes(attrs)
+        .into_iter()
+        .filter(|(prop, _)| !prop.starts_with("on") && prop != "style")
+        .collect();
+    if !out.components.iter().any(|(n, _)| n == name) {
+        out.components.push((name.to_string(), Vec::new()));
+    }
+    if let Some((_, names)) = out.components.iter_mut().find(|(n, _)| n == name) {
+        for (prop, _) in &props {
+            if !names.contains(prop) {
+                names.push(prop.clone());
+            }
+        }
+    }
+
+    let mut open = format!("<div data-component=\"{}\"", name);
+    for (prop, value) in &props {
+        open.push_str(&format!(" data-{}", prop));
+        if let Some(value) = value {
+            open.push('=');
+            open.push_str(value);
+        }
+    }
+    open.push('>');
+    out.markdown.push_str(&open);
+    if self_closing {
+        out.markdown.push_str("</div>");
+    }
+}
+
+/// Props in a tag's attribute text, as lowercased names with their raw
+/// (quoted or bare) values
+fn attributes(attrs: &str) -> Vec<(String, Option<String>)> {
+    let mut props = Vec::new();
+    let mut chars = attrs.chars().peekable();
+    while let Some(c) = chars.next() {
+        if !is_name_char(c) {
+            continue;
+        }
+        let mut name = c.to_string();
+        while let Some(&next) = chars.peek().filter(|n| is_name_char(**n)) {
+            name.push(next);
+            chars.next();
+        }
+        while chars.peek().is_some_and(|n| n.is_whitespace()) {
+            chars.next();
+        }
+        let mut value = None;
+        if chars.peek() == Some(&'=') {
+            chars.next();
+            while chars.peek().is_some_and(|n| n.is_whitespace()) {
+                chars.next();
+            }
+            let mut raw = String::new();
+            match chars.peek().copied() {
+                Some(q @ ('"' | '\'')) => {
+                    raw.push(q);
+                    chars.next();
+                    for next in chars.by_ref() {
+                        raw.push(next);
+                        if next == q {
+                            break;
+                        }
+                    }
+                }
+                _ => {
+                    while let Some(&next) = chars.peek().filter(|n| !n.is_whitespace()) {
+                        raw.push(next);
+                        chars.next();
+                    }
+                }
+            }
+            value = Some(raw);
+        }
+        props.push((name.to_lowercase(), value));
+    }
+    props
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_removes_esm_expressions_and_components() {
+        let mdx = "import Chart from './Chart'\n\n# Title {meta.x}\n\n<Note kind=\"tip\">\nHello\n</Note>\n\n<Chart data={[1, 2]}\n  title=\"Sales\" />\n";
+        let out = preprocess(mdx, JsxMode::Strip);
+        assert!(!out.markdown.contains("import"));
+        assert!(!out.markdown.contains("meta"));
+        assert!(!out.markdown.contains("Note"));
+        assert!(!out.markdown.contains("Chart"));
+        assert!(out.markdown.contains("# Title"));
+        assert!(out.markdown.contains("Hello"));
+        assert!(out.components.is_empty());
+    }
+
+    #[test]
+    fn test_passthrough_keeps_components() {
+        let mdx =
+            "<Note kind=\"tip\" onClick={go}>\nHello\n</Note>\n\n<Chart data={[1]} title='x' />\n";
+        let out = preprocess(mdx, JsxMode::Passthrough);
+        assert!(
+            out.markdown
+                .contains("<div data-component=\"Note\" data-kind=\"tip\">\nHello\n</div>")
+        );
+        assert!(
+            out.markdown
+                .contains("<div data-component=\"Chart\" data-title='x'></div>")
+        );
+        assert_eq!(
+            out.components,
+            vec![
+                ("Note".to_string(), vec!["kind".to_string()]),
+                ("Chart".to_string(), vec!["title".to_string()]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_code_is_untouched() {
+        let mdx = "Use `<Foo>` and `{x}`\n\n```jsx\n<Foo bar={1} />\n```\n";
+        let out = preprocess(mdx, JsxMode::Strip);
+        assert_eq!(out.markdown, mdx);
+    }
+}
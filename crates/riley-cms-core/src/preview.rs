@@ -0,0 +1,215 @@
+//! Branch preview caches (`[content.preview]`)
+//!
+//! Each previewed branch gets its own [`RileyCms`] reading the branch's commit
+//! from the object database. It is reloaded when the branch moves, and evicted
+//! when it has been idle for `idle_secs` or room is needed for another branch.
+
+use crate::RileyCms;
+use crate::config::{ContentSource, PreviewConfig, RileyCmsConfig};
+use crate::error::{Error, Result};
+use crate::push::glob_matches;
+use crate::storage::Storage;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::OnceCell;
+use tokio::task::JoinHandle;
+
+/// Longest time between two sweeps for idle previews
+const MAX_SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// A branch at one commit, loaded on first use
+struct Preview {
+    /// Filled by whichever request loads it first; others wait for that load
+    riley_cms: Arc<OnceCell<Arc<RileyCms>>>,
+    commit: String,
+    last_used: Instant,
+}
+
+type Entries = Arc<Mutex<HashMap<String, Preview>>>;
+
+/// Loaded branches, by branch name
+pub(crate) struct Previews {
+    entries: Entries,
+    /// Evicts idle previews; aborted on drop
+    sweeper: Option<JoinHandle<()>>,
+}
+
+impl Previews {
+    /// Previews under `config`, swept for idle ones in the background when
+    /// any branches can be previewed
+    pub fn new(config: &PreviewConfig) -> Self {
+        let entries = Entries::default();
+        let sweeper = (!config.branches.is_empty()).then(|| {
+            tokio::spawn(sweep(
+                Arc::downgrade(&entries),
+                Duration::from_secs(config.idle_secs),
+            ))
+        });
+        Self { entries, sweeper }
+    }
+
+    /// Content of `branch`, loading it if it isn't cached or has moved since.
+    ///
+    /// Returns `None` if the branch doesn't exist. Loads happen outside the
+    /// map's lock, so different branches load concurrently and cached ones are
+    /// served meanwhile.
+    pub async fn get(
+        &self,
+        config: &RileyCmsConfig,
+        storage: &Arc<dyn Storage>,
+        branch: &str,
+    ) -> Result<Option<Arc<RileyCms>>> {
+        let preview = &config.content.preview;
+        if !preview
+            .branches
+            .iter()
+            .any(|pattern| glob_matches(pattern, branch))
+        {
+            return Err(Error::PreviewNotAllowed(branch.to_string()));
+        }
+        let Some(commit) = resolve_branch(&config.content.repo_path, branch).await? else {
+            return Ok(None);
+        };
+
+        let cell = self.entry(branch, &commit, preview.max_caches);
+        let result = cell
+            .get_or_try_init(|| async {
+                let riley_cms =
+                    RileyCms::with_storage(preview_config(config, &commit), storage.clone())
+                        .await?;
+                tracing::info!("Loaded preview of {} at {}", branch, commit);
+                Ok(Arc::new(riley_cms))
+            })
+            .await;
+        match result {
+            Ok(riley_cms) => Ok(Some(riley_cms.clone())),
+            Err(e) => {
+                // Let the next request try again
+                let mut entries = lock(&self.entries);
+                if entries
+                    .get(branch)
+                    .is_some_and(|p| Arc::ptr_eq(&p.riley_cms, &cell))
+                {
+                    entries.remove(branch);
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// The cell holding `branch` at `commit`, replacing an entry for an older
+    /// commit and evicting the least recently used branch to make room
+    fn entry(&self, branch: &str, commit: &str, max_caches: usize) -> Arc<OnceCell<Arc<RileyCms>>> {
+        let mut entries = lock(&self.entries);
+        let now = Instant::now();
+        if let Some(p) = entries.get_mut(branch)
+            && p.commit == commit
+        {
+            p.last_used = now;
+            return p.riley_cms.clone();
+        }
+        entries.remove(branch);
+
+        while !entries.is_empty() && entries.len() >= max_caches {
+            let Some(oldest) = entries
+                .iter()
+                .min_by_key(|(_, p)| p.last_used)
+                .map(|(name, _)| name.clone())
+            else {
+                break;
+            };
+            tracing::info!("Evicting preview of {} to make room", oldest);
+            entries.remove(&oldest);
+        }
+
+        let cell = Arc::new(OnceCell::new());
+        if max_caches > 0 {
+            entries.insert(
+                branch.to_string(),
+                Preview {
+                    riley_cms: cell.clone(),
+                    commit: commit.to_string(),
+                    last_used: now,
+                },
+            );
+        }
+        cell
+    }
+}
+
+impl Drop for Previews {
+    fn drop(&mut self) {
+        if let Some(sweeper) = &self.sweeper {
+            sweeper.abort();
+        }
+    }
+}
+
+/// The map behind `entries`. It is only held briefly, never across a load.
+fn lock(entries: &Entries) -> std::sync::MutexGuard<'_, HashMap<String, Preview>> {
+    entries.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Evict previews unused for `idle`, until the previews are dropped
+async fn sweep(entries: std::sync::Weak<Mutex<HashMap<String, Preview>>>, idle: Duration) {
+    let interval = idle.clamp(Duration::from_secs(1), MAX_SWEEP_INTERVAL);
+    loop {
+        tokio::time::sleep(interval).await;
+        let Some(entries) = entries.upgrade() else {
+            return;
+        };
+        let now = Instant::now();
+        lock(&entries).retain(|name, p| {
+            let keep = now.duration_since(p.last_used) < idle;
+            if !keep {
+                tracing::info!("Evicting idle preview of {}", name);
+            }
+            keep
+        });
+    }
+}
+
+/// Config for a preview of `commit`: read from the object database, with no
+/// webhooks or nested previews
+fn preview_config(config: &RileyCmsConfig, commit: &str) -> RileyCmsConfig {
+    let mut config = config.clone();
+    config.content.source = ContentSource::Git;
+    config.content.git_ref = commit.to_string();
+    config.content.preview = PreviewConfig {
+        branches: Vec::new(),
+        ..config.content.preview
+    };
+    config.webhooks = None;
+    config
+}
+
+/// Commit `refs/heads/<branch>` points to, or `None` if there is no such branch
+async fn resolve_branch(repo_path: &Path, branch: &str) -> Result<Option<String>> {
+    let refname = format!("refs/heads/{}", branch);
+    // Rules out `~`, `^`, `..` and the like, which would select another commit
+    let valid = tokio::process::Command::new("git")
+        .args(["check-ref-format", &refname])
+        .status()
+        .await
+        .map_err(|e| Error::Git(format!("Failed to run git: {}", e)))?;
+    if !valid.success() {
+        return Ok(None);
+    }
+
+    let output = tokio::process::Command::new("git")
+        .arg("-C")
+        .arg(repo_path)
+        .args(["rev-parse", "--verify", "--quiet"])
+        .arg(format!("{}^{{commit}}", refname))
+        .output()
+        .await
+        .map_err(|e| Error::Git(format!("Failed to run git: {}", e)))?;
+    if !output.status.success() {
+        return Ok(None);
+    }
+    Ok(Some(
+        String::from_utf8_lossy(&output.stdout).trim().to_string(),
+    ))
+}
@@ -0,0 +1,964 @@
+//! Checks on `git push` before any ref is updated
+//!
+//! A `git-receive-pack` request is a list of ref update commands followed by a
+//! packfile. [`ReceivePack::parse`] reads the commands, and
+//! [`GitBackend::check_push`] indexes the packfile into a temporary quarantine
+//! (the repository's object store is never touched). Ref updates are checked
+//! against `[git.branch_protection]` and the files each pushed commit changes
+//! against `[git.push_policy]`, then the branch is
+//! checked out into a scratch directory and validated as content. A rejected
+//! push is answered with [`ReceivePack::rejection`], which `git push` reports
+//! as `remote:` messages and a failed ref update.
+
+use crate::config::{
+    BranchProtectionConfig, ContentConfig, ContentSource, PushPolicyConfig, RileyCmsConfig,
+};
+use crate::content::ContentCache;
+use crate::error::{Error, Result};
+use crate::git::{self, GitBackend};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+/// Object name git uses for "no object" (ref creation or deletion)
+const ZERO_OID: &str = "0000000000000000000000000000000000000000";
+
+/// Largest pkt-line payload allowed by the `side-band-64k` capability
+const SIDE_BAND_64K_MAX: usize = 65515;
+
+/// Largest pkt-line payload allowed by the `side-band` capability
+const SIDE_BAND_MAX: usize = 995;
+
+/// Git file mode of a symbolic link
+const SYMLINK_MODE: &str = "120000";
+
+/// Why a push was refused
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PushRejection {
+    /// Short reason reported against each ref (e.g. `invalid content`)
+    pub reason: String,
+    /// One message per problem, prefixed with the branch name
+    pub problems: Vec<String>,
+}
+
+/// A file a pushed commit adds, modifies or deletes
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct ChangedFile {
+    path: String,
+    /// Git mode after the push (`000000` if deleted)
+    mode: String,
+    /// Object after the push (all zeros if deleted)
+    oid: String,
+}
+
+impl ChangedFile {
+    fn is_delete(&self) -> bool {
+        is_zero_oid(&self.oid)
+    }
+}
+
+/// A ref update requested by `git push`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RefUpdate {
+    pub old: String,
+    pub new: String,
+    /// Full ref name, e.g. `refs/heads/main`
+    pub refname: String,
+}
+
+impl RefUpdate {
+    /// Whether this update deletes the ref
+    pub fn is_delete(&self) -> bool {
+        is_zero_oid(&self.new)
+    }
+
+    /// Whether this update creates the ref
+    pub fn is_create(&self) -> bool {
+        is_zero_oid(&self.old)
+    }
+
+    /// Branch name, if this updates a branch
+    pub fn branch(&self) -> Option<&str> {
+        self.refname.strip_prefix("refs/heads/")
+    }
+
+    /// Branch name for branches, full ref name otherwise
+    fn label(&self) -> &str {
+        self.branch().unwrap_or(&self.refname)
+    }
+}
+
+/// The command section of a `git-receive-pack` request body
+#[derive(Debug, Clone)]
+pub struct ReceivePack {
+    pub updates: Vec<RefUpdate>,
+    capabilities: Vec<String>,
+    /// Offset of the packfile in the request body (the body length if there is none)
+    pack_offset: usize,
+}
+
+impl ReceivePack {
+    /// Parse the ref update commands at the start of a request body
+    pub fn parse(body: &[u8]) -> Result<Self> {
+        let mut pos = 0;
+        let mut updates = Vec::new();
+        let mut capabilities = Vec::new();
+
+        while let Some(line) = read_pkt_line(body, &mut pos)? {
+            let line = String::from_utf8_lossy(line);
+            let line = line.trim_end_matches('\n');
+            if line.starts_with("shallow ") {
+                continue;
+            }
+            if line.starts_with("push-cert") {
+                return Err(Error::Git("Signed pushes are not supported".to_string()));
+            }
+
+            let (command, caps) = match line.split_once('\0') {
+                Some((command, caps)) => (command, Some(caps)),
+                None => (line, None),
+            };
+            if let Some(caps) = caps {
+                capabilities = caps.split(' ').map(String::from).collect();
+            }
+
+            let mut fields = command.splitn(3, ' ');
+            match (fields.next(), fields.next(), fields.next()) {
+                (Some(old), Some(new), Some(refname)) => updates.push(RefUpdate {
+                    old: old.to_string(),
+                    new: new.to_string(),
+                    refname: refname.to_string(),
+                }),
+                _ => {
+                    return Err(Error::Git(format!(
+                        "Malformed receive-pack command: {:?}",
+                        command
+                    )));
+                }
+            }
+        }
+
+        // Push options follow the commands as a second pkt-line list
+        if capabilities.iter().any(|c| c == "push-options") {
+            while read_pkt_line(body, &mut pos)?.is_some() {}
+        }
+
+        Ok(Self {
+            updates,
+            capabilities,
+            pack_offset: pos,
+        })
+    }
+
+    /// The packfile sent after the commands, if any
+    pub fn pack<'a>(&self, body: &'a [u8]) -> &'a [u8] {
+        &body[self.pack_offset.min(body.len())..]
+    }
+
+    fn has_capability(&self, name: &str) -> bool {
+        self.capabilities.iter().any(|c| c == name)
+    }
+
+    /// A `git-receive-pack` result body that refuses every ref update.
+    ///
+    /// `messages` are shown to the user as `remote:` lines when the client
+    /// asked for a side band; `reason` is reported against each ref.
+    pub fn rejection(&self, reason: &str, messages: &[String]) -> Vec<u8> {
+        let mut report = Vec::new();
+        if self.has_capability("report-status") || self.has_capability("report-status-v2") {
+            write_pkt_line(&mut report, b"unpack ok\n");
+            for update in &self.updates {
+                let line = format!("ng {} {}\n", update.refname, reason);
+                write_pkt_line(&mut report, line.as_bytes());
+            }
+            report.extend_from_slice(b"0000");
+        }
+
+        let max = if self.has_capability("side-band-64k") {
+            SIDE_BAND_64K_MAX
+        } else if self.has_capability("side-band") {
+            SIDE_BAND_MAX
+        } else {
+            return report;
+        };
+
+        let mut out = Vec::new();
+        let text: String = messages.iter().map(|m| format!("{}\n", m)).collect();
+        write_side_band(&mut out, 2, text.as_bytes(), max);
+        write_side_band(&mut out, 1, &report, max);
+        out.extend_from_slice(b"0000");
+        out
+    }
+}
+
+impl GitBackend {
+    /// Check every ref a push updates, before the refs move.
+    ///
+    /// In order, stopping at the first stage that finds problems: ref updates
+    /// are checked against `git.branch_protection` (unless `override_protection`,
+    /// i.e. the push used the override token), the files each new commit
+    /// changes against `git.push_policy`, and (if `git.validate_pushes` is on)
+    /// each branch's content is loaded and validated. Returns `None` if the push may proceed.
+    /// Errors mean the check itself couldn't run.
+    pub async fn check_push(
+        &self,
+        request: &ReceivePack,
+        body: &[u8],
+        config: &RileyCmsConfig,
+        override_protection: bool,
+    ) -> Result<Option<PushRejection>> {
+        let git = config.git.as_ref();
+        let protection = git
+            .and_then(|g| g.branch_protection.as_ref())
+            .filter(|_| !override_protection);
+        let policy = git.and_then(|g| g.push_policy.as_ref());
+        let validate = git.is_none_or(|g| g.validate_pushes);
+        let branches: Vec<&RefUpdate> = request
+            .updates
+            .iter()
+            .filter(|u| u.branch().is_some() && !u.is_delete())
+            .collect();
+        if protection.is_none() && (branches.is_empty() || (policy.is_none() && !validate)) {
+            return Ok(None);
+        }
+
+        let quarantine = Quarantine::new(self.repo_path())?;
+        quarantine.index_pack(request.pack(body)).await?;
+
+        if let Some(protection) = protection {
+            let mut problems = Vec::new();
+            for update in &request.updates {
+                if let Some(problem) = protection_violation(protection, update, &quarantine).await?
+                {
+                    problems.push(format!("{}: {}", update.label(), problem));
+                }
+            }
+            if !problems.is_empty() {
+                return Ok(Some(PushRejection {
+                    reason: "protected ref".to_string(),
+                    problems,
+                }));
+            }
+        }
+
+        if let Some(policy) = policy {
+            let mut problems = Vec::new();
+            for update in &branches {
+                // Every pushed commit is checked against its parents, so a file
+                // added and then removed within one push is still caught
+                let mut files = Vec::new();
+                for (commit, parents) in quarantine.new_commits(update).await? {
+                    if parents.is_empty() {
+                        files.extend(quarantine.changed_files(None, &commit).await?);
+                    }
+                    for parent in &parents {
+                        files.extend(quarantine.changed_files(Some(parent), &commit).await?);
+                    }
+                }
+                files.sort();
+                files.dedup();
+                let sizes = quarantine.blob_sizes(&files).await?;
+                problems.extend(policy_violations(
+                    policy,
+                    &config.content,
+                    update.label(),
+                    &files,
+                    &sizes,
+                ));
+            }
+            if !problems.is_empty() {
+                return Ok(Some(PushRejection {
+                    reason: "push policy violation".to_string(),
+                    problems,
+                }));
+            }
+        }
+
+        if !validate {
+            return Ok(None);
+        }
+        let mut problems = Vec::new();
+        for (i, update) in branches.iter().enumerate() {
+            let branch = update.label();
+            let tree = quarantine.checkout(&update.new, i).await?;
+
+            let content = ContentConfig {
+                repo_path: tree,
+                source: ContentSource::Worktree,
+                ..config.content.clone()
+            };
+            let errors = tokio::task::spawn_blocking(move || {
+                ContentCache::load(&content).map(|cache| cache.validate())
+            })
+            .await
+            .map_err(|e| Error::Io(std::io::Error::other(e)))?;
+
+            match errors {
+                Ok(errors) => problems.extend(errors.into_iter().map(|e| {
+                    format!(
+                        "{}: {}/{}: {}",
+                        branch,
+                        config.content.content_dir.trim_matches('/'),
+                        e.path,
+                        e.message
+                    )
+                })),
+                Err(e) => problems.push(format!("{}: {}", branch, e)),
+            }
+        }
+        Ok((!problems.is_empty()).then(|| PushRejection {
+            reason: "invalid content".to_string(),
+            problems,
+        }))
+    }
+}
+
+impl GitBackend {
+    /// Commit `refname` currently points at, or `None` if it doesn't exist
+    pub async fn ref_target(&self, refname: &str) -> Option<String> {
+        let out = run(Command::new("git")
+            .arg("-C")
+            .arg(self.repo_path())
+            .args(["rev-parse", "--verify", "--quiet"])
+            .arg(format!("{}^{{commit}}", refname)))
+        .await
+        .ok()?;
+        Some(String::from_utf8_lossy(&out).trim().to_string()).filter(|s| !s.is_empty())
+    }
+
+    /// Posts under `content_dir` with files that differ between `old` and `new`.
+    ///
+    /// A missing side counts as an empty tree. Posts are named by their
+    /// directory relative to `content_dir` (`slug` or `series/slug`), sorted.
+    pub async fn changed_posts(
+        &self,
+        old: Option<&str>,
+        new: Option<&str>,
+        content_dir: &str,
+    ) -> Result<Vec<String>> {
+        let files = match (old, new) {
+            (Some(old), Some(new)) => {
+                self.git_paths(&[
+                    "diff-tree",
+                    "-r",
+                    "-z",
+                    "--name-only",
+                    "--no-renames",
+                    old,
+                    new,
+                ])
+                .await?
+            }
+            (Some(commit), None) | (None, Some(commit)) => self.tree_paths(commit).await?,
+            (None, None) => return Ok(Vec::new()),
+        };
+
+        // A post is any directory holding a config.toml on either side
+        let mut post_dirs = std::collections::HashSet::new();
+        for commit in [old, new].into_iter().flatten() {
+            for path in self.tree_paths(commit).await? {
+                if let Some(dir) = path.strip_suffix("/config.toml") {
+                    post_dirs.insert(dir.to_string());
+                }
+            }
+        }
+
+        let content_dir = content_dir.trim_matches('/');
+        let mut posts = std::collections::BTreeSet::new();
+        for file in &files {
+            let mut dir = file.as_str();
+            while let Some((parent, _)) = dir.rsplit_once('/') {
+                if post_dirs.contains(parent) {
+                    let slug = if content_dir.is_empty() {
+                        Some(parent)
+                    } else {
+                        parent
+                            .strip_prefix(content_dir)
+                            .and_then(|rest| rest.strip_prefix('/'))
+                    };
+                    if let Some(slug) = slug {
+                        posts.insert(slug.to_string());
+                    }
+                    break;
+                }
+                dir = parent;
+            }
+        }
+        Ok(posts.into_iter().collect())
+    }
+
+    /// Every file path in `commit`
+    async fn tree_paths(&self, commit: &str) -> Result<Vec<String>> {
+        self.git_paths(&["ls-tree", "-r", "-z", "--name-only", commit])
+            .await
+    }
+
+    /// Run a git command that prints NUL-separated paths
+    async fn git_paths(&self, args: &[&str]) -> Result<Vec<String>> {
+        let out = run(Command::new("git")
+            .arg("-C")
+            .arg(self.repo_path())
+            .args(args))
+        .await?;
+        Ok(String::from_utf8_lossy(&out)
+            .split('\0')
+            .filter(|p| !p.is_empty())
+            .map(str::to_string)
+            .collect())
+    }
+}
+
+/// Why `protection` forbids `update`, if it does
+async fn protection_violation(
+    protection: &BranchProtectionConfig,
+    update: &RefUpdate,
+    quarantine: &Quarantine,
+) -> Result<Option<&'static str>> {
+    let pushable = protection.pushable_refs.is_empty()
+        || protection
+            .pushable_refs
+            .iter()
+            .any(|pattern| glob_matches(pattern, &update.refname));
+    if !pushable {
+        return Ok(Some("this ref may not be pushed to"));
+    }
+    if update.is_delete() {
+        return Ok(protection
+            .reject_deletions
+            .then_some("deleting this ref is not allowed"));
+    }
+    if protection.reject_non_fast_forward
+        && !update.is_create()
+        && !quarantine.is_ancestor(&update.old, &update.new).await?
+    {
+        return Ok(Some(
+            "non-fast-forward updates are not allowed; push with the override token to rewrite history",
+        ));
+    }
+    Ok(None)
+}
+
+/// Match `text` against a pattern in which `*` matches any run of characters
+pub(crate) fn glob_matches(pattern: &str, text: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern == text,
+        Some((prefix, rest)) => {
+            let Some(text) = text.strip_prefix(prefix) else {
+                return false;
+            };
+            if rest.is_empty() {
+                return true;
+            }
+            (0..=text.len())
+                .filter(|&i| text.is_char_boundary(i))
+                .any(|i| glob_matches(rest, &text[i..]))
+        }
+    }
+}
+
+/// Messages for every changed file on `branch` that `policy` forbids
+fn policy_violations(
+    policy: &PushPolicyConfig,
+    content: &ContentConfig,
+    branch: &str,
+    files: &[ChangedFile],
+    sizes: &[Option<u64>],
+) -> Vec<String> {
+    let max_size = policy
+        .max_file_size
+        .unwrap_or(content.max_content_file_size);
+    let banned: Vec<String> = policy
+        .banned_extensions
+        .iter()
+        .map(|e| e.trim_start_matches('.').to_lowercase())
+        .collect();
+
+    let mut problems = Vec::new();
+    for (file, size) in files.iter().zip(sizes) {
+        let mut problem = |message: String| {
+            problems.push(format!("{}: {}: {}", branch, file.path, message));
+        };
+
+        let allowed = policy.allowed_paths.is_empty()
+            || policy
+                .allowed_paths
+                .iter()
+                .any(|prefix| path_has_prefix(&file.path, prefix));
+        if !allowed {
+            problem(format!(
+                "changes are only allowed under {}",
+                policy.allowed_paths.join(", ")
+            ));
+            continue;
+        }
+        if file.is_delete() {
+            continue;
+        }
+        if policy.reject_symlinks && file.mode == SYMLINK_MODE {
+            problem("symlinks are not allowed".to_string());
+        }
+        if let Some(ext) = Path::new(&file.path)
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(str::to_lowercase)
+            && banned.contains(&ext)
+        {
+            problem(format!(".{} files are not allowed", ext));
+        }
+        if let Some(size) = *size
+            && size > max_size
+        {
+            problem(format!(
+                "file is {} bytes, over the {} byte limit",
+                size, max_size
+            ));
+        }
+    }
+    problems
+}
+
+/// Whether `path` is `prefix` or inside it (`content` and `content/` both match `content/a`)
+fn path_has_prefix(path: &str, prefix: &str) -> bool {
+    let prefix = prefix.trim_matches('/');
+    prefix.is_empty()
+        || path == prefix
+        || path
+            .strip_prefix(prefix)
+            .is_some_and(|rest| rest.starts_with('/'))
+}
+
+/// A scratch object store layered over the repository's, plus scratch work trees
+struct Quarantine {
+    dir: tempfile::TempDir,
+    git_dir: PathBuf,
+    repo_objects: PathBuf,
+}
+
+impl Quarantine {
+    fn new(repo_path: &Path) -> Result<Self> {
+        let git_dir = git::git_dir(repo_path);
+        let repo_objects = std::fs::canonicalize(git_dir.join("objects"))?;
+        let dir = tempfile::Builder::new()
+            .prefix("riley-cms-push-")
+            .tempdir()?;
+        std::fs::create_dir_all(dir.path().join("objects/pack"))?;
+        Ok(Self {
+            dir,
+            git_dir,
+            repo_objects,
+        })
+    }
+
+    /// A git command that reads the repository's objects and writes to the quarantine
+    fn git(&self) -> Command {
+        let mut command = Command::new("git");
+        command
+            .arg("--git-dir")
+            .arg(&self.git_dir)
+            .env("GIT_OBJECT_DIRECTORY", self.dir.path().join("objects"))
+            .env("GIT_ALTERNATE_OBJECT_DIRECTORIES", &self.repo_objects)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped());
+        command
+    }
+
+    async fn index_pack(&self, pack: &[u8]) -> Result<()> {
+        if pack.is_empty() {
+            return Ok(());
+        }
+        let mut child = self
+            .git()
+            .args(["index-pack", "--stdin", "--fix-thin"])
+            .stdin(Stdio::piped())
+            .spawn()
+            .map_err(|e| Error::Git(format!("Failed to run git index-pack: {}", e)))?;
+
+        let mut stdin = child
+            .stdin
+            .take()
+            .ok_or_else(|| Error::Git("Failed to open stdin pipe".to_string()))?;
+        let write = async {
+            let result = stdin.write_all(pack).await;
+            drop(stdin);
+            result
+        };
+        let (written, output) = tokio::join!(write, child.wait_with_output());
+        let output = output.map_err(|e| Error::Git(format!("git index-pack failed: {}", e)))?;
+        if !output.status.success() {
+            return Err(Error::Git(format!(
+                "git index-pack failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        written.map_err(|e| Error::Git(format!("Failed to write pack: {}", e)))
+    }
+
+    /// Whether `old` is an ancestor of `new` (so moving a ref from one to the other is a fast-forward)
+    async fn is_ancestor(&self, old: &str, new: &str) -> Result<bool> {
+        let output = self
+            .git()
+            .args(["merge-base", "--is-ancestor", old, new])
+            .output()
+            .await
+            .map_err(|e| Error::Git(format!("Failed to run git merge-base: {}", e)))?;
+        match output.status.code() {
+            Some(0) => Ok(true),
+            Some(1) => Ok(false),
+            _ => Err(Error::Git(format!(
+                "git merge-base failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ))),
+        }
+    }
+
+    /// Commits an update brings into the repository, each with its parents.
+    ///
+    /// Commits already reachable from the old tip or any existing ref are left out.
+    async fn new_commits(&self, update: &RefUpdate) -> Result<Vec<(String, Vec<String>)>> {
+        let mut command = self.git();
+        command.args(["rev-list", "--parents", &update.new]);
+        if !update.is_create() {
+            command.arg(format!("^{}", update.old));
+        }
+        command.args(["--not", "--all"]);
+        let out = run(&mut command).await?;
+
+        Ok(String::from_utf8_lossy(&out)
+            .lines()
+            .filter_map(|line| {
+                let mut oids = line.split(' ').map(String::from);
+                Some((oids.next()?, oids.collect()))
+            })
+            .collect())
+    }
+
+    /// Files that differ between `base` (or the empty tree) and `commit`
+    async fn changed_files(&self, base: Option<&str>, commit: &str) -> Result<Vec<ChangedFile>> {
+        let base = match base {
+            Some(base) => base.to_string(),
+            None => {
+                let out = run(self.git().args(["hash-object", "-t", "tree", "/dev/null"])).await?;
+                String::from_utf8_lossy(&out).trim().to_string()
+            }
+        };
+        let out = run(self.git().args([
+            "diff-tree",
+            "-r",
+            "-z",
+            "--raw",
+            "--no-renames",
+            &base,
+            commit,
+        ]))
+        .await?;
+
+        // Records are `:oldmode newmode oldoid newoid status\0path\0`
+        let out = String::from_utf8_lossy(&out);
+        let mut fields = out.split('\0');
+        let mut files = Vec::new();
+        while let (Some(meta), Some(path)) = (fields.next(), fields.next()) {
+            let meta: Vec<&str> = meta.trim_start_matches(':').split(' ').collect();
+            if let [_, mode, _, oid, _] = meta[..] {
+                files.push(ChangedFile {
+                    path: path.to_string(),
+                    mode: mode.to_string(),
+                    oid: oid.to_string(),
+                });
+            }
+        }
+        Ok(files)
+    }
+
+    /// Size of each file's new blob (`None` for deletions)
+    async fn blob_sizes(&self, files: &[ChangedFile]) -> Result<Vec<Option<u64>>> {
+        let present: Vec<&str> = files
+            .iter()
+            .filter(|f| !f.is_delete())
+            .map(|f| f.oid.as_str())
+            .collect();
+        if present.is_empty() {
+            return Ok(vec![None; files.len()]);
+        }
+
+        let mut child = self
+            .git()
+            .args(["cat-file", "--batch-check=%(objectsize)"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .map_err(|e| Error::Git(format!("Failed to run git cat-file: {}", e)))?;
+        let mut stdin = child
+            .stdin
+            .take()
+            .ok_or_else(|| Error::Git("Failed to open stdin pipe".to_string()))?;
+        let input = present.join("\n") + "\n";
+        let write = async {
+            let result = stdin.write_all(input.as_bytes()).await;
+            drop(stdin);
+            result
+        };
+        let (written, output) = tokio::join!(write, child.wait_with_output());
+        let output = output.map_err(|e| Error::Git(format!("git cat-file failed: {}", e)))?;
+        written.map_err(|e| Error::Git(format!("Failed to write to git cat-file: {}", e)))?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let mut sizes = stdout.lines().map(|l| l.trim().parse::<u64>().ok());
+        Ok(files
+            .iter()
+            .map(|f| {
+                if f.is_delete() {
+                    None
+                } else {
+                    sizes.next().flatten()
+                }
+            })
+            .collect())
+    }
+
+    /// Check `commit` out into a fresh scratch directory and return its path
+    async fn checkout(&self, commit: &str, n: usize) -> Result<PathBuf> {
+        let tree = self.dir.path().join(format!("tree-{}", n));
+        std::fs::create_dir_all(&tree)?;
+        let index = self.dir.path().join(format!("index-{}", n));
+
+        run(self
+            .git()
+            .env("GIT_INDEX_FILE", &index)
+            .args(["read-tree", commit]))
+        .await?;
+        run(self
+            .git()
+            .env("GIT_INDEX_FILE", &index)
+            .arg("--work-tree")
+            .arg(&tree)
+            .args(["checkout-index", "--all"]))
+        .await?;
+        Ok(tree)
+    }
+}
+
+/// Run a git command to completion, returning its stdout
+async fn run(command: &mut Command) -> Result<Vec<u8>> {
+    let output = command
+        .stdout(Stdio::piped())
+        .output()
+        .await
+        .map_err(|e| Error::Git(format!("Failed to run git: {}", e)))?;
+    if !output.status.success() {
+        return Err(Error::Git(format!(
+            "git failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(output.stdout)
+}
+
+fn is_zero_oid(oid: &str) -> bool {
+    oid == ZERO_OID || (!oid.is_empty() && oid.bytes().all(|b| b == b'0'))
+}
+
+/// Read one pkt-line payload at `pos`, advancing it. `None` is a flush packet.
+fn read_pkt_line<'a>(data: &'a [u8], pos: &mut usize) -> Result<Option<&'a [u8]>> {
+    let header = data
+        .get(*pos..*pos + 4)
+        .ok_or_else(|| Error::Git("Truncated receive-pack request".to_string()))?;
+    let len = std::str::from_utf8(header)
+        .ok()
+        .and_then(|h| usize::from_str_radix(h, 16).ok())
+        .ok_or_else(|| Error::Git("Invalid pkt-line length".to_string()))?;
+    if len == 0 {
+        *pos += 4;
+        return Ok(None);
+    }
+    let payload = data
+        .get(*pos + 4..*pos + len)
+        .filter(|_| len >= 4)
+        .ok_or_else(|| Error::Git("Truncated receive-pack request".to_string()))?;
+    *pos += len;
+    Ok(Some(payload))
+}
+
+fn write_pkt_line(out: &mut Vec<u8>, payload: &[u8]) {
+    out.extend_from_slice(format!("{:04x}", payload.len() + 4).as_bytes());
+    out.extend_from_slice(payload);
+}
+
+fn write_side_band(out: &mut Vec<u8>, band: u8, data: &[u8], max: usize) {
+    for chunk in data.chunks(max) {
+        let mut payload = Vec::with_capacity(chunk.len() + 1);
+        payload.push(band);
+        payload.extend_from_slice(chunk);
+        write_pkt_line(out, &payload);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OLD: &str = "1111111111111111111111111111111111111111";
+    const NEW: &str = "2222222222222222222222222222222222222222";
+
+    fn pkt(payload: &str) -> Vec<u8> {
+        let mut out = Vec::new();
+        write_pkt_line(&mut out, payload.as_bytes());
+        out
+    }
+
+    #[test]
+    fn test_parse_commands_and_pack() {
+        let mut body = pkt(&format!(
+            "{} {} refs/heads/main\0report-status side-band-64k\n",
+            OLD, NEW
+        ));
+        body.extend(pkt(&format!("{} {} refs/heads/old\n", OLD, ZERO_OID)));
+        body.extend_from_slice(b"0000PACKdata");
+
+        let request = ReceivePack::parse(&body).unwrap();
+        assert_eq!(request.updates.len(), 2);
+        assert_eq!(request.updates[0].branch(), Some("main"));
+        assert!(!request.updates[0].is_delete());
+        assert!(request.updates[1].is_delete());
+        assert!(request.has_capability("side-band-64k"));
+        assert_eq!(request.pack(&body), b"PACKdata");
+    }
+
+    #[test]
+    fn test_parse_skips_push_options() {
+        let mut body = pkt(&format!(
+            "{} {} refs/heads/main\0report-status push-options\n",
+            ZERO_OID, NEW
+        ));
+        body.extend_from_slice(b"0000");
+        body.extend(pkt("ci.skip\n"));
+        body.extend_from_slice(b"0000PACK");
+
+        let request = ReceivePack::parse(&body).unwrap();
+        assert!(request.updates[0].is_create());
+        assert_eq!(request.pack(&body), b"PACK");
+    }
+
+    #[test]
+    fn test_parse_rejects_truncated_body() {
+        assert!(ReceivePack::parse(b"00ffshort").is_err());
+        assert!(ReceivePack::parse(b"zzzz").is_err());
+    }
+
+    #[test]
+    fn test_rejection_with_side_band() {
+        let body = [
+            pkt(&format!(
+                "{} {} refs/heads/main\0report-status side-band-64k\n",
+                OLD, NEW
+            )),
+            b"0000".to_vec(),
+        ]
+        .concat();
+        let request = ReceivePack::parse(&body).unwrap();
+        let out = request.rejection("invalid content", &["bad title".to_string()]);
+
+        let mut pos = 0;
+        let progress = read_pkt_line(&out, &mut pos).unwrap().unwrap();
+        assert_eq!(progress, b"\x02bad title\n");
+        let report = read_pkt_line(&out, &mut pos).unwrap().unwrap();
+        assert_eq!(report[0], 1);
+        let mut inner = 1;
+        assert_eq!(
+            read_pkt_line(report, &mut inner).unwrap().unwrap(),
+            b"unpack ok\n"
+        );
+        assert_eq!(
+            read_pkt_line(report, &mut inner).unwrap().unwrap(),
+            b"ng refs/heads/main invalid content\n"
+        );
+        assert_eq!(read_pkt_line(report, &mut inner).unwrap(), None);
+        assert_eq!(read_pkt_line(&out, &mut pos).unwrap(), None);
+        assert_eq!(pos, out.len());
+    }
+
+    fn changed(path: &str, mode: &str, oid: &str) -> ChangedFile {
+        ChangedFile {
+            path: path.to_string(),
+            mode: mode.to_string(),
+            oid: oid.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_policy_violations() {
+        let policy = PushPolicyConfig {
+            allowed_paths: vec!["content/".to_string()],
+            max_file_size: None,
+            banned_extensions: vec![".EXE".to_string()],
+            reject_symlinks: true,
+        };
+        let content = ContentConfig {
+            max_content_file_size: 100,
+            ..toml::from_str("repo_path = '/repo'").unwrap()
+        };
+        let files = [
+            changed("content/ok/content.mdx", "100644", NEW),
+            changed("content/big.png", "100644", NEW),
+            changed("content/tool.exe", "100755", NEW),
+            changed("content/link", SYMLINK_MODE, NEW),
+            changed("README.md", "000000", ZERO_OID),
+            changed("contentious.txt", "100644", NEW),
+        ];
+        let sizes = [Some(10), Some(101), Some(1), Some(5), None, Some(1)];
+
+        let problems = policy_violations(&policy, &content, "main", &files, &sizes);
+        assert_eq!(
+            problems,
+            vec![
+                "main: content/big.png: file is 101 bytes, over the 100 byte limit",
+                "main: content/tool.exe: .exe files are not allowed",
+                "main: content/link: symlinks are not allowed",
+                "main: README.md: changes are only allowed under content/",
+                "main: contentious.txt: changes are only allowed under content/",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_glob_matches() {
+        assert!(glob_matches("refs/heads/main", "refs/heads/main"));
+        assert!(!glob_matches("refs/heads/main", "refs/heads/main2"));
+        assert!(glob_matches(
+            "refs/heads/drafts/*",
+            "refs/heads/drafts/new-post"
+        ));
+        assert!(!glob_matches("refs/heads/drafts/*", "refs/heads/main"));
+        assert!(glob_matches("refs/*/release-*", "refs/tags/release-1.0"));
+        assert!(glob_matches("*", "refs/heads/anything"));
+    }
+
+    #[test]
+    fn test_path_has_prefix() {
+        assert!(path_has_prefix("content/a.mdx", "content"));
+        assert!(path_has_prefix("content/a.mdx", "/content/"));
+        assert!(path_has_prefix("anything", ""));
+        assert!(!path_has_prefix("contentious", "content"));
+    }
+
+    #[test]
+    fn test_rejection_without_side_band() {
+        let body = [
+            pkt(&format!("{} {} refs/heads/main\0report-status\n", OLD, NEW)),
+            b"0000".to_vec(),
+        ]
+        .concat();
+        let request = ReceivePack::parse(&body).unwrap();
+        let out = request.rejection("invalid content", &["ignored".to_string()]);
+        assert_eq!(
+            out,
+            [
+                pkt("unpack ok\n"),
+                pkt("ng refs/heads/main invalid content\n"),
+                b"0000".to_vec()
+            ]
+            .concat()
+        );
+    }
+}
@@ -0,0 +1,266 @@
+//! Server-side rendering of post content to sanitized HTML
+//!
+//! For consumers that can't run MDX (newsletters, static fallbacks). Only the
+//! Markdown part of `content.mdx` is rendered; JSX is stripped or passed
+//! through per [`RenderConfig`], and the result is sanitized with ammonia.
+//! Headings get stable, GitHub-style `id` anchors.
+
+use crate::config::RenderConfig;
+use crate::mdx;
+use crate::types::TocEntry;
+use pulldown_cmark::{CowStr, Event, Options, Parser, Tag, TagEnd, html};
+use std::collections::{HashMap, HashSet};
+
+/// Average adult silent reading speed used for reading time estimates
+const WORDS_PER_MINUTE: usize = 200;
+
+/// A post's content rendered at load time
+#[derive(Debug)]
+pub(crate) struct Rendered {
+    /// Sanitized HTML
+    pub html: String,
+    /// Headings in document order, with the anchors used in `html`
+    pub toc: Vec<TocEntry>,
+}
+
+/// Render MDX to sanitized HTML and extract its table of contents
+pub(crate) fn render(source: &str, config: &RenderConfig) -> Rendered {
+    let preprocessed = mdx::preprocess(source, config.jsx);
+
+    let options = Options::ENABLE_TABLES
+        | Options::ENABLE_FOOTNOTES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS;
+    let (events, toc) =
+        with_heading_ids(Parser::new_ext(&preprocessed.markdown, options).collect());
+
+    let mut unsafe_html = String::with_capacity(preprocessed.markdown.len() * 3 / 2);
+    html::push_html(&mut unsafe_html, events.into_iter());
+
+    Rendered {
+        html: sanitize(&unsafe_html, &preprocessed.components),
+        toc,
+    }
+}
+
+/// Estimated reading time in whole minutes (at least 1 for non-empty content)
+pub(crate) fn reading_time_minutes(word_count: usize) -> u32 {
+    word_count.div_ceil(WORDS_PER_MINUTE) as u32
+}
+
+/// Turn heading text into an anchor slug (lowercase, words joined by `-`)
+pub(crate) fn heading_slug(text: &str) -> String {
+    let mut slug = String::with_capacity(text.len());
+    for c in text.trim().chars() {
+        if c.is_alphanumeric() {
+            slug.extend(c.to_lowercase());
+        } else if (c == ' ' || c == '-' || c == '_') && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_matches('-');
+    if slug.is_empty() {
+        "section".to_string()
+    } else {
+        slug.to_string()
+    }
+}
+
+/// Give every heading a unique `id`, suffixing duplicates with `-1`, `-2`, ...
+fn with_heading_ids(mut events: Vec<Event<'_>>) -> (Vec<Event<'_>>, Vec<TocEntry>) {
+    // Last suffix tried per slug, and every anchor handed out so far, so a
+    // suffixed anchor never collides with a heading that slugs to it directly
+    let mut suffixes: HashMap<String, usize> = HashMap::new();
+    let mut used: HashSet<String> = HashSet::new();
+    let mut toc = Vec::new();
+
+    for i in 0..events.len() {
+        if !matches!(events[i], Event::Start(Tag::Heading { .. })) {
+            continue;
+        }
+        let text: String = events[i + 1..]
+            .iter()
+            .take_while(|e| !matches!(e, Event::End(TagEnd::Heading(_))))
+            .filter_map(|e| match e {
+                Event::Text(t) | Event::Code(t) => Some(t.as_ref()),
+                _ => None,
+            })
+            .collect();
+
+        let base = heading_slug(&text);
+        let mut anchor = base.clone();
+        while used.contains(&anchor) {
+            let suffix = suffixes.entry(base.clone()).or_insert(0);
+            *suffix += 1;
+            anchor = format!("{}-{}", base, suffix);
+        }
+        used.insert(anchor.clone());
+
+        if let Event::Start(Tag::Heading { level, id, .. }) = &mut events[i] {
+            toc.push(TocEntry {
+                level: *level as u8,
+                text: text.trim().to_string(),
+                anchor: anchor.clone(),
+            });
+            *id = Some(CowStr::from(anchor));
+        }
+    }
+
+    (events, toc)
+}
+
+/// Sanitize rendered HTML, allowing heading anchors, code languages, footnotes,
+/// task lists and the props of any passed-through JSX components
+fn sanitize(unsafe_html: &str, components: &[(String, Vec<String>)]) -> String {
+    // Components are `div` wrappers with inert `data-` props, so a post can
+    // never get a tag allowed by naming a component after it
+    let props: HashSet<String> = components
+        .iter()
+        .flat_map(|(_, props)| props)
+        .map(|prop| format!("data-{}", prop))
+        .chain(std::iter::once("data-component".to_string()))
+        .collect();
+
+    let mut builder = ammonia::Builder::default();
+    for heading in ["h1", "h2", "h3", "h4", "h5", "h6"] {
+        builder.add_tag_attributes(heading, &["id"]);
+    }
+    builder
+        .add_tags(&["input"])
+        .add_tag_attributes("input", &["type", "checked", "disabled"])
+        .add_tag_attributes("code", &["class"])
+        .add_tag_attributes("div", &["class", "id"])
+        .add_tag_attributes("sup", &["class", "id"])
+        .add_tag_attributes("div", props.iter().map(String::as_str));
+
+    builder.clean(unsafe_html).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::JsxMode;
+
+    fn render_html(source: &str, config: &RenderConfig) -> String {
+        render(source, config).html
+    }
+
+    fn render_default(source: &str) -> String {
+        render_html(source, &RenderConfig::default())
+    }
+
+    #[test]
+    fn test_heading_anchors_are_stable_and_unique() {
+        let html = render_default("# Hello, World!\n\n## Setup\n\n## Setup\n\n### `code` & more");
+        assert!(html.contains(r#"<h1 id="hello-world">"#));
+        assert!(html.contains(r#"<h2 id="setup">"#));
+        assert!(html.contains(r#"<h2 id="setup-1">"#));
+        assert!(html.contains(r#"<h3 id="code-more">"#));
+
+        // A suffixed anchor skips ids that real headings already have, or will have
+        let html = render_default("## Setup\n\n## Setup\n\n## Setup 1\n\n## Setup 2\n\n## Setup");
+        let ids: Vec<&str> = html
+            .split(r#"id=""#)
+            .skip(1)
+            .filter_map(|rest| rest.split('"').next())
+            .collect();
+        assert_eq!(
+            ids,
+            vec!["setup", "setup-1", "setup-1-1", "setup-2", "setup-3"]
+        );
+    }
+
+    #[test]
+    fn test_markdown_features() {
+        let html = render_default("| a |\n|---|\n| b |\n\n~~old~~\n\n```rust\nfn main() {}\n```\n");
+        assert!(html.contains("<table>"));
+        assert!(html.contains("<del>old</del>"));
+        assert!(html.contains(r#"<code class="language-rust">"#));
+    }
+
+    #[test]
+    fn test_output_is_sanitized() {
+        let html = render_default(
+            "<script>alert(1)</script>\n\n[x](javascript:alert(1))\n\n<img src=\"a.png\" onerror=\"alert(1)\">",
+        );
+        assert!(!html.contains("<script"));
+        assert!(!html.contains("javascript:"));
+        assert!(!html.contains("onerror"));
+    }
+
+    #[test]
+    fn test_jsx_strip() {
+        let html = render_default(
+            "import X from './x'\n\n<Callout type=\"tip\">\nSome **bold** text\n</Callout>\n\n<Chart data={[1, 2]} />\n",
+        );
+        assert!(!html.contains("import"));
+        assert!(!html.to_lowercase().contains("callout"));
+        assert!(!html.to_lowercase().contains("chart"));
+        assert!(html.contains("<strong>bold</strong>"));
+    }
+
+    #[test]
+    fn test_jsx_passthrough() {
+        let config = RenderConfig {
+            jsx: JsxMode::Passthrough,
+        };
+        let html = render_html(
+            "<Callout type=\"tip\" onClick={go} style=\"x\">\nHi\n</Callout>\n",
+            &config,
+        );
+        assert!(html.contains(r#"<div data-component="Callout" data-type="tip">"#));
+        assert!(html.contains("Hi\n</div>"));
+        assert!(!html.contains("style"));
+        assert!(!html.contains("onclick"));
+    }
+
+    #[test]
+    fn test_jsx_passthrough_never_allows_dangerous_tags() {
+        let config = RenderConfig {
+            jsx: JsxMode::Passthrough,
+        };
+        let html = render_html(
+            "<Script src=\"x.js\">\nalert(1)\n</Script>\n\n<Style>body{}</Style>\n\n<Iframe src=\"https://evil.example\" />\n\n<script>alert(2)</script>\n",
+            &config,
+        );
+        assert!(html.contains(r#"<div data-component="Script" data-src="x.js">"#));
+        assert!(html.contains(r#"<div data-component="Iframe""#));
+        assert!(!html.to_lowercase().contains("<script"));
+        assert!(!html.to_lowercase().contains("<style"));
+        assert!(!html.to_lowercase().contains("<iframe"));
+        assert!(!html.contains("alert(2)"));
+    }
+
+    #[test]
+    fn test_toc_matches_anchors() {
+        let rendered = render(
+            "# Intro\n\n## Setup\n\n### Install `cargo`\n\n## Setup\n",
+            &RenderConfig::default(),
+        );
+        let toc: Vec<(u8, &str, &str)> = rendered
+            .toc
+            .iter()
+            .map(|e| (e.level, e.text.as_str(), e.anchor.as_str()))
+            .collect();
+        assert_eq!(
+            toc,
+            vec![
+                (1, "Intro", "intro"),
+                (2, "Setup", "setup"),
+                (3, "Install cargo", "install-cargo"),
+                (2, "Setup", "setup-1"),
+            ]
+        );
+        for entry in &rendered.toc {
+            assert!(rendered.html.contains(&format!("id=\"{}\"", entry.anchor)));
+        }
+    }
+
+    #[test]
+    fn test_reading_time() {
+        assert_eq!(reading_time_minutes(0), 0);
+        assert_eq!(reading_time_minutes(1), 1);
+        assert_eq!(reading_time_minutes(200), 1);
+        assert_eq!(reading_time_minutes(201), 2);
+    }
+}
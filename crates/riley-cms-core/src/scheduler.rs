@@ -0,0 +1,74 @@
+//! Scheduled publishing
+//!
+//! Content with a future `goes_live_at` becomes visible once that time passes,
+//! without a reload. The scheduler sleeps until the next such time and fires
+//! webhooks for whatever went live, so receivers revalidate on time.
+
+use crate::content::ContentCache;
+use crate::webhooks::WebhookQueue;
+use chrono::Utc;
+use std::sync::Arc;
+use tokio::sync::{Notify, RwLock};
+use tokio::task::JoinHandle;
+use tokio::time::Duration;
+
+/// Longest sleep between checks, in case the system clock jumps
+const MAX_SLEEP: Duration = Duration::from_secs(3600);
+
+/// Handle to the scheduler task. Dropping it stops the task.
+pub(crate) struct Scheduler {
+    task: JoinHandle<()>,
+}
+
+impl Scheduler {
+    /// Start the scheduler on the current tokio runtime.
+    ///
+    /// `reloaded` is notified whenever a refresh swaps in new content.
+    pub fn spawn(
+        cache: Arc<RwLock<ContentCache>>,
+        reloaded: Arc<Notify>,
+        webhooks: Arc<WebhookQueue>,
+    ) -> Self {
+        Self {
+            task: tokio::spawn(run(cache, reloaded, webhooks)),
+        }
+    }
+}
+
+impl Drop for Scheduler {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn run(cache: Arc<RwLock<ContentCache>>, reloaded: Arc<Notify>, webhooks: Arc<WebhookQueue>) {
+    // Everything live as of `checked` has been announced
+    let mut checked = Utc::now();
+    loop {
+        let next = cache.read().await.next_scheduled(checked);
+        let sleep = next
+            .map(|at| (at - Utc::now()).to_std().unwrap_or_default())
+            .unwrap_or(MAX_SLEEP)
+            .min(MAX_SLEEP);
+        tokio::select! {
+            _ = tokio::time::sleep(sleep) => {}
+            _ = reloaded.notified() => {
+                // Webhooks for the new content describe it as of the refresh
+                checked = Utc::now();
+                continue;
+            }
+        }
+
+        let now = Utc::now();
+        let changes = cache.read().await.changes_between(checked, now);
+        checked = now;
+        if !changes.is_empty() {
+            tracing::info!(
+                "Scheduled content went live: {} post(s), {} series",
+                changes.posts.created.len(),
+                changes.series.created.len()
+            );
+            webhooks.enqueue(&changes).await;
+        }
+    }
+}
@@ -0,0 +1,476 @@
+//! Full-text search over posts
+//!
+//! [`SearchIndex`] is an in-memory inverted index built when content is loaded.
+//! Title, subtitle, tags, preview text and the MDX body are indexed with
+//! per-field weights; MDX/JSX syntax is stripped from the body first.
+//!
+//! Queries match whole terms, except the last term which also matches as a
+//! prefix (so results update sensibly while a reader is still typing). A post
+//! must match every query term to be returned.
+
+use crate::config::JsxMode;
+use crate::mdx;
+use crate::types::Post;
+use pulldown_cmark::{Event, Parser};
+use std::collections::{BTreeMap, HashMap};
+
+const TITLE_WEIGHT: f32 = 5.0;
+const TAG_WEIGHT: f32 = 4.0;
+const SUBTITLE_WEIGHT: f32 = 3.0;
+const PREVIEW_WEIGHT: f32 = 2.0;
+const BODY_WEIGHT: f32 = 1.0;
+
+/// Queries are truncated to this many terms
+const MAX_QUERY_TERMS: usize = 10;
+
+/// Approximate snippet length in bytes
+const SNIPPET_LEN: usize = 160;
+
+/// Context shown before the first match in a snippet, in bytes
+const SNIPPET_LEAD: usize = 60;
+
+/// In-memory inverted index over all loaded posts
+#[derive(Debug, Default)]
+pub(crate) struct SearchIndex {
+    /// Term -> postings, sorted so prefix queries are a range scan
+    terms: BTreeMap<String, Vec<Posting>>,
+    docs: Vec<IndexedDoc>,
+    doc_ids: HashMap<String, usize>,
+}
+
+#[derive(Debug)]
+struct IndexedDoc {
+    /// Key of the post in the content cache
+    key: String,
+    /// Body with MDX/JSX syntax stripped, used for snippets
+    text: String,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Posting {
+    doc: usize,
+    weight: f32,
+}
+
+/// A parsed search query
+struct Query {
+    terms: Vec<String>,
+}
+
+impl Query {
+    fn parse(query: &str) -> Self {
+        let mut terms: Vec<String> = Vec::new();
+        for (_, token) in tokenize(query) {
+            let term = token.to_lowercase();
+            if !terms.contains(&term) {
+                terms.push(term);
+            }
+            if terms.len() == MAX_QUERY_TERMS {
+                break;
+            }
+        }
+        Self { terms }
+    }
+
+    /// Whether a (lowercased) token matches any query term
+    fn matches(&self, token: &str) -> bool {
+        let last = self.terms.len().saturating_sub(1);
+        self.terms
+            .iter()
+            .enumerate()
+            .any(|(i, term)| token == term || (i == last && token.starts_with(term.as_str())))
+    }
+}
+
+impl SearchIndex {
+    /// Build the index from `(cache key, post)` pairs
+    pub(crate) fn build<'a>(posts: impl IntoIterator<Item = (&'a String, &'a Post)>) -> Self {
+        let mut index = Self::default();
+
+        for (key, post) in posts {
+            let doc = index.docs.len();
+            let text = strip_mdx(&post.content);
+
+            let mut weights: HashMap<String, f32> = HashMap::new();
+            add_field(&mut weights, &post.title, TITLE_WEIGHT);
+            if let Some(ref subtitle) = post.subtitle {
+                add_field(&mut weights, subtitle, SUBTITLE_WEIGHT);
+            }
+            for tag in post.tags.iter().flatten() {
+                add_field(&mut weights, tag, TAG_WEIGHT);
+            }
+            add_field(&mut weights, &post.preview_text, PREVIEW_WEIGHT);
+            add_field(&mut weights, &text, BODY_WEIGHT);
+
+            for (term, weight) in weights {
+                index
+                    .terms
+                    .entry(term)
+                    .or_default()
+                    .push(Posting { doc, weight });
+            }
+
+            index.doc_ids.insert(key.clone(), doc);
+            index.docs.push(IndexedDoc {
+                key: key.clone(),
+                text,
+            });
+        }
+
+        index
+    }
+
+    /// Find posts matching every term of `query`.
+    ///
+    /// Returns `(cache key, score)` pairs, best match first.
+    pub(crate) fn search(&self, query: &str) -> Vec<(&str, f32)> {
+        let query = Query::parse(query);
+        if query.terms.is_empty() || self.docs.is_empty() {
+            return Vec::new();
+        }
+
+        let total_docs = self.docs.len() as f32;
+        let last = query.terms.len() - 1;
+        let mut scores: HashMap<usize, (f32, usize)> = HashMap::new();
+
+        for (i, term) in query.terms.iter().enumerate() {
+            // Per-document weight for this query term (summed across prefix expansions)
+            let mut term_weights: HashMap<usize, f32> = HashMap::new();
+            let postings: Box<dyn Iterator<Item = &Vec<Posting>>> = if i == last {
+                Box::new(
+                    self.terms
+                        .range(term.clone()..)
+                        .take_while(|(t, _)| t.starts_with(term.as_str()))
+                        .map(|(_, p)| p),
+                )
+            } else {
+                Box::new(self.terms.get(term).into_iter())
+            };
+            for posting in postings.flatten() {
+                *term_weights.entry(posting.doc).or_default() += posting.weight;
+            }
+
+            if term_weights.is_empty() {
+                return Vec::new();
+            }
+            let idf = (1.0 + total_docs / term_weights.len() as f32).ln();
+            for (doc, weight) in term_weights {
+                let entry = scores.entry(doc).or_default();
+                entry.0 += weight * idf;
+                entry.1 += 1;
+            }
+        }
+
+        let mut hits: Vec<(&str, f32)> = scores
+            .into_iter()
+            .filter(|(_, (_, matched))| *matched == query.terms.len())
+            .map(|(doc, (score, _))| (self.docs[doc].key.as_str(), score))
+            .collect();
+        hits.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        hits
+    }
+
+    /// Build an HTML snippet of a post's body around the first match.
+    ///
+    /// The snippet is HTML-escaped with matches wrapped in `<mark>`. Returns
+    /// `None` if the body doesn't contain any query term.
+    pub(crate) fn snippet(&self, key: &str, query: &str) -> Option<String> {
+        let doc = &self.docs[*self.doc_ids.get(key)?];
+        highlight_excerpt(&doc.text, &Query::parse(query))
+    }
+}
+
+/// Build a highlighted snippet from the start of `text` (used when the body has no match)
+pub(crate) fn snippet_from_start(text: &str, query: &str) -> String {
+    let query = Query::parse(query);
+    let end = floor_char_boundary(text, SNIPPET_LEN.min(text.len()));
+    let mut snippet = highlight(&text[..end], &query);
+    if end < text.len() {
+        snippet.push('…');
+    }
+    snippet
+}
+
+fn highlight_excerpt(text: &str, query: &Query) -> Option<String> {
+    let (first, _) = tokenize(text).find(|(_, token)| query.matches(&token.to_lowercase()))?;
+
+    let mut start = floor_char_boundary(text, first.saturating_sub(SNIPPET_LEAD));
+    // Start on a word boundary
+    if start > 0
+        && let Some(space) = text[start..first].find(char::is_whitespace)
+    {
+        start += space + 1;
+    }
+    let mut end = floor_char_boundary(text, (start + SNIPPET_LEN).min(text.len()));
+    if end < text.len()
+        && let Some(space) = text[first..end].rfind(char::is_whitespace)
+    {
+        end = first + space;
+    }
+
+    let mut snippet = String::new();
+    if start > 0 {
+        snippet.push('…');
+    }
+    snippet.push_str(&highlight(&text[start..end], query));
+    if end < text.len() {
+        snippet.push('…');
+    }
+    Some(snippet)
+}
+
+/// HTML-escape `text`, wrapping tokens that match the query in `<mark>`
+fn highlight(text: &str, query: &Query) -> String {
+    let mut out = String::with_capacity(text.len() + 16);
+    let mut pos = 0;
+    for (offset, token) in tokenize(text) {
+        if query.matches(&token.to_lowercase()) {
+            out.push_str(&escape_html(&text[pos..offset]));
+            out.push_str("<mark>");
+            out.push_str(&escape_html(token));
+            out.push_str("</mark>");
+            pos = offset + token.len();
+        }
+    }
+    out.push_str(&escape_html(&text[pos..]));
+    out
+}
+
+/// Add each token of `text` to the term weights, dampening repeated terms
+fn add_field(weights: &mut HashMap<String, f32>, text: &str, field_weight: f32) {
+    let mut counts: HashMap<String, u32> = HashMap::new();
+    for (_, token) in tokenize(text) {
+        *counts.entry(token.to_lowercase()).or_default() += 1;
+    }
+    for (term, count) in counts {
+        *weights.entry(term).or_default() += field_weight * (1.0 + (count as f32).ln());
+    }
+}
+
+/// Split text into alphanumeric tokens, yielding `(byte offset, token)`
+fn tokenize(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|token| !token.is_empty())
+        .map(move |token| (token.as_ptr() as usize - text.as_ptr() as usize, token))
+}
+
+/// Reduce MDX to its readable text.
+///
+/// Markdown syntax, ESM `import`/`export` lines, JSX tags and `{expressions}`
+/// are removed; text inside JSX components and code is kept.
+pub(crate) fn strip_mdx(source: &str) -> String {
+    let markdown = mdx::preprocess(source, JsxMode::Strip).markdown;
+
+    let mut out = String::with_capacity(markdown.len());
+    for event in Parser::new(&markdown) {
+        match event {
+            Event::Text(text) | Event::Code(text) => {
+                out.push_str(&text);
+                out.push(' ');
+            }
+            Event::Html(html) | Event::InlineHtml(html) => {
+                out.push_str(&strip_tags(&html));
+                out.push(' ');
+            }
+            Event::SoftBreak | Event::HardBreak => out.push(' '),
+            _ => {}
+        }
+    }
+
+    out.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Number of words in the readable text of an MDX document
+pub(crate) fn word_count(source: &str) -> usize {
+    strip_mdx(source)
+        .split_whitespace()
+        .filter(|word| word.chars().any(char::is_alphanumeric))
+        .count()
+}
+
+/// Remove `<...>` tags, keeping the text between them
+fn strip_tags(html: &str) -> String {
+    let mut out = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => {
+                in_tag = false;
+                out.push(' ');
+            }
+            c if !in_tag => out.push(c),
+            _ => {}
+        }
+    }
+    out
+}
+
+fn escape_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Largest char boundary `<= index`
+fn floor_char_boundary(text: &str, mut index: usize) -> usize {
+    while index > 0 && !text.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn post(slug: &str, title: &str, tags: &[&str], content: &str) -> Post {
+        Post {
+            slug: slug.to_string(),
+            title: title.to_string(),
+            subtitle: None,
+            preview_text: format!("Preview for {}", title),
+            preview_image: None,
+            tags: Some(tags.iter().map(|t| t.to_string()).collect()),
+            goes_live_at: None,
+            series_slug: None,
+            content: content.to_string(),
+            content_html: None,
+            toc: Vec::new(),
+            word_count: 0,
+            reading_time_minutes: 0,
+            created_at: None,
+            updated_at: None,
+            order: None,
+            etag: String::new(),
+            last_modified: None,
+        }
+    }
+
+    fn index(posts: &[Post]) -> SearchIndex {
+        let keyed: Vec<(String, &Post)> = posts.iter().map(|p| (p.slug.clone(), p)).collect();
+        SearchIndex::build(keyed.iter().map(|(k, p)| (k, *p)))
+    }
+
+    #[test]
+    fn test_strip_mdx() {
+        let mdx = r#"import Chart from '../components/Chart'
+export const meta = { draft: true }
+
+# Hello **world**
+
+Some [linked text](https://example.com) and `inline code`.
+
+<Callout type="warning">
+Careful with {props.value} here
+</Callout>
+
+<Chart data={[1, 2, 3]} />
+"#;
+        let text = strip_mdx(mdx);
+        assert_eq!(
+            text,
+            "Hello world Some linked text and inline code . Careful with here"
+        );
+    }
+
+    #[test]
+    fn test_strip_mdx_keeps_code_blocks() {
+        let mdx = "Text {hidden}\n\n```rust\nfn main() { run() }\n```\n";
+        assert_eq!(strip_mdx(mdx), "Text fn main() { run() }");
+    }
+
+    #[test]
+    fn test_word_count_ignores_markup() {
+        let mdx = "import X from './x'\n\n# Two words\n\n<Note kind=\"a\">three more words</Note> — `code`.\n";
+        assert_eq!(word_count(mdx), 6);
+    }
+
+    #[test]
+    fn test_title_matches_rank_above_body_matches() {
+        let posts = vec![
+            post("body", "Something else", &[], "We talk about rust briefly."),
+            post("title", "Learning Rust", &[], "An introduction."),
+        ];
+        let index = index(&posts);
+
+        let hits = index.search("rust");
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].0, "title");
+        assert_eq!(hits[1].0, "body");
+    }
+
+    #[test]
+    fn test_all_terms_required_and_last_term_is_prefix() {
+        let posts = vec![
+            post("a", "Async Rust", &[], "Futures and executors."),
+            post("b", "Rust macros", &[], "Declarative macros."),
+        ];
+        let index = index(&posts);
+
+        let hits = index.search("rust async");
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].0, "a");
+
+        let hits = index.search("rust mac");
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].0, "b");
+
+        assert!(index.search("python").is_empty());
+        assert!(index.search("   ").is_empty());
+    }
+
+    #[test]
+    fn test_tags_are_indexed() {
+        let posts = vec![post("a", "Post", &["web-dev"], "Body")];
+        let index = index(&posts);
+        assert_eq!(index.search("web").len(), 1);
+    }
+
+    #[test]
+    fn test_snippet_highlights_and_escapes() {
+        let posts = vec![post(
+            "a",
+            "Post",
+            &[],
+            "Intro text. Then <b> & some Rust code that matters.",
+        )];
+        let index = index(&posts);
+
+        let snippet = index.snippet("a", "rust").unwrap();
+        assert!(snippet.contains("<mark>Rust</mark>"));
+        assert!(!snippet.contains("<b>"));
+
+        assert!(index.snippet("a", "python").is_none());
+    }
+
+    #[test]
+    fn test_snippet_is_windowed() {
+        let filler = "lorem ipsum ".repeat(40);
+        let content = format!("{}needle {}", filler, filler);
+        let posts = vec![post("a", "Post", &[], &content)];
+
+        let snippet = index(&posts).snippet("a", "needle").unwrap();
+        assert!(snippet.starts_with('…'));
+        assert!(snippet.ends_with('…'));
+        assert!(snippet.contains("<mark>needle</mark>"));
+        assert!(snippet.len() < SNIPPET_LEN + 40);
+    }
+
+    #[test]
+    fn test_snippet_from_start() {
+        assert_eq!(
+            snippet_from_start("Rust & friends", "rust"),
+            "<mark>Rust</mark> &amp; friends"
+        );
+    }
+}
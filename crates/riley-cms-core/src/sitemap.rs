@@ -0,0 +1,180 @@
+//! sitemap.xml generation
+//!
+//! Like feeds, the sitemap only lists live content: drafts, scheduled items and
+//! posts inside series that are not live yet never appear.
+
+use crate::config::SitemapConfig;
+use crate::content::{ContentCache, body_etag};
+use crate::feed::escape;
+use crate::links;
+use chrono::{DateTime, SecondsFormat, Utc};
+use std::fmt::Write;
+
+/// The sitemap protocol caps a single sitemap at 50,000 URLs
+const MAX_URLS: usize = 50_000;
+
+/// A rendered sitemap
+#[derive(Debug, Clone)]
+pub struct Sitemap {
+    pub body: String,
+    /// Quoted strong ETag of `body`
+    pub etag: String,
+}
+
+/// A single `<url>` entry
+struct Entry {
+    loc: String,
+    lastmod: Option<DateTime<Utc>>,
+}
+
+/// Build the sitemap from the cache
+pub(crate) fn build(cache: &ContentCache, config: &SitemapConfig) -> Sitemap {
+    let mut entries = Vec::new();
+
+    for post in cache.published_posts() {
+        let template = match post.series_slug {
+            Some(_) => config
+                .series_post_url
+                .as_deref()
+                .unwrap_or(&config.post_url),
+            None => &config.post_url,
+        };
+        entries.push(Entry {
+            loc: links::expand(
+                template,
+                &config.base_url,
+                &post.slug,
+                post.series_slug.as_deref(),
+            ),
+            lastmod: lastmod(post.goes_live_at, post.last_modified),
+        });
+    }
+
+    if let Some(ref template) = config.series_url {
+        for series in cache.all_published_series() {
+            entries.push(Entry {
+                loc: links::expand(template, &config.base_url, &series.slug, Some(&series.slug)),
+                lastmod: lastmod(series.goes_live_at, series.last_modified),
+            });
+        }
+    }
+
+    if entries.len() > MAX_URLS {
+        tracing::warn!(
+            "Sitemap has {} URLs; truncating to the protocol limit of {}",
+            entries.len(),
+            MAX_URLS
+        );
+        entries.truncate(MAX_URLS);
+    }
+
+    let mut body = String::new();
+    body.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    body.push_str("<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n");
+    for entry in &entries {
+        body.push_str("<url>");
+        let _ = write!(body, "<loc>{}</loc>", escape(&entry.loc));
+        if let Some(lastmod) = entry.lastmod {
+            let _ = write!(
+                body,
+                "<lastmod>{}</lastmod>",
+                lastmod.to_rfc3339_opts(SecondsFormat::Secs, true)
+            );
+        }
+        body.push_str("</url>\n");
+    }
+    body.push_str("</urlset>\n");
+
+    Sitemap {
+        etag: body_etag(&body),
+        body,
+    }
+}
+
+/// `lastmod` is the publish date, or the last edit if that came later
+fn lastmod(
+    goes_live_at: Option<DateTime<Utc>>,
+    last_modified: Option<DateTime<Utc>>,
+) -> Option<DateTime<Utc>> {
+    goes_live_at.max(last_modified)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ContentConfig;
+    use riley_cms_testing::{PostFixture, SeriesFixture, TestContent};
+
+    fn load(content: &TestContent) -> ContentCache {
+        ContentCache::load(&ContentConfig::for_tests(content.path())).unwrap()
+    }
+
+    fn sitemap_config() -> SitemapConfig {
+        SitemapConfig {
+            base_url: "https://example.com".to_string(),
+            post_url: "{base_url}/{slug}".to_string(),
+            series_post_url: Some("{base_url}/{series_slug}/{slug}".to_string()),
+            series_url: Some("{base_url}/series/{slug}".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_sitemap_lists_only_live_content() {
+        let content = TestContent::new()
+            .with_post(PostFixture::new("live", "Live").live())
+            .with_post(PostFixture::new("draft", "Draft"))
+            .with_post(
+                PostFixture::new("scheduled", "Scheduled").goes_live_at("2099-01-01T00:00:00Z"),
+            )
+            .with_series(
+                SeriesFixture::new("rust", "Rust")
+                    .live()
+                    .post(PostFixture::new("part-1", "Part 1").live())
+                    .post(PostFixture::new("part-2", "Part 2")),
+            )
+            .with_series(
+                SeriesFixture::new("hidden", "Hidden")
+                    .post(PostFixture::new("secret", "Secret").live()),
+            );
+
+        let sitemap = build(&load(&content), &sitemap_config());
+        let body = &sitemap.body;
+
+        assert!(body.contains("<loc>https://example.com/live</loc>"));
+        assert!(body.contains("<loc>https://example.com/rust/part-1</loc>"));
+        assert!(body.contains("<loc>https://example.com/series/rust</loc>"));
+        assert!(!body.contains("draft"));
+        assert!(!body.contains("scheduled"));
+        assert!(!body.contains("part-2"));
+        assert!(!body.contains("hidden"));
+        assert!(!body.contains("secret"));
+        assert_eq!(body.matches("<url>").count(), 3);
+    }
+
+    #[test]
+    fn test_lastmod_prefers_later_edit() {
+        let content = TestContent::new()
+            .with_post(PostFixture::new("old", "Old").goes_live_at("2000-01-01T00:00:00Z"));
+
+        let sitemap = build(&load(&content), &sitemap_config());
+        // The file was written just now, which is later than goes_live_at
+        let (_, rest) = sitemap.body.split_once("<lastmod>").unwrap();
+        let (lastmod, _) = rest.split_once("</lastmod>").unwrap();
+        assert!(
+            lastmod.parse::<DateTime<Utc>>().unwrap()
+                > "2020-01-01T00:00:00Z".parse::<DateTime<Utc>>().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_series_omitted_without_template() {
+        let content = TestContent::new().with_series(SeriesFixture::new("rust", "Rust").live());
+
+        let config = SitemapConfig {
+            series_url: None,
+            ..sitemap_config()
+        };
+        let sitemap = build(&load(&content), &config);
+        assert_eq!(sitemap.body.matches("<url>").count(), 0);
+    }
+}
@@ -0,0 +1,414 @@
+//! Where [`ContentCache`](crate::ContentCache) reads content files from
+//!
+//! [`Worktree`] reads the filesystem under `repo_path`. [`GitTree`] reads one
+//! commit straight from the git object database, so the repository can be
+//! bare and the loaded content always matches an exact commit. [`Checkout`]
+//! reads a deploy checkout of a commit from disk, dated like a [`GitTree`].
+
+use crate::error::{Error, Result};
+use crate::git::{self, DirectoryDates};
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::fs;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// Files [`GitTree`] reads contents of; everything else only needs its metadata
+const CONTENT_FILES: [&str; 3] = ["config.toml", "content.mdx", "series.toml"];
+
+/// What a directory entry is, without following symlinks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum EntryKind {
+    Dir,
+    File,
+    Symlink,
+}
+
+/// A directory entry from [`Source::read_dir`]
+#[derive(Debug)]
+pub(crate) struct SourceEntry {
+    pub name: OsString,
+    pub kind: io::Result<EntryKind>,
+}
+
+/// Read-only access to content files
+pub(crate) trait Source {
+    /// Entries of a directory. Entries that can't be read are errors.
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<io::Result<SourceEntry>>>;
+
+    /// Whether anything exists at `path`
+    fn exists(&self, path: &Path) -> bool;
+
+    /// Whether `path` is a symlink
+    fn is_symlink(&self, path: &Path) -> io::Result<bool>;
+
+    /// Size of a file in bytes
+    fn len(&self, path: &Path) -> io::Result<u64>;
+
+    fn read_to_string(&self, path: &Path) -> io::Result<String>;
+
+    /// When a file last changed, if known
+    fn modified(&self, path: &Path) -> Option<DateTime<Utc>>;
+}
+
+/// Files on disk
+pub(crate) struct Worktree;
+
+impl Source for Worktree {
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<io::Result<SourceEntry>>> {
+        Ok(fs::read_dir(path)?
+            .map(|entry| {
+                let entry = entry?;
+                // DirEntry::file_type() does NOT follow symlinks
+                let kind = entry.file_type().map(|ft| {
+                    if ft.is_symlink() {
+                        EntryKind::Symlink
+                    } else if ft.is_dir() {
+                        EntryKind::Dir
+                    } else {
+                        EntryKind::File
+                    }
+                });
+                Ok(SourceEntry {
+                    name: entry.file_name(),
+                    kind,
+                })
+            })
+            .collect())
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        path.exists()
+    }
+
+    fn is_symlink(&self, path: &Path) -> io::Result<bool> {
+        Ok(fs::symlink_metadata(path)?.file_type().is_symlink())
+    }
+
+    fn len(&self, path: &Path) -> io::Result<u64> {
+        Ok(fs::metadata(path)?.len())
+    }
+
+    fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        fs::read_to_string(path)
+    }
+
+    fn modified(&self, path: &Path) -> Option<DateTime<Utc>> {
+        fs::metadata(path)
+            .and_then(|m| m.modified())
+            .ok()
+            .map(DateTime::<Utc>::from)
+    }
+}
+
+/// Files of a checked-out commit on disk.
+///
+/// Checkouts are written fresh for every deploy, so a file's modification
+/// time is the last commit touching its directory, as in [`GitTree`], rather
+/// than when it was checked out.
+pub(crate) struct Checkout<'a> {
+    root: &'a Path,
+    dates: &'a DirectoryDates,
+}
+
+impl<'a> Checkout<'a> {
+    /// The checkout at `root`, with commit dates keyed by paths relative to it
+    pub fn new(root: &'a Path, dates: &'a DirectoryDates) -> Self {
+        Self { root, dates }
+    }
+}
+
+impl Source for Checkout<'_> {
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<io::Result<SourceEntry>>> {
+        Worktree.read_dir(path)
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        Worktree.exists(path)
+    }
+
+    fn is_symlink(&self, path: &Path) -> io::Result<bool> {
+        Worktree.is_symlink(path)
+    }
+
+    fn len(&self, path: &Path) -> io::Result<u64> {
+        Worktree.len(path)
+    }
+
+    fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        Worktree.read_to_string(path)
+    }
+
+    fn modified(&self, path: &Path) -> Option<DateTime<Utc>> {
+        let path = tree_path(path.strip_prefix(self.root).ok()?);
+        let (dir, _) = path.rsplit_once('/')?;
+        self.dates.get(dir).map(|&(_, updated)| updated)
+    }
+}
+
+/// A tree entry of a [`GitTree`]
+#[derive(Debug)]
+struct TreeEntry {
+    kind: EntryKind,
+    /// Blob size (0 for trees)
+    size: u64,
+}
+
+/// The content directory of one commit, read from the object database.
+///
+/// Paths are relative to the repository root. Trees and sizes come from one
+/// `git ls-tree`, and the contents of every `config.toml`, `content.mdx` and
+/// `series.toml` within the size limit from one `git cat-file --batch`. A
+/// file's modification time is the last commit touching its directory.
+#[derive(Debug)]
+pub(crate) struct GitTree {
+    commit: String,
+    entries: HashMap<String, TreeEntry>,
+    /// Child names of each directory, `""` being the repository root
+    children: HashMap<String, Vec<String>>,
+    blobs: HashMap<String, Vec<u8>>,
+    dates: DirectoryDates,
+}
+
+impl GitTree {
+    /// Read `git_ref`'s content directory. Returns `None` if the ref doesn't
+    /// resolve to a commit, e.g. in a repository without commits.
+    pub(crate) fn read(
+        repo_path: &Path,
+        git_ref: &str,
+        content_dir: &str,
+        max_file_size: u64,
+    ) -> Result<Option<Self>> {
+        let commit = match git_output(
+            repo_path,
+            &[
+                "rev-parse",
+                "--verify",
+                "--quiet",
+                &format!("{}^{{commit}}", git_ref),
+            ],
+        ) {
+            Ok(out) => String::from_utf8_lossy(&out).trim().to_string(),
+            Err(_) => return Ok(None),
+        };
+
+        let content_dir = content_dir.trim_matches('/');
+        let mut args = vec!["ls-tree", "-r", "-t", "-l", "-z", commit.as_str()];
+        if !content_dir.is_empty() && content_dir != "." {
+            args.extend(["--", content_dir]);
+        }
+        let listing = git_output(repo_path, &args)?;
+
+        let mut tree = Self {
+            commit,
+            entries: HashMap::new(),
+            children: HashMap::new(),
+            blobs: HashMap::new(),
+            dates: DirectoryDates::new(),
+        };
+        let mut wanted = Vec::new();
+        // Records are `mode SP type SP oid SP+ size TAB path NUL`
+        for record in listing.split(|&b| b == 0).filter(|r| !r.is_empty()) {
+            let record = String::from_utf8_lossy(record);
+            let Some((meta, path)) = record.split_once('\t') else {
+                continue;
+            };
+            let meta: Vec<&str> = meta.split_whitespace().collect();
+            let [mode, kind, oid, size] = meta[..] else {
+                continue;
+            };
+            let kind = match (mode, kind) {
+                (_, "tree") => EntryKind::Dir,
+                ("120000", _) => EntryKind::Symlink,
+                _ => EntryKind::File,
+            };
+            let size = size.parse().unwrap_or(0);
+            let (parent, name) = path.rsplit_once('/').unwrap_or(("", path));
+            if kind == EntryKind::File && size <= max_file_size && CONTENT_FILES.contains(&name) {
+                wanted.push((path.to_string(), oid.to_string()));
+            }
+            tree.children
+                .entry(parent.to_string())
+                .or_default()
+                .push(name.to_string());
+            tree.entries
+                .insert(path.to_string(), TreeEntry { kind, size });
+        }
+
+        tree.blobs = read_blobs(repo_path, wanted)?;
+        tree.dates = match git::directory_dates(repo_path, content_dir, Some(&tree.commit)) {
+            Ok(dates) => dates,
+            Err(e) => {
+                tracing::warn!("Failed to read git history for post dates: {}", e);
+                DirectoryDates::new()
+            }
+        };
+        Ok(Some(tree))
+    }
+
+    /// The commit this tree was read from
+    pub(crate) fn commit(&self) -> &str {
+        &self.commit
+    }
+
+    /// First and last commit dates of each directory, up to this commit
+    pub(crate) fn dates(&self) -> &DirectoryDates {
+        &self.dates
+    }
+
+    fn entry(&self, path: &Path) -> io::Result<&TreeEntry> {
+        self.entries
+            .get(&tree_path(path))
+            .ok_or_else(|| not_found(path))
+    }
+}
+
+impl Source for GitTree {
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<io::Result<SourceEntry>>> {
+        let dir = tree_path(path);
+        if !dir.is_empty() && self.entry(path)?.kind != EntryKind::Dir {
+            return Err(io::Error::new(
+                io::ErrorKind::NotADirectory,
+                format!("Not a directory: {}", dir),
+            ));
+        }
+        let names = self
+            .children
+            .get(&dir)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        Ok(names
+            .iter()
+            .map(|name| {
+                let kind = self.entry(&path.join(name)).map(|e| e.kind);
+                Ok(SourceEntry {
+                    name: OsString::from(name),
+                    kind,
+                })
+            })
+            .collect())
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        let path = tree_path(path);
+        path.is_empty() || self.entries.contains_key(&path)
+    }
+
+    fn is_symlink(&self, path: &Path) -> io::Result<bool> {
+        Ok(self.entry(path)?.kind == EntryKind::Symlink)
+    }
+
+    fn len(&self, path: &Path) -> io::Result<u64> {
+        Ok(self.entry(path)?.size)
+    }
+
+    fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        let blob = self
+            .blobs
+            .get(&tree_path(path))
+            .ok_or_else(|| not_found(path))?;
+        String::from_utf8(blob.clone()).map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                "stream did not contain valid UTF-8",
+            )
+        })
+    }
+
+    fn modified(&self, path: &Path) -> Option<DateTime<Utc>> {
+        let path = tree_path(path);
+        let (dir, _) = path.rsplit_once('/')?;
+        self.dates.get(dir).map(|&(_, updated)| updated)
+    }
+}
+
+/// A relative path as git writes it
+fn tree_path(path: &Path) -> String {
+    path.to_string_lossy().trim_matches('/').to_string()
+}
+
+fn not_found(path: &Path) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::NotFound,
+        format!("No such file in commit: {}", path.display()),
+    )
+}
+
+/// Run git in `repo_path` to completion, returning its stdout
+fn git_output(repo_path: &Path, args: &[&str]) -> Result<Vec<u8>> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo_path)
+        .args(args)
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| Error::Git(format!("Failed to run git: {}", e)))?;
+    if !output.status.success() {
+        return Err(Error::Git(format!(
+            "git {} failed: {}",
+            args.first().unwrap_or(&""),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(output.stdout)
+}
+
+/// Contents of `(path, oid)` blobs, keyed by path, via one `git cat-file --batch`
+fn read_blobs(repo_path: &Path, wanted: Vec<(String, String)>) -> Result<HashMap<String, Vec<u8>>> {
+    let mut blobs = HashMap::new();
+    if wanted.is_empty() {
+        return Ok(blobs);
+    }
+
+    let mut child = Command::new("git")
+        .arg("-C")
+        .arg(repo_path)
+        .args(["cat-file", "--batch"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| Error::Git(format!("Failed to run git cat-file: {}", e)))?;
+    let mut stdin = child
+        .stdin
+        .take()
+        .ok_or_else(|| Error::Git("Failed to open stdin pipe".to_string()))?;
+    let input: String = wanted.iter().map(|(_, oid)| format!("{}\n", oid)).collect();
+    // Write from another thread so a full stdout pipe can't deadlock us
+    let writer = std::thread::spawn(move || stdin.write_all(input.as_bytes()));
+
+    let stdout = child
+        .stdout
+        .take()
+        .ok_or_else(|| Error::Git("Failed to open stdout pipe".to_string()))?;
+    let mut reader = BufReader::new(stdout);
+    let mut header = String::new();
+    for (path, oid) in wanted {
+        header.clear();
+        reader.read_line(&mut header)?;
+        // `oid type size`, or `oid missing`
+        let size = header
+            .split_whitespace()
+            .nth(2)
+            .and_then(|s| s.parse::<usize>().ok())
+            .ok_or_else(|| Error::Git(format!("git cat-file: unexpected output for {}", oid)))?;
+        let mut data = vec![0; size + 1];
+        reader.read_exact(&mut data)?;
+        data.truncate(size);
+        blobs.insert(path, data);
+    }
+
+    writer
+        .join()
+        .map_err(|_| Error::Git("git cat-file writer panicked".to_string()))??;
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(Error::Git(format!(
+            "git cat-file failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(blobs)
+}
@@ -0,0 +1,211 @@
+//! Local filesystem storage backend
+
+use super::{Storage, object_key, page_by_key, public_url};
+use crate::config::StorageConfig;
+use crate::error::{Error, Result};
+use crate::types::{Asset, AssetListOptions, AssetListResult};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Storage backend that writes assets to a local directory.
+///
+/// Assets are not served by riley_cms itself; point `public_url_base` at
+/// whatever serves the directory (nginx, Caddy, a CDN origin, ...).
+#[derive(Clone)]
+pub struct LocalStorage {
+    root: PathBuf,
+    public_url_base: String,
+}
+
+impl LocalStorage {
+    /// Create a local storage backend rooted at `root`, creating the directory if needed.
+    pub fn new(root: impl Into<PathBuf>, public_url_base: impl Into<String>) -> Result<Self> {
+        let root = root.into();
+        fs::create_dir_all(&root)?;
+        Ok(Self {
+            root,
+            public_url_base: public_url_base.into(),
+        })
+    }
+
+    /// Create a local storage backend from the `[storage]` config section.
+    pub fn from_config(config: &StorageConfig) -> Result<Self> {
+        let root = config.path.clone().ok_or_else(|| {
+            Error::Config("storage.path is required for the local backend".to_string())
+        })?;
+        Self::new(root, config.public_url_base.resolve()?)
+    }
+
+    /// Recursively collect `(key, path)` pairs for every regular file under `dir`.
+    ///
+    /// Symlinks are skipped so the listing never escapes the storage root.
+    fn collect_files(&self, dir: &Path, out: &mut Vec<(String, PathBuf)>) -> Result<()> {
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            let file_type = entry.file_type()?;
+
+            if file_type.is_symlink() {
+                continue;
+            }
+            if file_type.is_dir() {
+                self.collect_files(&path, out)?;
+            } else if file_type.is_file() {
+                let Ok(relative) = path.strip_prefix(&self.root) else {
+                    continue;
+                };
+                let Some(key) = relative
+                    .components()
+                    .map(|c| c.as_os_str().to_str())
+                    .collect::<Option<Vec<_>>>()
+                else {
+                    tracing::warn!("Skipping asset with non UTF-8 path: {:?}", path);
+                    continue;
+                };
+                out.push((key.join("/"), path));
+            }
+        }
+        Ok(())
+    }
+
+    /// The asset for `key`, from its file's metadata
+    fn asset_for(&self, key: String, metadata: &fs::Metadata) -> Asset {
+        let last_modified = metadata
+            .modified()
+            .map(DateTime::<Utc>::from)
+            .unwrap_or_else(|_| Utc::now());
+
+        Asset {
+            url: public_url(&self.public_url_base, &key),
+            key,
+            size: metadata.len(),
+            last_modified,
+        }
+    }
+}
+
+#[async_trait]
+impl Storage for LocalStorage {
+    /// List assets in the storage directory, sorted by key.
+    ///
+    /// The continuation token is the last key of the previous page. The walk
+    /// runs on a blocking thread, and only the returned page's files are stat'ed.
+    async fn list_assets(&self, opts: &AssetListOptions) -> Result<AssetListResult> {
+        let storage = self.clone();
+        let opts = opts.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut files = Vec::new();
+            storage.collect_files(&storage.root, &mut files)?;
+
+            let (page, next_continuation_token) = page_by_key(files, |(key, _)| key, &opts);
+            let assets = page
+                .into_iter()
+                .map(|(key, path)| Ok(storage.asset_for(key, &fs::metadata(path)?)))
+                .collect::<Result<Vec<_>>>()?;
+
+            Ok(AssetListResult {
+                assets,
+                next_continuation_token,
+            })
+        })
+        .await
+        .map_err(|e| Error::Io(std::io::Error::other(e)))?
+    }
+
+    /// Copy a file into the storage directory
+    async fn upload_asset(&self, path: &Path, dest: Option<&str>) -> Result<Asset> {
+        let key = object_key(path, dest)?;
+        let target = self.root.join(&key);
+
+        if let Some(parent) = target.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::copy(path, &target)
+            .await
+            .map_err(|e| Error::Storage(format!("Failed to copy file: {}", e)))?;
+
+        let metadata = tokio::fs::metadata(&target).await?;
+        Ok(self.asset_for(key, &metadata))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write_source(dir: &TempDir, name: &str, contents: &[u8]) -> PathBuf {
+        let path = dir.path().join(name);
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[tokio::test]
+    async fn test_upload_and_list() {
+        let source = TempDir::new().unwrap();
+        let root = TempDir::new().unwrap();
+        let storage = LocalStorage::new(root.path(), "https://assets.example.com/").unwrap();
+
+        let file = write_source(&source, "photo.jpg", b"jpeg bytes");
+        let asset = storage.upload_asset(&file, Some("images")).await.unwrap();
+
+        assert_eq!(asset.key, "images/photo.jpg");
+        assert_eq!(asset.url, "https://assets.example.com/images/photo.jpg");
+        assert_eq!(asset.size, 10);
+        assert!(root.path().join("images/photo.jpg").exists());
+
+        let listed = storage
+            .list_assets(&AssetListOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(listed.assets.len(), 1);
+        assert_eq!(listed.assets[0].key, "images/photo.jpg");
+        assert!(listed.next_continuation_token.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_list_pagination() {
+        let source = TempDir::new().unwrap();
+        let root = TempDir::new().unwrap();
+        let storage = LocalStorage::new(root.path(), "https://assets.example.com").unwrap();
+
+        for name in ["c.txt", "a.txt", "b.txt"] {
+            let file = write_source(&source, name, b"x");
+            storage.upload_asset(&file, None).await.unwrap();
+        }
+
+        let page1 = storage
+            .list_assets(&AssetListOptions {
+                limit: Some(2),
+                continuation_token: None,
+            })
+            .await
+            .unwrap();
+        let keys: Vec<_> = page1.assets.iter().map(|a| a.key.as_str()).collect();
+        assert_eq!(keys, vec!["a.txt", "b.txt"]);
+        assert_eq!(page1.next_continuation_token.as_deref(), Some("b.txt"));
+
+        let page2 = storage
+            .list_assets(&AssetListOptions {
+                limit: Some(2),
+                continuation_token: page1.next_continuation_token,
+            })
+            .await
+            .unwrap();
+        assert_eq!(page2.assets.len(), 1);
+        assert_eq!(page2.assets[0].key, "c.txt");
+        assert!(page2.next_continuation_token.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_upload_rejects_traversal() {
+        let source = TempDir::new().unwrap();
+        let root = TempDir::new().unwrap();
+        let storage = LocalStorage::new(root.path(), "https://assets.example.com").unwrap();
+
+        let file = write_source(&source, "evil.txt", b"x");
+        assert!(storage.upload_asset(&file, Some("../..")).await.is_err());
+    }
+}
@@ -0,0 +1,169 @@
+//! In-memory storage backend
+
+use super::{Storage, object_key, paginate_by_key, public_url};
+use crate::error::{Error, Result};
+use crate::types::{Asset, AssetListOptions, AssetListResult};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Mutex;
+
+/// A stored object in [`MemoryStorage`]
+#[derive(Debug, Clone)]
+struct StoredObject {
+    bytes: Vec<u8>,
+    content_type: String,
+    last_modified: DateTime<Utc>,
+}
+
+/// Storage backend that keeps assets in process memory.
+///
+/// Nothing is persisted and nothing touches the network, which makes it the
+/// backend of choice for tests. Uploaded bytes can be inspected with [`MemoryStorage::get`].
+#[derive(Debug)]
+pub struct MemoryStorage {
+    objects: Mutex<BTreeMap<String, StoredObject>>,
+    public_url_base: String,
+}
+
+impl MemoryStorage {
+    /// Create an empty in-memory store
+    pub fn new(public_url_base: impl Into<String>) -> Self {
+        Self {
+            objects: Mutex::new(BTreeMap::new()),
+            public_url_base: public_url_base.into(),
+        }
+    }
+
+    /// Insert an object directly, bypassing the filesystem
+    pub fn insert(&self, key: impl Into<String>, bytes: impl Into<Vec<u8>>) {
+        let key = key.into();
+        let content_type = mime_guess::from_path(&key)
+            .first_or_octet_stream()
+            .to_string();
+        self.lock().insert(
+            key,
+            StoredObject {
+                bytes: bytes.into(),
+                content_type,
+                last_modified: Utc::now(),
+            },
+        );
+    }
+
+    /// Get the bytes stored under `key`
+    pub fn get(&self, key: &str) -> Option<Vec<u8>> {
+        self.lock().get(key).map(|o| o.bytes.clone())
+    }
+
+    /// Get the detected content type of the object stored under `key`
+    pub fn content_type(&self, key: &str) -> Option<String> {
+        self.lock().get(key).map(|o| o.content_type.clone())
+    }
+
+    /// All stored keys, in sorted order
+    pub fn keys(&self) -> Vec<String> {
+        self.lock().keys().cloned().collect()
+    }
+
+    /// Number of stored objects
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Whether the store is empty
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BTreeMap<String, StoredObject>> {
+        // A poisoned lock only means another test thread panicked mid-insert;
+        // the map itself is still usable.
+        self.objects.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[async_trait]
+impl Storage for MemoryStorage {
+    async fn list_assets(&self, opts: &AssetListOptions) -> Result<AssetListResult> {
+        let assets = self
+            .lock()
+            .iter()
+            .map(|(key, object)| Asset {
+                key: key.clone(),
+                url: public_url(&self.public_url_base, key),
+                size: object.bytes.len() as u64,
+                last_modified: object.last_modified,
+            })
+            .collect();
+
+        Ok(paginate_by_key(assets, opts))
+    }
+
+    async fn upload_asset(&self, path: &Path, dest: Option<&str>) -> Result<Asset> {
+        let key = object_key(path, dest)?;
+        let bytes = tokio::fs::read(path)
+            .await
+            .map_err(|e| Error::Storage(format!("Failed to read file: {}", e)))?;
+        let size = bytes.len() as u64;
+
+        self.insert(key.clone(), bytes);
+
+        Ok(Asset {
+            url: public_url(&self.public_url_base, &key),
+            key,
+            size,
+            last_modified: Utc::now(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_upload_and_get() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let file = dir.path().join("logo.png");
+        std::fs::write(&file, b"png bytes").unwrap();
+
+        let storage = MemoryStorage::new("https://assets.example.com");
+        let asset = storage.upload_asset(&file, Some("brand")).await.unwrap();
+
+        assert_eq!(asset.key, "brand/logo.png");
+        assert_eq!(asset.url, "https://assets.example.com/brand/logo.png");
+        assert_eq!(storage.get("brand/logo.png").unwrap(), b"png bytes");
+        assert_eq!(storage.content_type("brand/logo.png").unwrap(), "image/png");
+    }
+
+    #[tokio::test]
+    async fn test_list_pagination() {
+        let storage = MemoryStorage::new("https://assets.example.com");
+        for key in ["b.txt", "a.txt", "c.txt"] {
+            storage.insert(key, "x");
+        }
+
+        let page1 = storage
+            .list_assets(&AssetListOptions {
+                limit: Some(2),
+                continuation_token: None,
+            })
+            .await
+            .unwrap();
+        assert_eq!(page1.assets.len(), 2);
+        assert_eq!(page1.next_continuation_token.as_deref(), Some("b.txt"));
+
+        let page2 = storage
+            .list_assets(&AssetListOptions {
+                limit: Some(2),
+                continuation_token: page1.next_continuation_token,
+            })
+            .await
+            .unwrap();
+        assert_eq!(page2.assets.len(), 1);
+        assert_eq!(page2.assets[0].key, "c.txt");
+        assert!(page2.next_continuation_token.is_none());
+    }
+}
@@ -0,0 +1,172 @@
+//! Asset storage backends for riley_cms
+//!
+//! [`RileyCms`](crate::RileyCms) talks to asset storage through the [`Storage`]
+//! trait. The backend is selected with `storage.backend` in the config:
+//!
+//! - `"s3"` (default) - S3 or any S3-compatible service such as Cloudflare R2
+//! - `"local"` - a directory on the local filesystem, served by your own web server
+//!
+//! [`MemoryStorage`] is for tests only and can't be selected in the config;
+//! pass it to [`RileyCms::with_storage`](crate::RileyCms::with_storage).
+
+mod local;
+mod memory;
+mod s3;
+
+pub use local::LocalStorage;
+pub use memory::MemoryStorage;
+pub use s3::S3Storage;
+
+use crate::config::StorageConfig;
+use crate::error::{Error, Result};
+use crate::types::{Asset, AssetListOptions, AssetListResult};
+use async_trait::async_trait;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Maximum assets per page, shared by all backends
+pub(crate) const MAX_PAGE_SIZE: usize = 1000;
+
+/// An asset storage backend.
+///
+/// Implementations must be cheap to share between tasks; `RileyCms` holds
+/// the backend as an `Arc<dyn Storage>`.
+#[async_trait]
+pub trait Storage: Send + Sync {
+    /// List assets with cursor-based pagination.
+    ///
+    /// Defaults to 100 assets per page, capped at 1000. The meaning of the
+    /// continuation token is backend-specific; callers should treat it as opaque.
+    async fn list_assets(&self, opts: &AssetListOptions) -> Result<AssetListResult>;
+
+    /// Upload a local file.
+    ///
+    /// `dest` is an optional key prefix; the file name is always appended.
+    async fn upload_asset(&self, path: &Path, dest: Option<&str>) -> Result<Asset>;
+}
+
+/// Build the storage backend selected by `storage.backend`.
+pub async fn from_config(config: &StorageConfig) -> Result<Arc<dyn Storage>> {
+    match config.backend.as_str() {
+        "s3" => Ok(Arc::new(S3Storage::new(config).await?)),
+        "local" => Ok(Arc::new(LocalStorage::from_config(config)?)),
+        other => Err(Error::Config(format!(
+            "Unknown storage backend '{}'. Expected \"s3\" or \"local\".",
+            other
+        ))),
+    }
+}
+
+/// Compute the object key for an upload from the local file name and an optional prefix.
+pub(crate) fn object_key(path: &Path, dest: Option<&str>) -> Result<String> {
+    let file_name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| Error::Storage("Invalid file name".to_string()))?;
+
+    match dest {
+        Some(prefix) => {
+            // Reject path traversal attempts in the destination prefix
+            let sanitized = prefix.trim_matches('/');
+            if sanitized.split('/').any(|seg| seg == "..") {
+                return Err(Error::Storage(
+                    "Invalid destination: path traversal not allowed".to_string(),
+                ));
+            }
+            if sanitized.is_empty() {
+                Ok(file_name.to_string())
+            } else {
+                Ok(format!("{}/{}", sanitized, file_name))
+            }
+        }
+        None => Ok(file_name.to_string()),
+    }
+}
+
+/// Paginate a list of assets by key for backends without native continuation tokens.
+///
+/// Assets are sorted by key; the continuation token is the last key of the previous page.
+pub(crate) fn paginate_by_key(assets: Vec<Asset>, opts: &AssetListOptions) -> AssetListResult {
+    let (assets, next_continuation_token) = page_by_key(assets, |a| &a.key, opts);
+    AssetListResult {
+        assets,
+        next_continuation_token,
+    }
+}
+
+/// The page of `items` selected by `opts`, sorted by `key`, and the
+/// continuation token for the next page if there is one.
+///
+/// Only keys are needed, so backends can build [`Asset`]s for the returned page alone.
+pub(crate) fn page_by_key<T>(
+    mut items: Vec<T>,
+    key: impl Fn(&T) -> &str,
+    opts: &AssetListOptions,
+) -> (Vec<T>, Option<String>) {
+    let limit = opts.limit.unwrap_or(100).min(MAX_PAGE_SIZE);
+    items.sort_by(|a, b| key(a).cmp(key(b)));
+
+    let start = match opts.continuation_token {
+        Some(ref token) => items.partition_point(|item| key(item) <= token.as_str()),
+        None => 0,
+    };
+    let has_more = items.len() > start + limit;
+
+    let items: Vec<T> = items.into_iter().skip(start).take(limit).collect();
+    let next_continuation_token = if has_more {
+        items.last().map(|item| key(item).to_string())
+    } else {
+        None
+    };
+    (items, next_continuation_token)
+}
+
+/// Build the public URL for an object key
+pub(crate) fn public_url(base: &str, key: &str) -> String {
+    format!("{}/{}", base.trim_end_matches('/'), key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_object_key_without_prefix() {
+        let key = object_key(Path::new("/tmp/photo.jpg"), None).unwrap();
+        assert_eq!(key, "photo.jpg");
+    }
+
+    #[test]
+    fn test_object_key_with_prefix() {
+        let key = object_key(Path::new("/tmp/photo.jpg"), Some("/images/2025/")).unwrap();
+        assert_eq!(key, "images/2025/photo.jpg");
+    }
+
+    #[test]
+    fn test_object_key_rejects_traversal() {
+        assert!(object_key(Path::new("/tmp/photo.jpg"), Some("../etc")).is_err());
+        assert!(object_key(Path::new("/tmp/photo.jpg"), Some("a/../../b")).is_err());
+    }
+
+    #[test]
+    fn test_public_url_trims_trailing_slash() {
+        assert_eq!(
+            public_url("https://assets.example.com/", "a/b.png"),
+            "https://assets.example.com/a/b.png"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_memory_backend_is_not_selectable() {
+        let config: StorageConfig = toml::from_str(
+            "backend = \"memory\"\npublic_url_base = \"https://assets.example.com\"",
+        )
+        .unwrap();
+        let err = from_config(&config).await.err().unwrap();
+        assert!(
+            err.to_string().contains("Unknown storage backend"),
+            "{}",
+            err
+        );
+    }
+}
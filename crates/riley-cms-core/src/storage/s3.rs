@@ -1,21 +1,23 @@
-//! S3/R2 storage operations for riley_cms
+//! S3/R2 storage backend
 
+use super::{MAX_PAGE_SIZE, Storage, object_key, public_url};
 use crate::config::{ResolvedStorageConfig, StorageConfig};
 use crate::error::{Error, Result};
 use crate::types::{Asset, AssetListOptions, AssetListResult};
+use async_trait::async_trait;
 use aws_sdk_s3::Client;
 use aws_sdk_s3::primitives::ByteStream;
 use chrono::{DateTime, Utc};
 use std::path::Path;
 
-/// Storage backend for assets
-pub struct Storage {
+/// Storage backend for S3 and S3-compatible services (Cloudflare R2, MinIO, ...)
+pub struct S3Storage {
     client: Client,
     config: ResolvedStorageConfig,
 }
 
-impl Storage {
-    /// Create a new storage backend
+impl S3Storage {
+    /// Create a new S3 storage backend
     pub async fn new(config: &StorageConfig) -> Result<Self> {
         let resolved = config.resolve()?;
         let mut aws_config_builder = aws_config::from_env();
@@ -67,16 +69,16 @@ impl Storage {
             .map_err(|e| Error::S3(format!("HeadBucket failed: {}", e)))?;
         Ok(())
     }
+}
 
-    /// Maximum assets per page
-    const MAX_PAGE_SIZE: usize = 1000;
-
+#[async_trait]
+impl Storage for S3Storage {
     /// List assets in the bucket with pagination.
     ///
     /// Uses S3's native continuation token for efficient cursor-based pagination.
     /// Defaults to 100 assets per page, capped at 1000.
-    pub async fn list_assets(&self, opts: &AssetListOptions) -> Result<AssetListResult> {
-        let limit = opts.limit.unwrap_or(100).min(Self::MAX_PAGE_SIZE);
+    async fn list_assets(&self, opts: &AssetListOptions) -> Result<AssetListResult> {
+        let limit = opts.limit.unwrap_or(100).min(MAX_PAGE_SIZE);
 
         let mut request = self
             .client
@@ -103,11 +105,7 @@ impl Storage {
                     .and_then(|t| DateTime::from_timestamp(t.secs(), t.subsec_nanos()))
                     .unwrap_or_else(Utc::now);
 
-                let url = format!(
-                    "{}/{}",
-                    self.config.public_url_base.trim_end_matches('/'),
-                    key
-                );
+                let url = public_url(&self.config.public_url_base, &key);
 
                 assets.push(Asset {
                     key,
@@ -131,25 +129,8 @@ impl Storage {
     }
 
     /// Upload an asset to the bucket
-    pub async fn upload_asset(&self, path: &Path, dest: Option<&str>) -> Result<Asset> {
-        let file_name = path
-            .file_name()
-            .and_then(|n| n.to_str())
-            .ok_or_else(|| Error::Storage("Invalid file name".to_string()))?;
-
-        let key = match dest {
-            Some(prefix) => {
-                // Reject path traversal attempts in the destination prefix
-                let sanitized = prefix.trim_matches('/');
-                if sanitized.split('/').any(|seg| seg == "..") {
-                    return Err(Error::Storage(
-                        "Invalid destination: path traversal not allowed".to_string(),
-                    ));
-                }
-                format!("{}/{}", sanitized, file_name)
-            }
-            None => file_name.to_string(),
-        };
+    async fn upload_asset(&self, path: &Path, dest: Option<&str>) -> Result<Asset> {
+        let key = object_key(path, dest)?;
 
         let body = ByteStream::from_path(path)
             .await
@@ -171,11 +152,7 @@ impl Storage {
             .map_err(|e| Error::S3(format!("Failed to upload: {}", e)))?;
 
         let metadata = std::fs::metadata(path)?;
-        let url = format!(
-            "{}/{}",
-            self.config.public_url_base.trim_end_matches('/'),
-            key
-        );
+        let url = public_url(&self.config.public_url_base, &key);
 
         Ok(Asset {
             key,
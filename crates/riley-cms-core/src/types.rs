@@ -43,9 +43,27 @@ pub struct Post {
     pub goes_live_at: Option<DateTime<Utc>>,
     pub series_slug: Option<String>,
     pub content: String,
+    /// `content` rendered to sanitized HTML, rendered once at load time.
+    /// Only included in API responses when requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_html: Option<String>,
+    /// Headings in document order
+    pub toc: Vec<TocEntry>,
+    pub word_count: usize,
+    pub reading_time_minutes: u32,
+    /// Date of the first commit touching the post's directory
+    pub created_at: Option<DateTime<Utc>>,
+    /// Date of the latest commit touching the post's directory
+    pub updated_at: Option<DateTime<Utc>>,
     /// Order within a series (not serialized in API responses for standalone posts)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub order: Option<i32>,
+    /// Quoted strong ETag for this post, computed at load time
+    #[serde(skip)]
+    pub etag: String,
+    /// Newest modification time of the post's files
+    #[serde(skip)]
+    pub last_modified: Option<DateTime<Utc>>,
 }
 
 /// Post summary without content (for list endpoints)
@@ -59,6 +77,10 @@ pub struct PostSummary {
     pub tags: Option<Vec<String>>,
     pub goes_live_at: Option<DateTime<Utc>>,
     pub series_slug: Option<String>,
+    pub word_count: usize,
+    pub reading_time_minutes: u32,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
 }
 
 impl From<&Post> for PostSummary {
@@ -72,10 +94,35 @@ impl From<&Post> for PostSummary {
             tags: post.tags.clone(),
             goes_live_at: post.goes_live_at,
             series_slug: post.series_slug.clone(),
+            word_count: post.word_count,
+            reading_time_minutes: post.reading_time_minutes,
+            created_at: post.created_at,
+            updated_at: post.updated_at,
         }
     }
 }
 
+/// A heading in a post's table of contents
+#[derive(Debug, Clone, Serialize)]
+pub struct TocEntry {
+    /// Heading level, 1-6
+    pub level: u8,
+    pub text: String,
+    /// `id` of the heading in the rendered HTML
+    pub anchor: String,
+}
+
+/// A ranked search hit (for the search endpoint)
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchResult {
+    #[serde(flatten)]
+    pub post: PostSummary,
+    /// Relevance score; higher is better
+    pub score: f32,
+    /// HTML-escaped excerpt with matching terms wrapped in `<mark>`
+    pub snippet: String,
+}
+
 /// A series with its posts
 #[derive(Debug, Clone, Serialize)]
 pub struct Series {
@@ -85,6 +132,12 @@ pub struct Series {
     pub preview_image: Option<String>,
     pub goes_live_at: Option<DateTime<Utc>>,
     pub posts: Vec<SeriesPostSummary>,
+    /// Quoted strong ETag for this series and its post summaries, computed at load time
+    #[serde(skip)]
+    pub etag: String,
+    /// Newest modification time of `series.toml` and the series' posts
+    #[serde(skip)]
+    pub last_modified: Option<DateTime<Utc>>,
 }
 
 /// Series summary without posts (for list endpoints)
@@ -98,6 +151,13 @@ pub struct SeriesSummary {
     pub post_count: usize,
 }
 
+/// A tag with the number of visible posts using it (for the tags endpoint)
+#[derive(Debug, Clone, Serialize)]
+pub struct TagSummary {
+    pub tag: String,
+    pub post_count: usize,
+}
+
 /// Post summary within a series (includes order)
 #[derive(Debug, Clone, Serialize)]
 pub struct SeriesPostSummary {
@@ -109,6 +169,18 @@ pub struct SeriesPostSummary {
     pub tags: Option<Vec<String>>,
     pub goes_live_at: Option<DateTime<Utc>>,
     pub order: Option<i32>,
+    pub word_count: usize,
+    pub reading_time_minutes: u32,
+}
+
+/// A commit touching a post (for the history endpoint)
+#[derive(Debug, Clone, Serialize)]
+pub struct Revision {
+    pub sha: String,
+    pub author: String,
+    pub date: DateTime<Utc>,
+    /// First line of the commit message
+    pub message: String,
 }
 
 /// An asset in the storage bucket
@@ -129,6 +201,10 @@ pub struct ListOptions {
     pub include_scheduled: bool,
     pub limit: Option<usize>,
     pub offset: Option<usize>,
+    /// Only posts with at least one of these tags (case-insensitive)
+    pub tags_any: Vec<String>,
+    /// Only posts with all of these tags (case-insensitive)
+    pub tags_all: Vec<String>,
 }
 
 /// Paginated list result
@@ -138,6 +214,9 @@ pub struct ListResult<T> {
     pub total: usize,
     pub limit: usize,
     pub offset: usize,
+    /// Quoted strong ETag for this page, derived from the ETags of the listed items
+    #[serde(skip)]
+    pub etag: String,
 }
 
 /// Options for listing assets with pagination
@@ -166,6 +245,252 @@ pub struct ValidationError {
     pub message: String,
 }
 
+/// A problem found while loading content from disk
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LoadDiagnostic {
+    pub kind: DiagnosticKind,
+    /// Path relative to the content directory (e.g. `my-post` or `my-series/part-1`)
+    pub path: String,
+    pub message: String,
+}
+
+impl LoadDiagnostic {
+    /// Whether content was skipped because of this problem
+    pub fn is_error(&self) -> bool {
+        self.kind.is_error()
+    }
+}
+
+/// What kind of problem a [`LoadDiagnostic`] describes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticKind {
+    /// A directory or file couldn't be read; its content was skipped
+    Io,
+    /// A post or series was invalid (bad TOML, oversized file, symlink); it was skipped
+    Parse,
+    /// `max_total_content_size` was exceeded; the remaining content was skipped
+    SizeLimit,
+    /// A post's slug is already taken; the post is loaded but not reachable by slug
+    SlugCollision,
+}
+
+impl DiagnosticKind {
+    /// Whether this kind of problem means content was skipped
+    pub fn is_error(self) -> bool {
+        !matches!(self, Self::SlugCollision)
+    }
+}
+
+/// An accepted ref update, from the push audit log
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PushRecord {
+    pub pushed_at: DateTime<Utc>,
+    /// Name of the git credential that pushed
+    pub user: String,
+    /// Full ref name, e.g. `refs/heads/main`
+    pub refname: String,
+    /// Previous target (`None` if the ref was created)
+    pub old_sha: Option<String>,
+    /// New target (`None` if the ref was deleted)
+    pub new_sha: Option<String>,
+    /// Posts with changed files, as `slug` or `series/slug`
+    pub changed_posts: Vec<String>,
+}
+
+/// Publicly live posts and series that differ between two versions of the content,
+/// as sent in webhook payloads
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContentChanges {
+    /// Commit the previous content was read from, if known
+    pub old_sha: Option<String>,
+    /// Commit the new content was read from, if known
+    pub new_sha: Option<String>,
+    /// Posts, as `slug` or `series/slug`
+    pub posts: SlugChanges,
+    /// Series, by slug
+    pub series: SlugChanges,
+}
+
+/// Slugs that became live, changed, or stopped being live, each sorted
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SlugChanges {
+    pub created: Vec<String>,
+    pub updated: Vec<String>,
+    pub deleted: Vec<String>,
+}
+
+impl SlugChanges {
+    pub fn is_empty(&self) -> bool {
+        self.created.is_empty() && self.updated.is_empty() && self.deleted.is_empty()
+    }
+}
+
+impl ContentChanges {
+    /// Whether no live post or series changed
+    pub fn is_empty(&self) -> bool {
+        self.posts.is_empty() && self.series.is_empty()
+    }
+
+    /// One event per changed post or series: posts first, then series
+    pub fn events(&self) -> Vec<ContentEvent> {
+        let mut events = Vec::new();
+        for (changes, kinds) in [
+            (
+                &self.posts,
+                [
+                    ContentEventKind::PostPublished,
+                    ContentEventKind::PostUpdated,
+                    ContentEventKind::PostDeleted,
+                ],
+            ),
+            (
+                &self.series,
+                [
+                    ContentEventKind::SeriesPublished,
+                    ContentEventKind::SeriesUpdated,
+                    ContentEventKind::SeriesDeleted,
+                ],
+            ),
+        ] {
+            for (slugs, kind) in [&changes.created, &changes.updated, &changes.deleted]
+                .into_iter()
+                .zip(kinds)
+            {
+                events.extend(slugs.iter().map(|slug| ContentEvent {
+                    kind,
+                    slug: slug.clone(),
+                }));
+            }
+        }
+        events
+    }
+}
+
+/// A change to one post or series, for targeted revalidation by webhook receivers
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContentEvent {
+    #[serde(rename = "type")]
+    pub kind: ContentEventKind,
+    pub slug: String,
+}
+
+/// Webhook event types
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ContentEventKind {
+    /// A post became live: it is new, or was a draft or scheduled
+    #[serde(rename = "post.published")]
+    PostPublished,
+    /// A live post changed
+    #[serde(rename = "post.updated")]
+    PostUpdated,
+    /// A post stopped being live: it was removed or unpublished
+    #[serde(rename = "post.deleted")]
+    PostDeleted,
+    #[serde(rename = "series.published")]
+    SeriesPublished,
+    #[serde(rename = "series.updated")]
+    SeriesUpdated,
+    #[serde(rename = "series.deleted")]
+    SeriesDeleted,
+}
+
+impl ContentEventKind {
+    /// Event type as sent in webhook payloads, e.g. `post.published`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::PostPublished => "post.published",
+            Self::PostUpdated => "post.updated",
+            Self::PostDeleted => "post.deleted",
+            Self::SeriesPublished => "series.published",
+            Self::SeriesUpdated => "series.updated",
+            Self::SeriesDeleted => "series.deleted",
+        }
+    }
+}
+
+/// Result of [`RileyCms::refresh`](crate::RileyCms::refresh)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RefreshOutcome {
+    /// The newly loaded content is now live
+    Replaced,
+    /// The refresh policy rejected the new content; the previous cache is still live
+    KeptPrevious { reason: String },
+}
+
+/// State of the background refresh worker, from [`RileyCms::refresh_status`](crate::RileyCms::refresh_status)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct RefreshStatus {
+    /// Requests waiting for the next run, which serves them all at once
+    pub pending: u64,
+    /// Whether a run is in progress
+    pub running: bool,
+    /// Runs completed so far
+    pub runs: u64,
+    /// Requests served by a run started for an earlier request
+    pub coalesced: u64,
+    pub last_started_at: Option<DateTime<Utc>>,
+    pub last_finished_at: Option<DateTime<Utc>>,
+    /// How the last completed run ended
+    pub last_result: Option<RefreshRunResult>,
+    /// Why the last run kept the previous content or failed
+    pub last_message: Option<String>,
+}
+
+/// How a refresh worker run ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RefreshRunResult {
+    /// New content went live and webhooks were fired
+    Changed,
+    /// The reload matched the live content, so no webhooks were fired
+    Unchanged,
+    /// The refresh policy kept the previous content
+    KeptPrevious,
+    /// The reload failed
+    Failed,
+}
+
+/// A webhook delivery from the delivery queue, with every attempt made so far
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WebhookDelivery {
+    pub id: String,
+    pub url: String,
+    pub created_at: DateTime<Utc>,
+    pub status: DeliveryStatus,
+    /// What the payload describes
+    pub changes: ContentChanges,
+    /// Oldest first
+    pub attempts: Vec<DeliveryAttempt>,
+    /// When the next attempt is due, while pending
+    pub next_attempt_at: Option<DateTime<Utc>>,
+    /// The delivery this one repeats, for redeliveries
+    pub redelivery_of: Option<String>,
+}
+
+/// Where a webhook delivery stands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeliveryStatus {
+    /// Waiting for its first attempt or a retry
+    Pending,
+    /// The receiver answered with a 2xx status
+    Delivered,
+    /// Gave up: the receiver rejected it, the URL is unusable, or attempts ran out
+    Failed,
+}
+
+/// One attempt at a webhook delivery
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeliveryAttempt {
+    pub at: DateTime<Utc>,
+    /// HTTP status of the response, if one arrived
+    pub status_code: Option<u16>,
+    pub duration_ms: u64,
+    /// Why the attempt failed, if it did
+    pub error: Option<String>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -183,7 +508,15 @@ mod tests {
             goes_live_at: Some(Utc.with_ymd_and_hms(2025, 1, 15, 0, 0, 0).unwrap()),
             series_slug: Some("my-series".to_string()),
             content: "# Hello World".to_string(),
+            content_html: None,
+            toc: Vec::new(),
+            word_count: 0,
+            reading_time_minutes: 0,
+            created_at: None,
+            updated_at: None,
             order: Some(1),
+            etag: String::new(),
+            last_modified: None,
         };
 
         let summary: PostSummary = (&post).into();
@@ -216,7 +549,15 @@ mod tests {
             goes_live_at: None,
             series_slug: None,
             content: "content".to_string(),
+            content_html: None,
+            toc: Vec::new(),
+            word_count: 0,
+            reading_time_minutes: 0,
+            created_at: None,
+            updated_at: None,
             order: None,
+            etag: String::new(),
+            last_modified: None,
         };
 
         let json = serde_json::to_string(&post).unwrap();
@@ -235,7 +576,15 @@ mod tests {
             goes_live_at: None,
             series_slug: None,
             content: "content".to_string(),
+            content_html: None,
+            toc: Vec::new(),
+            word_count: 0,
+            reading_time_minutes: 0,
+            created_at: None,
+            updated_at: None,
             order: Some(5),
+            etag: String::new(),
+            last_modified: None,
         };
 
         let json = serde_json::to_string(&post).unwrap();
@@ -293,11 +642,13 @@ description = "A series description"
             total: 10,
             limit: 2,
             offset: 0,
+            etag: "\"abc\"".to_string(),
         };
 
         let json = serde_json::to_string(&result).unwrap();
         assert!(json.contains("\"total\":10"));
         assert!(json.contains("\"limit\":2"));
         assert!(json.contains("\"offset\":0"));
+        assert!(!json.contains("etag"));
     }
 }
@@ -0,0 +1,885 @@
+//! Durable webhook delivery (`[webhooks]`)
+//!
+//! Each webhook is a [`WebhookDelivery`] in a JSONL queue file, one line per
+//! delivery holding its latest state, so pending deliveries survive restarts.
+//! A task started by [`RileyCms::start_background_tasks`](crate::RileyCms::start_background_tasks)
+//! sends whatever is due and retries failures with exponential backoff until
+//! `max_attempts`. Finished
+//! deliveries stay in the file as the delivery log, up to `keep_deliveries`.
+//!
+//! Requests are signed Stripe-style: the HMAC-SHA256 of `<timestamp>.<body>`
+//! under each active secret, checked by [`verify_webhook_signature`].
+
+use crate::config::{RileyCmsConfig, WebhookEndpointConfig, WebhooksConfig};
+use crate::error::{Error, Result};
+use crate::git;
+use crate::push::glob_matches;
+use crate::security;
+use crate::types::{ContentChanges, DeliveryAttempt, DeliveryStatus, WebhookDelivery};
+use chrono::{DateTime, Utc};
+use fs4::FileExt;
+use futures_util::future::join_all;
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::Write;
+use std::net::ToSocketAddrs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tokio::sync::{Mutex, Notify};
+use tokio::task::JoinHandle;
+use tokio::time::{Duration, Instant};
+
+/// Header with the Unix time a webhook request was signed at
+pub const WEBHOOK_TIMESTAMP_HEADER: &str = "X-Riley-Cms-Timestamp";
+
+/// Header with one `v1=<hex>` signature per active secret, comma-separated
+pub const WEBHOOK_SIGNATURE_HEADER: &str = "X-Riley-Cms-Signature";
+
+/// Scheme of the signatures in [`WEBHOOK_SIGNATURE_HEADER`]
+const SIGNATURE_SCHEME: &str = "v1";
+
+/// File name of the queue in the repository's git directory
+const DEFAULT_FILE_NAME: &str = "riley-cms-webhooks.jsonl";
+
+/// How long past the slowest endpoint's timeout a claimed delivery stays claimed.
+/// It is retried after that if its attempt never finishes, e.g. because the
+/// process exited mid-request.
+const CLAIM_MARGIN: Duration = Duration::from_secs(60);
+
+/// Longest delay between two attempts of a delivery
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(6 * 3600);
+
+/// Longest sleep between checks, so deliveries queued by another process
+/// (e.g. a redelivery from the CLI) are picked up
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Serializes read-modify-write cycles on queue files within this process, so
+/// tasks wait here rather than holding blocking threads on the file lock
+static FILE_LOCK: Mutex<()> = Mutex::const_new(());
+
+/// Where the queue lives: `webhooks.queue_path`, or the repository's git directory
+fn queue_path(config: &RileyCmsConfig) -> PathBuf {
+    if let Some(path) = config.webhooks.as_ref().and_then(|w| w.queue_path.clone()) {
+        return path;
+    }
+    git::git_dir(&config.content.repo_path).join(DEFAULT_FILE_NAME)
+}
+
+/// The delivery queue of one [`RileyCms`](crate::RileyCms)
+pub(crate) struct WebhookQueue {
+    config: Option<WebhooksConfig>,
+    /// Receivers, from [`WebhooksConfig::all_endpoints`]
+    endpoints: Vec<WebhookEndpointConfig>,
+    /// How long a claimed delivery is held, from [`claim_lease`]
+    lease: Duration,
+    path: PathBuf,
+    /// Notified when deliveries are queued
+    queued: Notify,
+}
+
+impl WebhookQueue {
+    pub fn new(config: &RileyCmsConfig) -> Result<Self> {
+        let endpoints = config
+            .webhooks
+            .as_ref()
+            .map(|w| w.all_endpoints())
+            .transpose()?
+            .unwrap_or_default();
+        Ok(Self {
+            config: config.webhooks.clone(),
+            lease: claim_lease(&endpoints),
+            endpoints,
+            path: queue_path(config),
+            queued: Notify::new(),
+        })
+    }
+
+    /// Queue one delivery of `changes` per endpoint whose event filter matches
+    pub async fn enqueue(&self, changes: &ContentChanges) {
+        let now = Utc::now();
+        let deliveries: Vec<WebhookDelivery> = self
+            .endpoints
+            .iter()
+            .filter(|endpoint| accepts(endpoint, changes))
+            .map(|endpoint| new_delivery(&endpoint.url, changes.clone(), now, None))
+            .collect();
+        if deliveries.is_empty() {
+            return;
+        }
+        let count = deliveries.len();
+        match self.update(move |queue| queue.extend(deliveries)).await {
+            Ok(()) => self.queued.notify_one(),
+            Err(e) => tracing::error!(
+                "Failed to queue {} webhook delivery(ies) in {}: {}",
+                count,
+                self.path.display(),
+                e
+            ),
+        }
+    }
+
+    /// Queue a new delivery with the same URL and payload as delivery `id`.
+    ///
+    /// Returns `None` if there is no such delivery.
+    pub async fn redeliver(&self, id: &str) -> Result<Option<WebhookDelivery>> {
+        let id = id.to_string();
+        let delivery = self
+            .update(move |queue| {
+                let original = queue.iter().find(|d| d.id == id)?;
+                let delivery = new_delivery(
+                    &original.url,
+                    original.changes.clone(),
+                    Utc::now(),
+                    Some(id),
+                );
+                queue.push(delivery.clone());
+                Some(delivery)
+            })
+            .await?;
+        if delivery.is_some() {
+            self.queued.notify_one();
+        }
+        Ok(delivery)
+    }
+
+    /// Up to `limit` deliveries, newest first
+    pub async fn list(&self, limit: usize) -> Result<Vec<WebhookDelivery>> {
+        let path = self.path.clone();
+        let queue = tokio::task::spawn_blocking(move || load(&path))
+            .await
+            .map_err(|e| Error::Io(std::io::Error::other(e)))??;
+        Ok(queue.into_iter().rev().take(limit).collect())
+    }
+
+    /// Attempt every delivery that is due.
+    ///
+    /// Returns when the next delivery is due, if any are pending.
+    async fn deliver_due(&self) -> Result<Option<DateTime<Utc>>> {
+        let Some(webhooks) = &self.config else {
+            return Ok(None);
+        };
+        let now = Utc::now();
+        let lease = chrono::Duration::from_std(self.lease).unwrap_or_default();
+        // Claim due deliveries on disk first, so another process doesn't send them too
+        let (due, next) = self
+            .update(move |queue| {
+                let mut due = Vec::new();
+                for delivery in queue.iter_mut() {
+                    if delivery.status == DeliveryStatus::Pending
+                        && delivery.next_attempt_at.is_none_or(|at| at <= now)
+                    {
+                        delivery.next_attempt_at = Some(now + lease);
+                        due.push(delivery.clone());
+                    }
+                }
+                let next = queue
+                    .iter()
+                    .filter(|d| d.status == DeliveryStatus::Pending)
+                    .filter_map(|d| d.next_attempt_at)
+                    .min();
+                (due, next)
+            })
+            .await?;
+        if due.is_empty() {
+            return Ok(next);
+        }
+
+        let attempts = join_all(
+            due.iter()
+                .map(|d| attempt(webhooks.allow_private_ips, self.endpoint(&d.url), d)),
+        )
+        .await;
+        let policies: Vec<(u32, u64)> = due
+            .iter()
+            .map(|d| match self.endpoint(&d.url) {
+                Some(endpoint) => (
+                    endpoint.max_attempts.unwrap_or(webhooks.max_attempts),
+                    endpoint.retry_base_secs.unwrap_or(webhooks.retry_base_secs),
+                ),
+                None => (webhooks.max_attempts, webhooks.retry_base_secs),
+            })
+            .collect();
+        self.update(move |queue| {
+            for ((claimed, (attempt, retry)), (max_attempts, retry_base_secs)) in
+                due.into_iter().zip(attempts).zip(policies)
+            {
+                if let Some(delivery) = queue.iter_mut().find(|d| d.id == claimed.id) {
+                    record(delivery, attempt, retry, max_attempts, retry_base_secs);
+                }
+            }
+        })
+        .await?;
+        // Check again right away; retries may already be due
+        Ok(Some(now))
+    }
+
+    /// The endpoint deliveries to `url` are sent with, if it is still configured.
+    /// URLs are unique (see [`WebhooksConfig::all_endpoints`]).
+    fn endpoint(&self, url: &str) -> Option<&WebhookEndpointConfig> {
+        self.endpoints.iter().find(|endpoint| endpoint.url == url)
+    }
+
+    /// Apply `f` to the queue on disk and write it back if it changed
+    async fn update<T: Send + 'static>(
+        &self,
+        f: impl FnOnce(&mut Vec<WebhookDelivery>) -> T + Send + 'static,
+    ) -> Result<T> {
+        let _guard = FILE_LOCK.lock().await;
+        let path = self.path.clone();
+        let keep = self
+            .config
+            .as_ref()
+            .map_or(usize::MAX, |w| w.keep_deliveries);
+        tokio::task::spawn_blocking(move || {
+            let _lock = lock_file(&path)?;
+            let mut queue = load(&path)?;
+            let before = queue.clone();
+            let out = f(&mut queue);
+            prune(&mut queue, keep);
+            if queue != before {
+                store(&path, &queue)?;
+            }
+            Ok(out)
+        })
+        .await
+        .map_err(|e| Error::Io(std::io::Error::other(e)))?
+    }
+}
+
+/// Handle to the delivery task. Dropping it stops the task.
+pub(crate) struct DeliveryWorker {
+    task: JoinHandle<()>,
+}
+
+impl DeliveryWorker {
+    /// Start delivering from `queue` on the current tokio runtime
+    pub fn spawn(queue: Arc<WebhookQueue>) -> Self {
+        Self {
+            task: tokio::spawn(run(queue)),
+        }
+    }
+}
+
+impl Drop for DeliveryWorker {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn run(queue: Arc<WebhookQueue>) {
+    loop {
+        let next = match queue.deliver_due().await {
+            Ok(next) => next,
+            Err(e) => {
+                tracing::error!(
+                    "Failed to process webhook queue {}: {}",
+                    queue.path.display(),
+                    e
+                );
+                None
+            }
+        };
+        let sleep = next
+            .map(|at| (at - Utc::now()).to_std().unwrap_or_default())
+            .unwrap_or(POLL_INTERVAL)
+            .min(POLL_INTERVAL);
+        tokio::select! {
+            _ = tokio::time::sleep(sleep) => {}
+            _ = queue.queued.notified() => {}
+        }
+    }
+}
+
+/// Whether `endpoint`'s event filter lets a delivery of `changes` through
+fn accepts(endpoint: &WebhookEndpointConfig, changes: &ContentChanges) -> bool {
+    endpoint.events.is_empty()
+        || changes.events().iter().any(|event| {
+            endpoint
+                .events
+                .iter()
+                .any(|pattern| glob_matches(pattern, event.kind.as_str()))
+        })
+}
+
+/// A pending delivery, due now
+fn new_delivery(
+    url: &str,
+    changes: ContentChanges,
+    now: DateTime<Utc>,
+    redelivery_of: Option<String>,
+) -> WebhookDelivery {
+    WebhookDelivery {
+        id: delivery_id(url, now),
+        url: url.to_string(),
+        created_at: now,
+        status: DeliveryStatus::Pending,
+        changes,
+        attempts: Vec::new(),
+        next_attempt_at: Some(now),
+        redelivery_of,
+    }
+}
+
+/// A short id, unique across restarts and processes sharing a queue file
+fn delivery_id(url: &str, now: DateTime<Utc>) -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let mut hasher = Sha256::new();
+    hasher.update(now.timestamp_nanos_opt().unwrap_or_default().to_le_bytes());
+    hasher.update(std::process::id().to_le_bytes());
+    hasher.update(COUNTER.fetch_add(1, Ordering::Relaxed).to_le_bytes());
+    hasher.update(url.as_bytes());
+    hex::encode(&hasher.finalize()[..8])
+}
+
+/// Add an attempt to a delivery and decide what happens next
+fn record(
+    delivery: &mut WebhookDelivery,
+    attempt: DeliveryAttempt,
+    retry: bool,
+    max_attempts: u32,
+    retry_base_secs: u64,
+) {
+    let succeeded = attempt.error.is_none();
+    let at = attempt.at;
+    delivery.attempts.push(attempt);
+    let attempts = delivery.attempts.len() as u32;
+    if succeeded {
+        delivery.status = DeliveryStatus::Delivered;
+        delivery.next_attempt_at = None;
+    } else if !retry || attempts >= max_attempts {
+        tracing::error!(
+            "Webhook {} to {} failed after {} attempt(s)",
+            delivery.id,
+            delivery.url,
+            attempts
+        );
+        delivery.status = DeliveryStatus::Failed;
+        delivery.next_attempt_at = None;
+    } else {
+        let delay = retry_delay(retry_base_secs, attempts);
+        delivery.next_attempt_at = Some(at + chrono::Duration::from_std(delay).unwrap_or_default());
+    }
+}
+
+/// How long a claim lasts: past every endpoint's request timeout, so no
+/// delivery is claimed again while its attempt may still be running
+fn claim_lease(endpoints: &[WebhookEndpointConfig]) -> Duration {
+    let slowest = endpoints.iter().map(|e| e.timeout_secs).max().unwrap_or(0);
+    Duration::from_secs(slowest).saturating_add(CLAIM_MARGIN)
+}
+
+/// Delay after the `attempts`th failed attempt: the base delay, doubled for each retry
+fn retry_delay(base_secs: u64, attempts: u32) -> Duration {
+    let factor = 1u64 << attempts.saturating_sub(1).min(32);
+    Duration::from_secs(base_secs.saturating_mul(factor)).min(MAX_RETRY_DELAY)
+}
+
+/// Drop the oldest finished deliveries beyond `keep`; pending ones always stay
+fn prune(queue: &mut Vec<WebhookDelivery>, keep: usize) {
+    let finished = queue
+        .iter()
+        .filter(|d| d.status != DeliveryStatus::Pending)
+        .count();
+    let mut excess = finished.saturating_sub(keep);
+    queue.retain(|d| {
+        if excess > 0 && d.status != DeliveryStatus::Pending {
+            excess -= 1;
+            false
+        } else {
+            true
+        }
+    });
+}
+
+/// Take an exclusive OS lock on the queue at `path`, held until the returned
+/// file is dropped.
+///
+/// This serializes updates from every process sharing the queue (the server
+/// and CLI commands). The lock is on a `.lock` file beside the queue, since
+/// [`store`] replaces the queue file itself.
+fn lock_file(path: &Path) -> Result<fs::File> {
+    let mut lock_path = path.as_os_str().to_owned();
+    lock_path.push(".lock");
+    let lock_path = PathBuf::from(lock_path);
+    if let Some(dir) = lock_path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir)?;
+    }
+    let file = fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&lock_path)?;
+    FileExt::lock(&file)?;
+    Ok(file)
+}
+
+/// Read the queue, oldest first. A missing file is empty; unreadable lines are skipped.
+fn load(path: &Path) -> Result<Vec<WebhookDelivery>> {
+    let data = match fs::read_to_string(path) {
+        Ok(data) => data,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    Ok(data
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| match serde_json::from_str(line) {
+            Ok(delivery) => Some(delivery),
+            Err(e) => {
+                tracing::warn!("Skipping unreadable webhook delivery: {}", e);
+                None
+            }
+        })
+        .collect())
+}
+
+/// Replace the queue file, so readers see either the old or the new queue
+fn store(path: &Path, queue: &[WebhookDelivery]) -> Result<()> {
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    fs::create_dir_all(dir)?;
+    let mut file = tempfile::NamedTempFile::new_in(dir)?;
+    for delivery in queue {
+        serde_json::to_writer(&mut file, delivery).map_err(std::io::Error::other)?;
+        file.write_all(b"\n")?;
+    }
+    file.as_file().sync_all()?;
+    file.persist(path).map_err(|e| e.error)?;
+    Ok(())
+}
+
+/// Make one attempt at a delivery.
+///
+/// Returns the attempt and whether a failure is worth retrying.
+async fn attempt(
+    allow_private_ips: bool,
+    endpoint: Option<&WebhookEndpointConfig>,
+    delivery: &WebhookDelivery,
+) -> (DeliveryAttempt, bool) {
+    let at = Utc::now();
+    let started = Instant::now();
+    let result = match endpoint {
+        Some(endpoint) => send(allow_private_ips, endpoint, &delivery.changes).await,
+        None => Err(SendFailure::permanent(
+            "endpoint is no longer configured".to_string(),
+        )),
+    };
+    let duration_ms = started.elapsed().as_millis() as u64;
+    let (status_code, error, retry) = match result {
+        Ok(status) => (Some(status), None, false),
+        Err(failure) => {
+            tracing::warn!(
+                "Webhook {} to {} failed: {}",
+                delivery.id,
+                delivery.url,
+                failure.message
+            );
+            (failure.status_code, Some(failure.message), failure.retry)
+        }
+    };
+    (
+        DeliveryAttempt {
+            at,
+            status_code,
+            duration_ms,
+            error,
+        },
+        retry,
+    )
+}
+
+/// Why an attempt failed
+struct SendFailure {
+    message: String,
+    status_code: Option<u16>,
+    /// Whether a later attempt could succeed
+    retry: bool,
+}
+
+impl SendFailure {
+    fn permanent(message: String) -> Self {
+        Self {
+            message,
+            status_code: None,
+            retry: false,
+        }
+    }
+
+    fn transient(message: String) -> Self {
+        Self {
+            message,
+            status_code: None,
+            retry: true,
+        }
+    }
+}
+
+/// Send one webhook request to `endpoint` with SSRF protection, optional HMAC
+/// signing and the endpoint's extra headers.
+///
+/// Resolves DNS once, validates all IPs against private ranges, then pins the
+/// connection to the validated IP using `reqwest::ClientBuilder::resolve()`.
+/// This prevents DNS rebinding (TOCTOU) attacks where DNS changes between
+/// validation and the actual connection.
+///
+/// Network errors and 5xx responses are worth retrying. 4xx responses are not,
+/// since those indicate a problem with the receiver's configuration, not a
+/// transient issue.
+///
+/// `allow_private_ips` disables the private/internal IP check (but not the pinning).
+async fn send(
+    allow_private_ips: bool,
+    endpoint: &WebhookEndpointConfig,
+    changes: &ContentChanges,
+) -> std::result::Result<u16, SendFailure> {
+    let url = endpoint.url.as_str();
+    // 1. Parse URL and validate scheme
+    let parsed = reqwest::Url::parse(url)
+        .map_err(|e| SendFailure::permanent(format!("invalid URL: {}", e)))?;
+
+    let scheme = parsed.scheme();
+    if scheme != "http" && scheme != "https" {
+        return Err(SendFailure::permanent(format!(
+            "unsupported scheme: {}",
+            scheme
+        )));
+    }
+
+    let host = parsed
+        .host_str()
+        .ok_or_else(|| SendFailure::permanent("missing host".to_string()))?
+        .to_string();
+    let port = parsed.port_or_known_default().unwrap_or(443);
+
+    // 2. Resolve DNS once and validate all IPs
+    let addr_str = format!("{}:{}", host, port);
+    let addrs: Vec<std::net::SocketAddr> = addr_str
+        .to_socket_addrs()
+        .map_err(|e| SendFailure::transient(format!("DNS resolution failed: {}", e)))?
+        .collect();
+
+    // 3. Find a safe (non-private) IP address to connect to
+    let safe_addr = addrs
+        .into_iter()
+        .find(|a| allow_private_ips || security::is_safe_ip(&a.ip()))
+        .ok_or_else(|| {
+            SendFailure::permanent("all resolved IPs are private/internal".to_string())
+        })?;
+
+    // 4. Build client pinned to the validated IP (prevents DNS rebinding)
+    //    Redirects disabled to prevent SSRF bypass via 302 to internal IPs.
+    let client = reqwest::Client::builder()
+        .resolve(&host, safe_addr)
+        .redirect(reqwest::redirect::Policy::none())
+        .timeout(Duration::from_secs(endpoint.timeout_secs))
+        .build()
+        .unwrap_or_else(|_| reqwest::Client::new());
+
+    // The timestamp is signed along with the body, so receivers can reject
+    // replays. Each attempt gets a fresh timestamp and signature.
+    let signed_at = Utc::now().timestamp();
+    let timestamp = signed_at.to_string();
+    let body = serde_json::json!({
+        "event": "content_update",
+        "timestamp": signed_at,
+        "old_sha": changes.old_sha,
+        "new_sha": changes.new_sha,
+        "posts": changes.posts,
+        "series": changes.series,
+        "events": changes.events(),
+    })
+    .to_string();
+
+    let mut request = client
+        .post(url)
+        .header("Content-Type", "application/json")
+        .body(body.clone());
+
+    for (name, value) in &endpoint.headers {
+        let value = value
+            .resolve()
+            .map_err(|e| SendFailure::transient(format!("header {}: {}", name, e)))?;
+        let name = reqwest::header::HeaderName::from_bytes(name.as_bytes())
+            .map_err(|e| SendFailure::permanent(format!("header {}: {}", name, e)))?;
+        let value = reqwest::header::HeaderValue::from_str(&value)
+            .map_err(|e| SendFailure::permanent(format!("header {}: {}", name, e)))?;
+        request = request.header(name, value);
+    }
+
+    // Sign with the current secret and the one being rotated out, if configured
+    let mut signatures = Vec::new();
+    for secret_config in [&endpoint.secret, &endpoint.previous_secret]
+        .into_iter()
+        .flatten()
+    {
+        let secret = secret_config
+            .resolve()
+            .map_err(|e| SendFailure::transient(format!("webhook secret: {}", e)))?;
+        if secret.is_empty() {
+            return Err(SendFailure::transient(
+                "webhook secret resolves to an empty string".to_string(),
+            ));
+        }
+        let mac = signature_mac(secret.as_bytes(), &timestamp, body.as_bytes());
+        signatures.push(format!(
+            "{}={}",
+            SIGNATURE_SCHEME,
+            hex::encode(mac.finalize().into_bytes())
+        ));
+    }
+    if !signatures.is_empty() {
+        request = request
+            .header(WEBHOOK_TIMESTAMP_HEADER, &timestamp)
+            .header(WEBHOOK_SIGNATURE_HEADER, signatures.join(","));
+    }
+
+    let response = request
+        .send()
+        .await
+        .map_err(|e| SendFailure::transient(e.to_string()))?;
+    let status = response.status();
+    if status.is_success() {
+        Ok(status.as_u16())
+    } else {
+        Err(SendFailure {
+            message: format!("receiver returned {}", status),
+            status_code: Some(status.as_u16()),
+            retry: !status.is_client_error(),
+        })
+    }
+}
+
+/// HMAC-SHA256 of `<timestamp>.<body>`
+fn signature_mac(secret: &[u8], timestamp: &str, body: &[u8]) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC accepts keys of any length");
+    mac.update(timestamp.as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    mac
+}
+
+/// Check the signature of a webhook request, for receivers.
+///
+/// `timestamp` and `signature` are the values of the [`WEBHOOK_TIMESTAMP_HEADER`]
+/// and [`WEBHOOK_SIGNATURE_HEADER`] headers, and `body` the raw request body.
+/// The request is genuine if any of its signatures was made with any of
+/// `secrets`, so a receiver can accept both secrets while they are rotated.
+/// Timestamps further than `tolerance` from now are rejected, which bounds
+/// how long a captured request can be replayed.
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidWebhookSignature`] if the timestamp is malformed or
+/// outside the tolerance, or no signature matches.
+pub fn verify_webhook_signature(
+    body: &[u8],
+    timestamp: &str,
+    signature: &str,
+    secrets: &[&str],
+    tolerance: std::time::Duration,
+) -> Result<()> {
+    verify_signature_at(Utc::now(), body, timestamp, signature, secrets, tolerance)
+}
+
+fn verify_signature_at(
+    now: DateTime<Utc>,
+    body: &[u8],
+    timestamp: &str,
+    signature: &str,
+    secrets: &[&str],
+    tolerance: std::time::Duration,
+) -> Result<()> {
+    let invalid = |reason: &str| Error::InvalidWebhookSignature(reason.to_string());
+    let timestamp = timestamp.trim();
+    let signed_at: i64 = timestamp
+        .parse()
+        .map_err(|_| invalid("malformed timestamp"))?;
+    if now.timestamp().abs_diff(signed_at) > tolerance.as_secs() {
+        return Err(invalid("timestamp is outside the tolerance"));
+    }
+
+    let signatures: Vec<Vec<u8>> = signature
+        .split(',')
+        .filter_map(|part| part.trim().split_once('='))
+        .filter(|(scheme, _)| *scheme == SIGNATURE_SCHEME)
+        .filter_map(|(_, sig)| hex::decode(sig).ok())
+        .collect();
+    if signatures.is_empty() {
+        return Err(invalid("no v1 signature"));
+    }
+    let matches = secrets
+        .iter()
+        .filter(|secret| !secret.is_empty())
+        .any(|secret| {
+            let mac = signature_mac(secret.as_bytes(), timestamp, body);
+            // verify_slice compares in constant time
+            signatures
+                .iter()
+                .any(|sig| mac.clone().verify_slice(sig).is_ok())
+        });
+    if matches {
+        Ok(())
+    } else {
+        Err(invalid("no signature matches"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn delivery(id: &str, status: DeliveryStatus) -> WebhookDelivery {
+        WebhookDelivery {
+            id: id.to_string(),
+            status,
+            ..new_delivery(
+                "http://example.com/hook",
+                ContentChanges::default(),
+                Utc::now(),
+                None,
+            )
+        }
+    }
+
+    #[test]
+    fn test_event_filter() {
+        let config: WebhooksConfig = toml::from_str(
+            r#"
+[[endpoints]]
+url = "http://example.com/all"
+
+[[endpoints]]
+url = "http://example.com/series"
+events = ["series.*", "post.deleted"]
+"#,
+        )
+        .unwrap();
+        let endpoints = config.all_endpoints().unwrap();
+        let mut changes = ContentChanges::default();
+        changes.posts.created.push("hello".to_string());
+        assert!(accepts(&endpoints[0], &changes));
+        assert!(!accepts(&endpoints[1], &changes));
+
+        changes.series.updated.push("guide".to_string());
+        assert!(accepts(&endpoints[1], &changes));
+        assert!(!accepts(&endpoints[1], &ContentChanges::default()));
+    }
+
+    fn sign(secret: &str, timestamp: &str, body: &[u8]) -> String {
+        let mac = signature_mac(secret.as_bytes(), timestamp, body);
+        format!("v1={}", hex::encode(mac.finalize().into_bytes()))
+    }
+
+    #[test]
+    fn test_verify_signature() {
+        let now = Utc::now();
+        let timestamp = now.timestamp().to_string();
+        let body = br#"{"event":"content_update"}"#;
+        let tolerance = std::time::Duration::from_secs(300);
+        let verify = |timestamp: &str, signature: &str, secrets: &[&str]| {
+            verify_signature_at(now, body, timestamp, signature, secrets, tolerance)
+        };
+
+        let signature = sign("new", &timestamp, body);
+        assert!(verify(&timestamp, &signature, &["new"]).is_ok());
+        assert!(verify(&timestamp, &signature, &["old", "new"]).is_ok());
+        assert!(verify(&timestamp, &signature, &["old"]).is_err());
+        assert!(verify(&timestamp, &signature, &[""]).is_err());
+
+        // Either signature of a rotation matches
+        let both = format!(
+            "{},{}",
+            sign("new", &timestamp, body),
+            sign("old", &timestamp, body)
+        );
+        assert!(verify(&timestamp, &both, &["old"]).is_ok());
+        assert!(verify(&timestamp, &both, &["new"]).is_ok());
+
+        // The timestamp is covered by the signature and must be recent
+        let earlier = (now.timestamp() - 1).to_string();
+        assert!(verify(&earlier, &signature, &["new"]).is_err());
+        let stale = (now.timestamp() - 301).to_string();
+        assert!(verify(&stale, &sign("new", &stale, body), &["new"]).is_err());
+        assert!(verify("soon", &signature, &["new"]).is_err());
+
+        assert!(matches!(
+            verify(&timestamp, "sha256=abc", &["new"]),
+            Err(Error::InvalidWebhookSignature(_))
+        ));
+    }
+
+    #[test]
+    fn test_claim_lease_outlasts_slowest_timeout() {
+        let config: WebhooksConfig = toml::from_str(
+            r#"
+on_content_update = ["http://example.com/default"]
+
+[[endpoints]]
+url = "http://example.com/slow"
+timeout_secs = 300
+"#,
+        )
+        .unwrap();
+        let endpoints = config.all_endpoints().unwrap();
+        assert_eq!(
+            claim_lease(&endpoints),
+            Duration::from_secs(300) + CLAIM_MARGIN
+        );
+        assert_eq!(
+            claim_lease(&endpoints[..1]),
+            Duration::from_secs(10) + CLAIM_MARGIN
+        );
+        assert_eq!(claim_lease(&[]), CLAIM_MARGIN);
+    }
+
+    #[test]
+    fn test_retry_delay_doubles_up_to_cap() {
+        assert_eq!(retry_delay(30, 1), Duration::from_secs(30));
+        assert_eq!(retry_delay(30, 2), Duration::from_secs(60));
+        assert_eq!(retry_delay(30, 4), Duration::from_secs(240));
+        assert_eq!(retry_delay(30, 40), MAX_RETRY_DELAY);
+    }
+
+    #[test]
+    fn test_prune_keeps_pending_and_newest_finished() {
+        let mut queue = vec![
+            delivery("a", DeliveryStatus::Delivered),
+            delivery("b", DeliveryStatus::Pending),
+            delivery("c", DeliveryStatus::Failed),
+            delivery("d", DeliveryStatus::Delivered),
+        ];
+        prune(&mut queue, 1);
+        let ids: Vec<&str> = queue.iter().map(|d| d.id.as_str()).collect();
+        assert_eq!(ids, ["b", "d"]);
+    }
+
+    #[test]
+    fn test_store_and_load_round_trip() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("nested").join("queue.jsonl");
+        assert!(load(&path).unwrap().is_empty());
+
+        let queue = vec![
+            delivery("a", DeliveryStatus::Delivered),
+            delivery("b", DeliveryStatus::Pending),
+        ];
+        store(&path, &queue).unwrap();
+        assert_eq!(load(&path).unwrap(), queue);
+    }
+
+    #[test]
+    fn test_lock_file_is_exclusive() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("queue.jsonl");
+        let held = lock_file(&path).unwrap();
+
+        let other = fs::File::open(dir.path().join("queue.jsonl.lock")).unwrap();
+        assert!(FileExt::try_lock(&other).is_err());
+        drop(held);
+        assert!(FileExt::try_lock(&other).is_ok());
+    }
+}
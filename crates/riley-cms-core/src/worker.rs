@@ -0,0 +1,221 @@
+//! Background refresh worker
+//!
+//! Pushes request a refresh instead of running one. A single task per
+//! [`RileyCms`](crate::RileyCms) does the work: it waits for a burst of
+//! requests to settle, checks out the working tree if content is read from it,
+//! reloads once, and fires webhooks only if the live content changed.
+
+use crate::config::{ContentSource, RefreshPolicy, RileyCmsConfig};
+use crate::content::ContentCache;
+use crate::error::{Error, Result};
+use crate::git::GitBackend;
+use crate::types::{ContentChanges, RefreshOutcome, RefreshRunResult, RefreshStatus};
+use crate::webhooks::WebhookQueue;
+use chrono::Utc;
+use std::path::Path;
+use std::sync::{Arc, Mutex, PoisonError};
+use tokio::sync::{Notify, RwLock, mpsc};
+use tokio::time::{Duration, Instant, timeout};
+
+/// A burst of requests delays a run by at most this many debounce periods
+const MAX_DEBOUNCE_PERIODS: u32 = 10;
+
+/// What a refresh needs from [`RileyCms`](crate::RileyCms)
+#[derive(Clone)]
+pub(crate) struct Refresher {
+    pub config: RileyCmsConfig,
+    pub cache: Arc<RwLock<ContentCache>>,
+    /// Held while a reload runs, so reloads never overlap
+    pub lock: Arc<tokio::sync::Mutex<()>>,
+    /// Notified when new content is swapped in
+    pub reloaded: Arc<Notify>,
+    pub webhooks: Arc<WebhookQueue>,
+}
+
+impl Refresher {
+    /// Reload content and swap it in, as allowed by `[content.refresh]`
+    pub async fn refresh(&self) -> Result<RefreshOutcome> {
+        self.reload().await.map(|(outcome, _)| outcome)
+    }
+
+    /// [`refresh`](Self::refresh), also returning what changed if the new content went live
+    async fn reload(&self) -> Result<(RefreshOutcome, Option<ContentChanges>)> {
+        let _guard = self.lock.lock().await;
+
+        // Clone the config to move into the blocking task closure
+        let content_config = self.config.content.clone();
+
+        // Offload blocking filesystem I/O to a dedicated thread pool
+        let new_cache = tokio::task::spawn_blocking(move || ContentCache::load(&content_config))
+            .await
+            .map_err(|e| Error::Io(std::io::Error::other(e)))??;
+
+        let mut cache = self.cache.write().await;
+        let policy = self.config.content.refresh.policy;
+        if policy != RefreshPolicy::Replace
+            && let Some(reason) = new_cache.regression_from(&cache, &self.config.content)
+        {
+            if policy == RefreshPolicy::Strict {
+                return Err(Error::RefreshRejected(reason));
+            }
+            tracing::warn!("Keeping previous content: {}", reason);
+            return Ok((RefreshOutcome::KeptPrevious { reason }, None));
+        }
+        let changes = new_cache.changes_from(&cache);
+        *cache = new_cache;
+        self.reloaded.notify_one();
+        Ok((RefreshOutcome::Replaced, Some(changes)))
+    }
+
+    /// One worker run: check out, reload, and notify webhooks of a change.
+    ///
+    /// `head` is the commit checked out by the last run that went live, for
+    /// content read from the working tree.
+    async fn deploy(&self, head: &mut Option<String>) -> (RefreshRunResult, Option<String>) {
+        // Content read from a commit needs no checkout, and knows its commit
+        let worktree = self.config.content.source == ContentSource::Worktree;
+        let new_head = if worktree {
+            checkout_head(&self.config.content.repo_path).await;
+            worktree_head(&self.config.content.repo_path).await
+        } else {
+            None
+        };
+
+        match self.reload().await {
+            Ok((RefreshOutcome::Replaced, changes)) => {
+                let mut changes = changes.unwrap_or_default();
+                if worktree {
+                    changes.old_sha = std::mem::replace(head, new_head.clone());
+                    changes.new_sha = new_head;
+                }
+                if changes.is_empty() {
+                    (RefreshRunResult::Unchanged, None)
+                } else {
+                    self.webhooks.enqueue(&changes).await;
+                    (RefreshRunResult::Changed, None)
+                }
+            }
+            Ok((RefreshOutcome::KeptPrevious { reason }, _)) => {
+                tracing::warn!(
+                    "Pushed content was not published, previous content kept: {}",
+                    reason
+                );
+                (RefreshRunResult::KeptPrevious, Some(reason))
+            }
+            Err(e) => {
+                tracing::error!("Failed to refresh content after git push: {}", e);
+                (RefreshRunResult::Failed, Some(e.to_string()))
+            }
+        }
+    }
+}
+
+/// Handle to the worker task. Dropping it stops the worker once queued work is done.
+pub(crate) struct RefreshWorker {
+    requests: mpsc::UnboundedSender<()>,
+    status: Arc<Mutex<RefreshStatus>>,
+}
+
+impl RefreshWorker {
+    /// Start the worker on the current tokio runtime, once the live content is loaded
+    pub async fn spawn(refresher: Refresher) -> Self {
+        let (requests, receiver) = mpsc::unbounded_channel();
+        let status = Arc::new(Mutex::new(RefreshStatus::default()));
+        let debounce = Duration::from_millis(refresher.config.content.refresh.debounce_ms);
+        let head = if refresher.config.content.source == ContentSource::Worktree {
+            worktree_head(&refresher.config.content.repo_path).await
+        } else {
+            None
+        };
+        tokio::spawn(run(receiver, refresher, status.clone(), debounce, head));
+        Self { requests, status }
+    }
+
+    /// Ask for a run
+    pub fn request(&self) {
+        // Count and send under the lock, so a run that starts counts exactly
+        // the requests it drains from the channel
+        let mut status = self.status.lock().unwrap_or_else(PoisonError::into_inner);
+        if self.requests.send(()).is_ok() {
+            status.pending += 1;
+        } else {
+            tracing::error!("Refresh worker has stopped; refresh request dropped");
+        }
+    }
+
+    pub fn status(&self) -> RefreshStatus {
+        self.status
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+}
+
+async fn run(
+    mut requests: mpsc::UnboundedReceiver<()>,
+    refresher: Refresher,
+    status: Arc<Mutex<RefreshStatus>>,
+    debounce: Duration,
+    mut head: Option<String>,
+) {
+    let update = |f: &mut dyn FnMut(&mut RefreshStatus)| {
+        f(&mut status.lock().unwrap_or_else(PoisonError::into_inner));
+    };
+
+    while requests.recv().await.is_some() {
+        // Let a burst of requests settle into one run, but don't wait forever
+        let deadline = Instant::now() + debounce * MAX_DEBOUNCE_PERIODS;
+        while let Ok(Some(())) = timeout(
+            debounce.min(deadline.saturating_duration_since(Instant::now())),
+            requests.recv(),
+        )
+        .await
+        {}
+
+        update(&mut |s| {
+            while requests.try_recv().is_ok() {}
+            s.coalesced += s.pending.saturating_sub(1);
+            s.pending = 0;
+            s.running = true;
+            s.last_started_at = Some(Utc::now());
+        });
+        let (result, message) = refresher.deploy(&mut head).await;
+        update(&mut |s| {
+            s.running = false;
+            s.runs += 1;
+            s.last_finished_at = Some(Utc::now());
+            s.last_result = Some(result);
+            s.last_message = message.clone();
+        });
+    }
+}
+
+/// Commit `HEAD` points to, if `repo_path` is a git repository with commits
+async fn worktree_head(repo_path: &Path) -> Option<String> {
+    GitBackend::new(repo_path).ref_target("HEAD").await
+}
+
+/// Check HEAD out into the working tree.
+///
+/// git-http-backend updates the git database but not the working tree, so we
+/// must checkout explicitly before refreshing the content cache.
+async fn checkout_head(repo_path: &Path) {
+    match tokio::process::Command::new("git")
+        .arg("-C")
+        .arg(repo_path)
+        .args(["checkout", "-f", "HEAD"])
+        .output()
+        .await
+    {
+        Ok(output) if !output.status.success() => {
+            tracing::error!(
+                "git checkout failed after push: {}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+        Err(e) => {
+            tracing::error!("Failed to run git checkout after push: {}", e);
+        }
+        _ => {}
+    }
+}
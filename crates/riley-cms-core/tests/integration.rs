@@ -2,12 +2,20 @@
 //!
 //! These tests verify the full RileyCms workflow works end-to-end.
 
-use riley_cms_core::{ListOptions, RileyCms, RileyCmsConfig};
+use riley_cms_core::{
+    ContentCache, ContentChanges, ContentEvent, ContentEventKind, ContentSource, DeliveryStatus,
+    DiagnosticKind, Error, ListOptions, RefreshOutcome, RefreshRunResult, RefreshStatus, RileyCms,
+    RileyCmsConfig, WEBHOOK_SIGNATURE_HEADER, WEBHOOK_TIMESTAMP_HEADER, WebhookDelivery,
+    verify_webhook_signature,
+};
+use riley_cms_testing::{MemoryStorage, PostFixture, SeriesFixture, TestContent, WebhookReceiver};
 use std::fs;
+use std::sync::Arc;
+use std::time::Duration;
 use tempfile::TempDir;
 
 fn create_test_config(temp_dir: &TempDir) -> RileyCmsConfig {
-    // Parse a minimal config
+    // Parse a minimal config; see `riley_cms` for storage
     let toml = format!(
         r#"
 [content]
@@ -15,7 +23,6 @@ repo_path = "{}"
 content_dir = "content"
 
 [storage]
-bucket = "test-bucket"
 public_url_base = "https://test.example.com"
 "#,
         temp_dir.path().display()
@@ -23,73 +30,10 @@ public_url_base = "https://test.example.com"
     toml::from_str(&toml).unwrap()
 }
 
-fn create_post(dir: &std::path::Path, slug: &str, title: &str, goes_live_at: Option<&str>) {
-    let post_dir = dir.join(slug);
-    fs::create_dir_all(&post_dir).unwrap();
-
-    let date_line = goes_live_at
-        .map(|d| format!("goes_live_at = \"{}\"", d))
-        .unwrap_or_default();
-
-    fs::write(
-        post_dir.join("config.toml"),
-        format!(
-            r#"title = "{}"
-preview_text = "Preview for {}"
-{}
-"#,
-            title, title, date_line
-        ),
-    )
-    .unwrap();
-
-    fs::write(
-        post_dir.join("content.mdx"),
-        format!("# {}\n\nContent for this post.", title),
-    )
-    .unwrap();
-}
-
-fn create_series(
-    dir: &std::path::Path,
-    slug: &str,
-    title: &str,
-    posts: &[(&str, &str, i32)], // (slug, title, order)
-) {
-    let series_dir = dir.join(slug);
-    fs::create_dir_all(&series_dir).unwrap();
-
-    fs::write(
-        series_dir.join("series.toml"),
-        format!(
-            r#"title = "{}"
-description = "Description for {}"
-goes_live_at = "2025-01-01T00:00:00Z"
-"#,
-            title, title
-        ),
-    )
-    .unwrap();
-
-    for (post_slug, post_title, order) in posts {
-        let post_dir = series_dir.join(post_slug);
-        fs::create_dir_all(&post_dir).unwrap();
-
-        fs::write(
-            post_dir.join("config.toml"),
-            format!(
-                r#"title = "{}"
-preview_text = "Preview"
-goes_live_at = "2025-01-01T00:00:00Z"
-order = {}
-"#,
-                post_title, order
-            ),
-        )
-        .unwrap();
-
-        fs::write(post_dir.join("content.mdx"), format!("# {}", post_title)).unwrap();
-    }
+/// Build a `RileyCms`; in-memory storage keeps these tests off the network
+async fn riley_cms(config: RileyCmsConfig) -> RileyCms {
+    let storage = Arc::new(MemoryStorage::new("https://test.example.com"));
+    RileyCms::with_storage(config, storage).await.unwrap()
 }
 
 #[tokio::test]
@@ -97,7 +41,7 @@ async fn test_riley_cms_with_empty_content() {
     let temp_dir = TempDir::new().unwrap();
     let config = create_test_config(&temp_dir);
 
-    let riley_cms = RileyCms::from_config(config).await.unwrap();
+    let riley_cms = riley_cms(config).await;
 
     let posts = riley_cms.list_posts(&ListOptions::default()).await.unwrap();
     assert_eq!(posts.total, 0);
@@ -116,27 +60,18 @@ async fn test_riley_cms_list_posts() {
     let content_dir = temp_dir.path().join("content");
 
     // Create 3 live posts
-    create_post(
-        &content_dir,
-        "post-a",
-        "Post A",
-        Some("2020-01-01T00:00:00Z"),
-    );
-    create_post(
-        &content_dir,
-        "post-b",
-        "Post B",
-        Some("2020-02-01T00:00:00Z"),
-    );
-    create_post(
-        &content_dir,
-        "post-c",
-        "Post C",
-        Some("2020-03-01T00:00:00Z"),
-    );
+    PostFixture::new("post-a", "Post A")
+        .live()
+        .write_to(&content_dir);
+    PostFixture::new("post-b", "Post B")
+        .goes_live_at("2020-02-01T00:00:00Z")
+        .write_to(&content_dir);
+    PostFixture::new("post-c", "Post C")
+        .goes_live_at("2020-03-01T00:00:00Z")
+        .write_to(&content_dir);
 
     let config = create_test_config(&temp_dir);
-    let riley_cms = RileyCms::from_config(config).await.unwrap();
+    let riley_cms = riley_cms(config).await;
 
     let posts = riley_cms.list_posts(&ListOptions::default()).await.unwrap();
     assert_eq!(posts.total, 3);
@@ -153,15 +88,12 @@ async fn test_riley_cms_get_post() {
     let temp_dir = TempDir::new().unwrap();
     let content_dir = temp_dir.path().join("content");
 
-    create_post(
-        &content_dir,
-        "my-post",
-        "My Post",
-        Some("2020-01-01T00:00:00Z"),
-    );
+    PostFixture::new("my-post", "My Post")
+        .live()
+        .write_to(&content_dir);
 
     let config = create_test_config(&temp_dir);
-    let riley_cms = RileyCms::from_config(config).await.unwrap();
+    let riley_cms = riley_cms(config).await;
 
     let post = riley_cms.get_post("my-post").await.unwrap().unwrap();
     assert_eq!(post.slug, "my-post");
@@ -179,19 +111,18 @@ async fn test_riley_cms_drafts_and_scheduled() {
     let content_dir = temp_dir.path().join("content");
 
     // Live post (past date)
-    create_post(&content_dir, "live", "Live", Some("2020-01-01T00:00:00Z"));
+    PostFixture::new("live", "Live")
+        .live()
+        .write_to(&content_dir);
     // Draft post (no date)
-    create_post(&content_dir, "draft", "Draft", None);
+    PostFixture::new("draft", "Draft").write_to(&content_dir);
     // Scheduled post (future date)
-    create_post(
-        &content_dir,
-        "scheduled",
-        "Scheduled",
-        Some("2099-01-01T00:00:00Z"),
-    );
+    PostFixture::new("scheduled", "Scheduled")
+        .goes_live_at("2099-01-01T00:00:00Z")
+        .write_to(&content_dir);
 
     let config = create_test_config(&temp_dir);
-    let riley_cms = RileyCms::from_config(config).await.unwrap();
+    let riley_cms = riley_cms(config).await;
 
     // Default: only live
     let posts = riley_cms.list_posts(&ListOptions::default()).await.unwrap();
@@ -235,19 +166,19 @@ async fn test_riley_cms_series() {
     let temp_dir = TempDir::new().unwrap();
     let content_dir = temp_dir.path().join("content");
 
-    create_series(
-        &content_dir,
-        "rust-series",
-        "Learning Rust",
-        &[
-            ("intro", "Introduction", 1),
-            ("advanced", "Advanced Topics", 3),
-            ("basics", "Basic Concepts", 2),
-        ],
-    );
+    SeriesFixture::new("rust-series", "Learning Rust")
+        .live()
+        .post(PostFixture::new("intro", "Introduction").live().order(1))
+        .post(
+            PostFixture::new("advanced", "Advanced Topics")
+                .live()
+                .order(3),
+        )
+        .post(PostFixture::new("basics", "Basic Concepts").live().order(2))
+        .write_to(&content_dir);
 
     let config = create_test_config(&temp_dir);
-    let riley_cms = RileyCms::from_config(config).await.unwrap();
+    let riley_cms = riley_cms(config).await;
 
     // List series
     let series_list = riley_cms
@@ -276,16 +207,13 @@ async fn test_riley_cms_pagination() {
 
     // Create 5 posts
     for i in 1..=5 {
-        create_post(
-            &content_dir,
-            &format!("post-{}", i),
-            &format!("Post {}", i),
-            Some("2020-01-01T00:00:00Z"),
-        );
+        PostFixture::new(format!("post-{}", i), format!("Post {}", i))
+            .live()
+            .write_to(&content_dir);
     }
 
     let config = create_test_config(&temp_dir);
-    let riley_cms = RileyCms::from_config(config).await.unwrap();
+    let riley_cms = riley_cms(config).await;
 
     // Limit to 2
     let posts = riley_cms
@@ -319,12 +247,9 @@ async fn test_riley_cms_content_validation() {
     let content_dir = temp_dir.path().join("content");
 
     // Create a valid post
-    create_post(
-        &content_dir,
-        "valid",
-        "Valid Post",
-        Some("2020-01-01T00:00:00Z"),
-    );
+    PostFixture::new("valid", "Valid Post")
+        .live()
+        .write_to(&content_dir);
 
     // Create an invalid post (empty title)
     let bad_post = content_dir.join("bad-post");
@@ -339,7 +264,7 @@ preview_text = "Preview"
     fs::write(bad_post.join("content.mdx"), "# Content").unwrap();
 
     let config = create_test_config(&temp_dir);
-    let riley_cms = RileyCms::from_config(config).await.unwrap();
+    let riley_cms = riley_cms(config).await;
 
     let errors = riley_cms.validate_content().await.unwrap();
     assert!(!errors.is_empty());
@@ -352,26 +277,20 @@ async fn test_riley_cms_refresh() {
     let content_dir = temp_dir.path().join("content");
 
     // Start with one post
-    create_post(
-        &content_dir,
-        "post-1",
-        "Post 1",
-        Some("2020-01-01T00:00:00Z"),
-    );
+    PostFixture::new("post-1", "Post 1")
+        .live()
+        .write_to(&content_dir);
 
     let config = create_test_config(&temp_dir);
-    let riley_cms = RileyCms::from_config(config).await.unwrap();
+    let riley_cms = riley_cms(config).await;
 
     let posts = riley_cms.list_posts(&ListOptions::default()).await.unwrap();
     assert_eq!(posts.total, 1);
 
     // Add another post
-    create_post(
-        &content_dir,
-        "post-2",
-        "Post 2",
-        Some("2020-01-01T00:00:00Z"),
-    );
+    PostFixture::new("post-2", "Post 2")
+        .live()
+        .write_to(&content_dir);
 
     // Before refresh, still shows 1
     let posts = riley_cms.list_posts(&ListOptions::default()).await.unwrap();
@@ -383,20 +302,279 @@ async fn test_riley_cms_refresh() {
     assert_eq!(posts.total, 2);
 }
 
+fn content_with_two_posts() -> TestContent {
+    TestContent::new()
+        .with_post(PostFixture::new("hello", "Hello").live())
+        .with_post(PostFixture::new("world", "World").live())
+}
+
+#[tokio::test]
+async fn test_refresh_keeps_previous_content_on_load_error() {
+    let content = content_with_two_posts();
+    let riley_cms = content.riley_cms().await;
+
+    content.write_file("hello/config.toml", "title = ");
+    content.add_post(&PostFixture::new("new", "New").live());
+
+    let outcome = riley_cms.refresh().await.unwrap();
+    let RefreshOutcome::KeptPrevious { reason } = outcome else {
+        panic!("expected previous content to be kept, got {:?}", outcome);
+    };
+    assert!(reason.contains("'hello'"), "{}", reason);
+
+    // Still serving the previous content, broken post included
+    assert!(riley_cms.get_post("hello").await.unwrap().is_some());
+    assert!(riley_cms.get_post("new").await.unwrap().is_none());
+    assert!(riley_cms.load_diagnostics().await.is_empty());
+}
+
+#[tokio::test]
+async fn test_refresh_keeps_previous_content_when_posts_disappear() {
+    let content = content_with_two_posts().with_post(PostFixture::new("third", "Third").live());
+    let riley_cms = content.riley_cms().await;
+
+    fs::remove_dir_all(content.content_path().join("hello")).unwrap();
+    fs::remove_dir_all(content.content_path().join("world")).unwrap();
+
+    let outcome = riley_cms.refresh().await.unwrap();
+    assert!(matches!(outcome, RefreshOutcome::KeptPrevious { .. }));
+    let posts = riley_cms.list_posts(&ListOptions::default()).await.unwrap();
+    assert_eq!(posts.total, 3);
+
+    // Losing one post in three is within the default 50% allowance
+    let content = content_with_two_posts().with_post(PostFixture::new("third", "Third").live());
+    let riley_cms = content.riley_cms().await;
+    fs::remove_dir_all(content.content_path().join("hello")).unwrap();
+    assert_eq!(riley_cms.refresh().await.unwrap(), RefreshOutcome::Replaced);
+}
+
+#[tokio::test]
+async fn test_refresh_strict_policy_returns_error() {
+    let content = content_with_two_posts();
+    let config = content.config_with("[content.refresh]\npolicy = \"strict\"");
+    let riley_cms = content.riley_cms_with(config).await;
+
+    content.write_file("world/config.toml", "not toml");
+
+    let err = riley_cms.refresh().await.unwrap_err();
+    assert!(matches!(err, Error::RefreshRejected(_)), "{}", err);
+    let posts = riley_cms.list_posts(&ListOptions::default()).await.unwrap();
+    assert_eq!(posts.total, 2);
+}
+
+#[tokio::test]
+async fn test_refresh_allows_edits_while_other_content_stays_broken() {
+    let content = content_with_two_posts();
+    content.write_file("world/config.toml", "not toml");
+    let config = content.config_with("[content.refresh]\npolicy = \"strict\"");
+    let riley_cms = content.riley_cms_with(config).await;
+    let posts = riley_cms.list_posts(&ListOptions::default()).await.unwrap();
+    assert_eq!(posts.total, 1);
+
+    content.write_file("hello/content.mdx", "# Edited");
+
+    assert_eq!(riley_cms.refresh().await.unwrap(), RefreshOutcome::Replaced);
+    let post = riley_cms.get_post("hello").await.unwrap().unwrap();
+    assert!(post.content.contains("Edited"));
+
+    // A newly broken post is still rejected
+    content.write_file("hello/config.toml", "not toml");
+    let err = riley_cms.refresh().await.unwrap_err();
+    assert!(matches!(err, Error::RefreshRejected(_)), "{}", err);
+}
+
+#[tokio::test]
+async fn test_refresh_replace_policy_always_swaps() {
+    let content = content_with_two_posts();
+    let config = content.config_with("[content.refresh]\npolicy = \"replace\"");
+    let riley_cms = content.riley_cms_with(config).await;
+
+    content.write_file("world/config.toml", "not toml");
+
+    assert_eq!(riley_cms.refresh().await.unwrap(), RefreshOutcome::Replaced);
+    assert!(riley_cms.get_post("world").await.unwrap().is_none());
+
+    let diagnostics = riley_cms.load_diagnostics().await;
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].kind, DiagnosticKind::Parse);
+    assert_eq!(diagnostics[0].path, "world");
+}
+
+#[test]
+fn test_content_changes() {
+    let guide = SeriesFixture::new("guide", "Guide")
+        .live()
+        .post(PostFixture::new("intro", "Intro").live());
+    let content = TestContent::new()
+        .with_post(PostFixture::new("hello", "Hello").live())
+        .with_post(PostFixture::new("world", "World").live())
+        .with_post(PostFixture::new("draft", "Draft"))
+        .with_series(guide.clone());
+    let config = content.config();
+    let before = ContentCache::load(&config.content).unwrap();
+    assert!(before.changes_from(&before).is_empty());
+
+    content.add_post(&PostFixture::new("hello", "Hello, edited").live());
+    fs::remove_dir_all(content.content_path().join("world")).unwrap();
+    content.add_post(&PostFixture::new("draft", "Draft").live());
+    content.add_series(&guide.post(PostFixture::new("part-2", "Part 2").live()));
+    let after = ContentCache::load(&config.content).unwrap();
+
+    let changes = after.changes_from(&before);
+    assert_eq!(changes.posts.created, ["draft", "guide/part-2"]);
+    assert_eq!(changes.posts.updated, ["hello"]);
+    assert_eq!(changes.posts.deleted, ["world"]);
+    assert_eq!(changes.series.updated, ["guide"]);
+    assert!(changes.series.created.is_empty() && changes.series.deleted.is_empty());
+    assert_eq!(changes.old_sha, None);
+
+    let events = changes.events();
+    assert_eq!(events.len(), 5);
+    assert_eq!(
+        events[0],
+        ContentEvent {
+            kind: ContentEventKind::PostPublished,
+            slug: "draft".to_string()
+        }
+    );
+    assert_eq!(events[3].kind, ContentEventKind::PostDeleted);
+    assert_eq!(events[4].kind, ContentEventKind::SeriesUpdated);
+}
+
+#[test]
+fn test_content_changes_between() {
+    let content = TestContent::new()
+        .with_post(PostFixture::new("hello", "Hello").live())
+        .with_post(PostFixture::new("later", "Later").goes_live_at("2030-01-01T00:00:00Z"))
+        .with_series(
+            SeriesFixture::new("guide", "Guide")
+                .live()
+                .post(PostFixture::new("intro", "Intro").live())
+                .post(PostFixture::new("part-2", "Part 2").goes_live_at("2030-06-01T00:00:00Z")),
+        );
+    let cache = ContentCache::load(&content.config().content).unwrap();
+    let at = |s: &str| s.parse::<chrono::DateTime<chrono::Utc>>().unwrap();
+
+    assert_eq!(
+        cache.next_scheduled(at("2029-01-01T00:00:00Z")),
+        Some(at("2030-01-01T00:00:00Z"))
+    );
+    assert_eq!(
+        cache.next_scheduled(at("2030-01-01T00:00:00Z")),
+        Some(at("2030-06-01T00:00:00Z"))
+    );
+    assert_eq!(cache.next_scheduled(at("2030-06-01T00:00:00Z")), None);
+
+    // The boundary itself counts as live
+    let changes = cache.changes_between(at("2029-01-01T00:00:00Z"), at("2030-01-01T00:00:00Z"));
+    assert_eq!(changes.posts.created, ["later"]);
+    assert!(changes.series.is_empty());
+
+    let changes = cache.changes_between(at("2030-01-01T00:00:00Z"), at("2031-01-01T00:00:00Z"));
+    assert_eq!(changes.posts.created, ["guide/part-2"]);
+    assert_eq!(changes.series.updated, ["guide"]);
+    assert_eq!(
+        changes.events().iter().map(|e| e.kind).collect::<Vec<_>>(),
+        [
+            ContentEventKind::PostPublished,
+            ContentEventKind::SeriesUpdated
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_scheduler_announces_scheduled_posts() {
+    let goes_live = chrono::Utc::now() + chrono::Duration::seconds(2);
+    let content = TestContent::new()
+        .with_post(PostFixture::new("hello", "Hello").live())
+        .with_post(PostFixture::new("soon", "Soon").goes_live_at(goes_live.to_rfc3339()));
+    let receiver = WebhookReceiver::start().await;
+    let riley_cms = content
+        .riley_cms_with(content.config_with(&receiver.config_toml(None)))
+        .await;
+    let etag = riley_cms.content_etag().await;
+    let posts = riley_cms.list_posts(&ListOptions::default()).await.unwrap();
+    assert_eq!(posts.total, 1);
+
+    let deliveries = receiver.wait_for(1, Duration::from_secs(10)).await;
+    assert_eq!(deliveries.len(), 1);
+    assert_eq!(
+        deliveries[0].json()["events"],
+        serde_json::json!([{"type": "post.published", "slug": "soon"}])
+    );
+    assert!(chrono::Utc::now() >= goes_live);
+    assert_ne!(riley_cms.content_etag().await, etag);
+    let posts = riley_cms.list_posts(&ListOptions::default()).await.unwrap();
+    assert_eq!(posts.total, 2);
+}
+
+/// Wait for the refresh worker to finish `runs` runs with nothing queued
+async fn wait_for_runs(riley_cms: &RileyCms, runs: u64) -> RefreshStatus {
+    for _ in 0..50 {
+        let status = riley_cms.refresh_status();
+        if status.runs >= runs && !status.running && status.pending == 0 {
+            return status;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    panic!("refresh worker stuck: {:?}", riley_cms.refresh_status());
+}
+
+#[tokio::test]
+async fn test_refresh_worker_coalesces_requests() {
+    let content = TestContent::new().with_post(PostFixture::new("hello", "Hello").live());
+    content.git_init();
+    content.commit("Add hello", "2024-01-01T00:00:00Z");
+    let receiver = WebhookReceiver::start().await;
+    let mut config = content.config_with(&receiver.config_toml(None));
+    config.content.refresh.debounce_ms = 100;
+    let riley_cms = content.riley_cms_with(config).await;
+    assert_eq!(riley_cms.refresh_status(), RefreshStatus::default());
+
+    let first = content.git(&["rev-parse", "HEAD"]);
+    content.add_post(&PostFixture::new("second", "Second").live());
+    let second = content.commit("Add second", "2024-01-02T00:00:00Z");
+    for _ in 0..3 {
+        riley_cms.request_refresh();
+    }
+
+    let status = wait_for_runs(&riley_cms, 1).await;
+    assert_eq!(status.runs, 1);
+    assert_eq!(status.coalesced, 2);
+    assert_eq!(status.last_result, Some(RefreshRunResult::Changed));
+    assert!(status.last_finished_at >= status.last_started_at);
+    assert!(riley_cms.get_post("second").await.unwrap().is_some());
+    let deliveries = receiver.wait_for(1, Duration::from_secs(5)).await;
+    assert_eq!(deliveries.len(), 1);
+    let payload = deliveries[0].json();
+    assert_eq!(payload["event"], "content_update");
+    assert_eq!(payload["old_sha"], first.as_str());
+    assert_eq!(payload["new_sha"], second.as_str());
+    assert_eq!(payload["posts"]["created"], serde_json::json!(["second"]));
+    assert_eq!(
+        payload["events"],
+        serde_json::json!([{"type": "post.published", "slug": "second"}])
+    );
+
+    // Nothing changed since the last run: no webhooks
+    riley_cms.request_refresh();
+    let status = wait_for_runs(&riley_cms, 2).await;
+    assert_eq!(status.last_result, Some(RefreshRunResult::Unchanged));
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(receiver.deliveries().len(), 1);
+}
+
 #[tokio::test]
 async fn test_riley_cms_etag() {
     let temp_dir = TempDir::new().unwrap();
     let content_dir = temp_dir.path().join("content");
 
-    create_post(
-        &content_dir,
-        "post-1",
-        "Post 1",
-        Some("2020-01-01T00:00:00Z"),
-    );
+    PostFixture::new("post-1", "Post 1")
+        .live()
+        .write_to(&content_dir);
 
     let config = create_test_config(&temp_dir);
-    let riley_cms = RileyCms::from_config(config).await.unwrap();
+    let riley_cms = riley_cms(config).await;
 
     let etag1 = riley_cms.content_etag().await;
     assert!(!etag1.is_empty());
@@ -414,3 +592,489 @@ async fn test_riley_cms_etag() {
     let etag3 = riley_cms.content_etag().await;
     assert_ne!(etag1, etag3);
 }
+
+#[tokio::test]
+async fn test_git_dates_and_history() {
+    let content = TestContent::new()
+        .with_post(PostFixture::new("hello", "Hello").live())
+        .with_series(
+            SeriesFixture::new("rust", "Rust")
+                .live()
+                .post(PostFixture::new("part-1", "Part 1").live()),
+        );
+    content.git_init();
+    content.commit("Add posts", "2024-01-01T00:00:00Z");
+    content.add_post(&PostFixture::new("hello", "Hello again").live());
+    content.commit("Revise hello", "2024-03-01T12:00:00Z");
+    // Uncommitted posts have no git dates
+    content.add_post(&PostFixture::new("wip", "WIP").live());
+
+    let riley_cms = content.riley_cms().await;
+
+    let hello = riley_cms.get_post("hello").await.unwrap().unwrap();
+    assert_eq!(
+        hello.created_at.unwrap().to_rfc3339(),
+        "2024-01-01T00:00:00+00:00"
+    );
+    assert_eq!(
+        hello.updated_at.unwrap().to_rfc3339(),
+        "2024-03-01T12:00:00+00:00"
+    );
+
+    let part = riley_cms.get_post("part-1").await.unwrap().unwrap();
+    assert_eq!(part.created_at, part.updated_at);
+    assert!(part.created_at.is_some());
+
+    let wip = riley_cms.get_post("wip").await.unwrap().unwrap();
+    assert!(wip.created_at.is_none());
+
+    let history = riley_cms.post_history("hello", 10).await.unwrap().unwrap();
+    let messages: Vec<&str> = history.iter().map(|r| r.message.as_str()).collect();
+    assert_eq!(messages, vec!["Revise hello", "Add posts"]);
+    assert_eq!(history[0].author, "Test Author");
+    assert_eq!(history[0].sha.len(), 40);
+
+    let history = riley_cms.post_history("part-1", 10).await.unwrap().unwrap();
+    assert_eq!(history.len(), 1);
+
+    assert!(
+        riley_cms
+            .post_history("missing", 10)
+            .await
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[tokio::test]
+async fn test_git_source_reads_committed_content() {
+    let content = TestContent::new()
+        .with_post(PostFixture::new("hello", "Hello").live())
+        .with_series(
+            SeriesFixture::new("rust", "Rust")
+                .live()
+                .post(PostFixture::new("part-1", "Part 1").live()),
+        );
+    content.git_init();
+    let first = content.commit("Add posts", "2024-01-01T00:00:00Z");
+    // Uncommitted changes are not served
+    content.add_post(&PostFixture::new("hello", "Hello, uncommitted").live());
+    content.add_post(&PostFixture::new("wip", "WIP").live());
+
+    let mut config = content.config();
+    config.content.source = ContentSource::Git;
+    let riley_cms = content.riley_cms_with(config).await;
+    assert_eq!(riley_cms.content_revision().await, Some(first.clone()));
+
+    let hello = riley_cms.get_post("hello").await.unwrap().unwrap();
+    assert_eq!(hello.title, "Hello");
+    assert_eq!(
+        hello.updated_at.unwrap().to_rfc3339(),
+        "2024-01-01T00:00:00+00:00"
+    );
+    assert_eq!(hello.last_modified, hello.updated_at);
+    assert!(riley_cms.get_post("wip").await.unwrap().is_none());
+    let series = riley_cms.get_series("rust").await.unwrap().unwrap();
+    assert_eq!(series.posts.len(), 1);
+
+    // A bare repository works, and refresh picks up new commits
+    let bare = content.clone_bare();
+    let mut config = bare.config();
+    config.content.source = ContentSource::Git;
+    let riley_cms = bare.riley_cms_with(config).await;
+    assert_eq!(riley_cms.content_revision().await, Some(first.clone()));
+
+    let second = content.commit("Revise hello", "2024-02-01T00:00:00Z");
+    content.git(&["push", "--quiet", &bare.path().to_string_lossy(), "main"]);
+    riley_cms.refresh().await.unwrap();
+    assert_eq!(riley_cms.content_revision().await, Some(second));
+    let hello = riley_cms.get_post("hello").await.unwrap().unwrap();
+    assert_eq!(hello.title, "Hello, uncommitted");
+    assert!(riley_cms.get_post("wip").await.unwrap().is_some());
+
+    // The working tree isn't reported as a revision
+    let riley_cms = content.riley_cms().await;
+    assert_eq!(riley_cms.content_revision().await, None);
+}
+
+#[tokio::test]
+async fn test_git_source_history_follows_git_ref() {
+    let content = TestContent::new().with_post(PostFixture::new("hello", "Hello").live());
+    content.git_init();
+    content.commit("Add hello", "2024-01-01T00:00:00Z");
+    content.git(&["branch", "published"]);
+    // HEAD moves on, but only `published` is served
+    content.add_post(&PostFixture::new("hello", "Hello, unpublished").live());
+    content.commit("Revise hello", "2024-02-01T00:00:00Z");
+
+    let mut config = content.config();
+    config.content.source = ContentSource::Git;
+    config.content.git_ref = "published".to_string();
+    let riley_cms = content.riley_cms_with(config).await;
+
+    let history = riley_cms.post_history("hello", 10).await.unwrap().unwrap();
+    let messages: Vec<&str> = history.iter().map(|r| r.message.as_str()).collect();
+    assert_eq!(messages, vec!["Add hello"]);
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_git_source_rejects_committed_symlinks() {
+    let content = TestContent::new()
+        .with_post(PostFixture::new("hello", "Hello").live())
+        .with_post(PostFixture::new("linked", "Linked").live());
+    let mdx = content.content_path().join("linked/content.mdx");
+    fs::remove_file(&mdx).unwrap();
+    std::os::unix::fs::symlink("/etc/passwd", &mdx).unwrap();
+    content.git_init();
+    content.commit("Add posts", "2024-01-01T00:00:00Z");
+
+    let mut config = content.config();
+    config.content.source = ContentSource::Git;
+    let riley_cms = content.riley_cms_with(config).await;
+    assert!(riley_cms.get_post("hello").await.unwrap().is_some());
+    assert!(riley_cms.get_post("linked").await.unwrap().is_none());
+    let diagnostics = riley_cms.load_diagnostics().await;
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].path, "linked");
+}
+
+#[tokio::test]
+async fn test_deploy_source_checks_out_each_commit() {
+    let content = TestContent::new().with_post(PostFixture::new("hello", "Hello").live());
+    content.git_init();
+    let first = content.commit("Add hello", "2024-01-01T00:00:00Z");
+    let bare = content.clone_bare();
+    let deploys = TempDir::new().unwrap();
+
+    let mut config = bare.config();
+    config.content.source = ContentSource::Deploy;
+    config.content.deploy.dir = Some(deploys.path().to_path_buf());
+    config.content.deploy.keep = 2;
+    let riley_cms = bare.riley_cms_with(config).await;
+    assert_eq!(riley_cms.content_revision().await, Some(first.clone()));
+    assert!(
+        deploys
+            .path()
+            .join(&first)
+            .join("content/hello/content.mdx")
+            .is_file()
+    );
+    let hello = riley_cms.get_post("hello").await.unwrap().unwrap();
+    assert_eq!(
+        hello.updated_at.unwrap().to_rfc3339(),
+        "2024-01-01T00:00:00+00:00"
+    );
+    // Dated by commit, not by when the checkout was written
+    assert_eq!(hello.last_modified, hello.updated_at);
+
+    let deployed = |n: usize| {
+        let mut names: Vec<String> = fs::read_dir(deploys.path())
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        assert_eq!(names.len(), n, "{:?}", names);
+        names
+    };
+
+    let mut shas = vec![first];
+    for (i, title) in ["Second", "Third"].iter().enumerate() {
+        content.add_post(&PostFixture::new("hello", *title).live());
+        let sha = content.commit(title, &format!("2024-0{}-01T00:00:00Z", i + 2));
+        content.git(&["push", "--quiet", &bare.path().to_string_lossy(), "main"]);
+        assert_eq!(riley_cms.refresh().await.unwrap(), RefreshOutcome::Replaced);
+        assert_eq!(riley_cms.content_revision().await, Some(sha.clone()));
+        let hello = riley_cms.get_post("hello").await.unwrap().unwrap();
+        assert_eq!(hello.title, *title);
+        assert_eq!(
+            hello.last_modified.unwrap().to_rfc3339(),
+            format!("2024-0{}-01T00:00:00+00:00", i + 2)
+        );
+        shas.push(sha);
+    }
+
+    // Only the live deploy and the one before it are kept
+    let mut expected = vec![shas[1].clone(), shas[2].clone()];
+    expected.sort();
+    assert_eq!(deployed(2), expected);
+
+    // Refreshing the same commit reuses its directory
+    riley_cms.refresh().await.unwrap();
+    assert_eq!(deployed(2), expected);
+}
+
+/// Commit a post on a new branch of `content`, leaving `main` checked out
+fn commit_on_branch(content: &TestContent, branch: &str, post: PostFixture) -> String {
+    content.git(&["checkout", "--quiet", "-B", branch]);
+    content.add_post(&post);
+    let sha = content.commit(branch, "2024-02-01T00:00:00Z");
+    content.git(&["checkout", "--quiet", "main"]);
+    sha
+}
+
+#[tokio::test]
+async fn test_branch_previews() {
+    let content = TestContent::new().with_post(PostFixture::new("hello", "Hello").live());
+    content.git_init();
+    content.commit("Add hello", "2024-01-01T00:00:00Z");
+    let first = commit_on_branch(&content, "draft/a", PostFixture::new("a", "A").live());
+    commit_on_branch(&content, "draft/b", PostFixture::new("b", "B").live());
+
+    let mut config = content.config();
+    config.content.preview.branches = vec!["draft/*".to_string()];
+    config.content.preview.max_caches = 1;
+    let riley_cms = content.riley_cms_with(config).await;
+    assert!(riley_cms.get_post("a").await.unwrap().is_none());
+
+    let preview = riley_cms.preview("draft/a").await.unwrap().unwrap();
+    assert!(preview.get_post("a").await.unwrap().is_some());
+    assert!(preview.get_post("hello").await.unwrap().is_some());
+    assert_eq!(preview.content_revision().await, Some(first));
+    let again = riley_cms.preview("draft/a").await.unwrap().unwrap();
+    assert!(std::sync::Arc::ptr_eq(&preview, &again));
+
+    // Previewing another branch evicts the first
+    let b = riley_cms.preview("draft/b").await.unwrap().unwrap();
+    assert!(b.get_post("b").await.unwrap().is_some());
+    let reloaded = riley_cms.preview("draft/a").await.unwrap().unwrap();
+    assert!(!std::sync::Arc::ptr_eq(&preview, &reloaded));
+
+    // A moved branch is reloaded
+    content.git(&["checkout", "--quiet", "draft/a"]);
+    let second = commit_on_branch(
+        &content,
+        "draft/a",
+        PostFixture::new("a", "A, edited").live(),
+    );
+    let moved = riley_cms.preview("draft/a").await.unwrap().unwrap();
+    assert_eq!(moved.content_revision().await, Some(second));
+    assert_eq!(
+        moved.get_post("a").await.unwrap().unwrap().title,
+        "A, edited"
+    );
+
+    assert!(riley_cms.preview("draft/missing").await.unwrap().is_none());
+    assert!(riley_cms.preview("draft/a~1").await.unwrap().is_none());
+    assert!(matches!(
+        riley_cms.preview("main").await,
+        Err(Error::PreviewNotAllowed(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_idle_previews_are_evicted_without_requests() {
+    let content = TestContent::new().with_post(PostFixture::new("hello", "Hello").live());
+    content.git_init();
+    content.commit("Add hello", "2024-01-01T00:00:00Z");
+    commit_on_branch(&content, "draft/a", PostFixture::new("a", "A").live());
+
+    let mut config = content.config();
+    config.content.preview.branches = vec!["draft/*".to_string()];
+    config.content.preview.idle_secs = 1;
+    let riley_cms = content.riley_cms_with(config).await;
+
+    // Concurrent requests for one branch share a single load
+    let (first, second) = tokio::join!(riley_cms.preview("draft/a"), riley_cms.preview("draft/a"));
+    let (first, second) = (first.unwrap().unwrap(), second.unwrap().unwrap());
+    assert!(std::sync::Arc::ptr_eq(&first, &second));
+
+    let preview = std::sync::Arc::downgrade(&first);
+    drop((first, second));
+    for _ in 0..50 {
+        if preview.upgrade().is_none() {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    panic!("idle preview was not evicted");
+}
+
+#[tokio::test]
+async fn test_git_source_without_commits() {
+    let content = TestContent::new().with_post(PostFixture::new("hello", "Hello").live());
+    content.git_init();
+
+    let mut config = content.config();
+    config.content.source = ContentSource::Git;
+    let riley_cms = content.riley_cms_with(config).await;
+    let posts = riley_cms.list_posts(&ListOptions::default()).await.unwrap();
+    assert_eq!(posts.total, 0);
+    assert_eq!(riley_cms.content_revision().await, None);
+}
+
+#[tokio::test]
+async fn test_post_history_without_git() {
+    let content = TestContent::new().with_post(PostFixture::new("hello", "Hello").live());
+    let riley_cms = content.riley_cms().await;
+
+    let post = riley_cms.get_post("hello").await.unwrap().unwrap();
+    assert!(post.created_at.is_none());
+    let history = riley_cms.post_history("hello", 10).await.unwrap().unwrap();
+    assert!(history.is_empty());
+}
+
+/// Poll the webhook delivery log until `done` holds for the newest delivery
+async fn wait_for_delivery(
+    riley_cms: &RileyCms,
+    done: impl Fn(&WebhookDelivery) -> bool,
+) -> WebhookDelivery {
+    for _ in 0..100 {
+        let deliveries = riley_cms.list_webhook_deliveries(1).await.unwrap();
+        if let Some(delivery) = deliveries.into_iter().next()
+            && done(&delivery)
+        {
+            return delivery;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    panic!("webhook delivery did not reach the expected state");
+}
+
+#[tokio::test]
+async fn test_webhook_deliveries_survive_restart() {
+    let content = TestContent::new();
+    let receiver = WebhookReceiver::start().await;
+    receiver.set_status(reqwest::StatusCode::INTERNAL_SERVER_ERROR);
+    let mut config = content.config_with(&receiver.config_toml(None));
+    config.webhooks.as_mut().unwrap().retry_base_secs = 1;
+
+    let changes = ContentChanges {
+        new_sha: Some("abc".to_string()),
+        ..Default::default()
+    };
+    let riley_cms = content.riley_cms_with(config.clone()).await;
+    riley_cms.fire_webhooks(&changes).await;
+    let pending = wait_for_delivery(&riley_cms, |d| !d.attempts.is_empty()).await;
+    assert_eq!(pending.status, DeliveryStatus::Pending);
+    assert_eq!(pending.url, receiver.url());
+    assert_eq!(pending.changes, changes);
+    assert_eq!(pending.attempts[0].status_code, Some(500));
+    assert!(pending.attempts[0].error.is_some());
+    assert!(pending.next_attempt_at.unwrap() > pending.attempts[0].at);
+
+    // A new instance picks the pending delivery up from the queue file
+    drop(riley_cms);
+    receiver.set_status(reqwest::StatusCode::OK);
+    let riley_cms = content.riley_cms_with(config).await;
+    let delivered = wait_for_delivery(&riley_cms, |d| d.status != DeliveryStatus::Pending).await;
+    assert_eq!(delivered.id, pending.id);
+    assert_eq!(delivered.status, DeliveryStatus::Delivered);
+    let codes: Vec<_> = delivered.attempts.iter().map(|a| a.status_code).collect();
+    assert_eq!(codes, [Some(500), Some(200)]);
+    assert_eq!(delivered.next_attempt_at, None);
+    let deliveries = receiver.deliveries();
+    assert_eq!(deliveries.len(), 2);
+    assert_eq!(deliveries[1].json()["new_sha"], "abc");
+}
+
+#[tokio::test]
+async fn test_webhook_delivery_failure_and_redelivery() {
+    let content = TestContent::new();
+    let receiver = WebhookReceiver::start().await;
+    receiver.set_status(reqwest::StatusCode::BAD_REQUEST);
+    let riley_cms = content
+        .riley_cms_with(content.config_with(&receiver.config_toml(None)))
+        .await;
+
+    // 4xx responses are not retried
+    riley_cms.fire_webhooks(&ContentChanges::default()).await;
+    let failed = wait_for_delivery(&riley_cms, |d| d.status != DeliveryStatus::Pending).await;
+    assert_eq!(failed.status, DeliveryStatus::Failed);
+    assert_eq!(failed.attempts.len(), 1);
+    assert_eq!(failed.attempts[0].status_code, Some(400));
+
+    receiver.set_status(reqwest::StatusCode::OK);
+    let queued = riley_cms
+        .redeliver_webhook(&failed.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_ne!(queued.id, failed.id);
+    assert_eq!(queued.redelivery_of.as_deref(), Some(failed.id.as_str()));
+    let redelivered = wait_for_delivery(&riley_cms, |d| d.status != DeliveryStatus::Pending).await;
+    assert_eq!(redelivered.id, queued.id);
+    assert_eq!(redelivered.status, DeliveryStatus::Delivered);
+    assert_eq!(receiver.deliveries().len(), 2);
+
+    let log = riley_cms.list_webhook_deliveries(10).await.unwrap();
+    let ids: Vec<_> = log.iter().map(|d| d.id.as_str()).collect();
+    assert_eq!(ids, [queued.id.as_str(), failed.id.as_str()]);
+    assert!(
+        riley_cms
+            .redeliver_webhook("missing")
+            .await
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[tokio::test]
+async fn test_webhook_endpoints_filter_events_and_send_headers() {
+    let content = TestContent::new();
+    let all = WebhookReceiver::start().await;
+    let series = WebhookReceiver::start().await;
+    let config = content.config_with(&format!(
+        r#"
+[webhooks]
+on_content_update = ["{}"]
+secret = "shared"
+previous_secret = "old"
+allow_private_ips = true
+
+[[webhooks.endpoints]]
+url = "{}"
+events = ["series.*"]
+headers = {{ "x-bypass" = "bypass-token" }}
+"#,
+        all.url(),
+        series.url()
+    ));
+    let riley_cms = content.riley_cms_with(config).await;
+
+    let mut changes = ContentChanges::default();
+    changes.posts.created.push("hello".to_string());
+    riley_cms.fire_webhooks(&changes).await;
+    changes.series.updated.push("guide".to_string());
+    riley_cms.fire_webhooks(&changes).await;
+
+    let received = all.wait_for(2, Duration::from_secs(5)).await;
+    assert_eq!(received.len(), 2);
+    assert!(received[0].header("x-bypass").is_none());
+
+    let received = series.wait_for(1, Duration::from_secs(5)).await;
+    assert_eq!(received.len(), 1);
+    assert_eq!(received[0].json()["series"]["updated"][0], "guide");
+    assert_eq!(received[0].header("x-bypass"), Some("bypass-token"));
+    // The section's secrets apply to endpoints without their own, and either
+    // side of a rotation verifies
+    let timestamp = received[0].header(WEBHOOK_TIMESTAMP_HEADER).unwrap();
+    let signature = received[0].header(WEBHOOK_SIGNATURE_HEADER).unwrap();
+    assert_eq!(signature.matches("v1=").count(), 2);
+    assert_eq!(
+        timestamp,
+        received[0].json()["timestamp"].to_string().as_str()
+    );
+    for secret in ["shared", "old"] {
+        verify_webhook_signature(
+            &received[0].body,
+            timestamp,
+            signature,
+            &[secret],
+            Duration::from_secs(300),
+        )
+        .unwrap();
+    }
+    assert!(matches!(
+        verify_webhook_signature(
+            &received[0].body,
+            timestamp,
+            signature,
+            &["other"],
+            Duration::from_secs(300),
+        ),
+        Err(Error::InvalidWebhookSignature(_))
+    ));
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    assert_eq!(series.deliveries().len(), 1);
+}
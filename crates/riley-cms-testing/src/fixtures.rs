@@ -0,0 +1,474 @@
+//! Content fixtures: temporary content repositories with posts and series
+
+use riley_cms_core::{MemoryStorage, RileyCms, RileyCmsConfig};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::sync::Arc;
+use tempfile::TempDir;
+
+/// A `goes_live_at` far enough in the past that the content is always live
+const LIVE_DATE: &str = "2020-01-01T00:00:00Z";
+
+/// Public URL base used by [`TestContent`] configs
+const TEST_PUBLIC_URL_BASE: &str = "https://test.example.com";
+
+/// Builder for a post directory (`config.toml` + `content.mdx`).
+///
+/// Posts are drafts unless [`live`](Self::live) or [`goes_live_at`](Self::goes_live_at)
+/// is called.
+#[derive(Debug, Clone)]
+pub struct PostFixture {
+    slug: String,
+    title: String,
+    subtitle: Option<String>,
+    preview_text: String,
+    preview_image: Option<String>,
+    tags: Option<Vec<String>>,
+    goes_live_at: Option<String>,
+    order: Option<i64>,
+    content: String,
+}
+
+impl PostFixture {
+    /// A draft post with a generated preview and a `# {title}` body
+    pub fn new(slug: impl Into<String>, title: impl Into<String>) -> Self {
+        let title = title.into();
+        Self {
+            slug: slug.into(),
+            preview_text: format!("Preview for {}", title),
+            content: format!("# {}\n\nContent for this post.", title),
+            title,
+            subtitle: None,
+            preview_image: None,
+            tags: None,
+            goes_live_at: None,
+            order: None,
+        }
+    }
+
+    /// Publish the post at a fixed date in the past
+    pub fn live(self) -> Self {
+        self.goes_live_at(LIVE_DATE)
+    }
+
+    /// Set `goes_live_at` (RFC 3339). A future date makes the post scheduled.
+    pub fn goes_live_at(mut self, date: impl Into<String>) -> Self {
+        self.goes_live_at = Some(date.into());
+        self
+    }
+
+    /// Set the subtitle (none by default)
+    pub fn subtitle(mut self, subtitle: impl Into<String>) -> Self {
+        self.subtitle = Some(subtitle.into());
+        self
+    }
+
+    /// Replace the generated preview text
+    pub fn preview_text(mut self, preview_text: impl Into<String>) -> Self {
+        self.preview_text = preview_text.into();
+        self
+    }
+
+    /// Set the preview image URL (none by default)
+    pub fn preview_image(mut self, preview_image: impl Into<String>) -> Self {
+        self.preview_image = Some(preview_image.into());
+        self
+    }
+
+    /// Set the tags (none by default)
+    pub fn tags(mut self, tags: &[&str]) -> Self {
+        self.tags = Some(tags.iter().map(|t| t.to_string()).collect());
+        self
+    }
+
+    /// Order within a series
+    pub fn order(mut self, order: i64) -> Self {
+        self.order = Some(order);
+        self
+    }
+
+    /// Replace the MDX body
+    pub fn content(mut self, content: impl Into<String>) -> Self {
+        self.content = content.into();
+        self
+    }
+
+    /// The post's slug (its directory name)
+    pub fn slug(&self) -> &str {
+        &self.slug
+    }
+
+    /// Render the post's `config.toml`
+    pub fn config_toml(&self) -> String {
+        let mut table = toml::Table::new();
+        table.insert("title".into(), self.title.clone().into());
+        if let Some(ref subtitle) = self.subtitle {
+            table.insert("subtitle".into(), subtitle.clone().into());
+        }
+        table.insert("preview_text".into(), self.preview_text.clone().into());
+        if let Some(ref image) = self.preview_image {
+            table.insert("preview_image".into(), image.clone().into());
+        }
+        if let Some(ref tags) = self.tags {
+            table.insert("tags".into(), tags.clone().into());
+        }
+        if let Some(ref date) = self.goes_live_at {
+            table.insert("goes_live_at".into(), date.clone().into());
+        }
+        if let Some(order) = self.order {
+            table.insert("order".into(), order.into());
+        }
+        toml::to_string(&table).expect("post config serializes")
+    }
+
+    /// Write the post directory under `parent` (e.g. the content dir or a series dir)
+    pub fn write_to(&self, parent: &Path) -> PathBuf {
+        let dir = parent.join(&self.slug);
+        fs::create_dir_all(&dir).expect("create post dir");
+        fs::write(dir.join("config.toml"), self.config_toml()).expect("write config.toml");
+        fs::write(dir.join("content.mdx"), &self.content).expect("write content.mdx");
+        dir
+    }
+}
+
+/// Builder for a series directory (`series.toml` plus post subdirectories).
+///
+/// Series are drafts unless [`live`](Self::live) or [`goes_live_at`](Self::goes_live_at)
+/// is called.
+#[derive(Debug, Clone)]
+pub struct SeriesFixture {
+    slug: String,
+    title: String,
+    description: Option<String>,
+    preview_image: Option<String>,
+    goes_live_at: Option<String>,
+    posts: Vec<PostFixture>,
+}
+
+impl SeriesFixture {
+    /// A draft series with a generated description and no posts
+    pub fn new(slug: impl Into<String>, title: impl Into<String>) -> Self {
+        let title = title.into();
+        Self {
+            slug: slug.into(),
+            description: Some(format!("Description for {}", title)),
+            title,
+            preview_image: None,
+            goes_live_at: None,
+            posts: Vec::new(),
+        }
+    }
+
+    /// Publish the series at a fixed date in the past
+    pub fn live(self) -> Self {
+        self.goes_live_at(LIVE_DATE)
+    }
+
+    /// Set `goes_live_at` (RFC 3339). A future date makes the series scheduled.
+    pub fn goes_live_at(mut self, date: impl Into<String>) -> Self {
+        self.goes_live_at = Some(date.into());
+        self
+    }
+
+    /// Replace the generated description
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Set the preview image URL (none by default)
+    pub fn preview_image(mut self, preview_image: impl Into<String>) -> Self {
+        self.preview_image = Some(preview_image.into());
+        self
+    }
+
+    /// Add a post to the series
+    pub fn post(mut self, post: PostFixture) -> Self {
+        self.posts.push(post);
+        self
+    }
+
+    /// Render the series' `series.toml`
+    pub fn series_toml(&self) -> String {
+        let mut table = toml::Table::new();
+        table.insert("title".into(), self.title.clone().into());
+        if let Some(ref description) = self.description {
+            table.insert("description".into(), description.clone().into());
+        }
+        if let Some(ref image) = self.preview_image {
+            table.insert("preview_image".into(), image.clone().into());
+        }
+        if let Some(ref date) = self.goes_live_at {
+            table.insert("goes_live_at".into(), date.clone().into());
+        }
+        toml::to_string(&table).expect("series config serializes")
+    }
+
+    /// Write the series directory and its posts under `parent`
+    pub fn write_to(&self, parent: &Path) -> PathBuf {
+        let dir = parent.join(&self.slug);
+        fs::create_dir_all(&dir).expect("create series dir");
+        fs::write(dir.join("series.toml"), self.series_toml()).expect("write series.toml");
+        for post in &self.posts {
+            post.write_to(&dir);
+        }
+        dir
+    }
+}
+
+/// A temporary content repository.
+///
+/// The repository root is a fresh temp dir (removed on drop) with content in
+/// `content/`. [`RileyCms`] instances built from it store assets in a [`MemoryStorage`].
+pub struct TestContent {
+    dir: TempDir,
+}
+
+impl TestContent {
+    /// Create an empty content repository
+    pub fn new() -> Self {
+        let dir = TempDir::new().expect("create temp dir");
+        fs::create_dir_all(dir.path().join("content")).expect("create content dir");
+        Self { dir }
+    }
+
+    /// Repository root (`content.repo_path`)
+    pub fn path(&self) -> &Path {
+        self.dir.path()
+    }
+
+    /// Content directory (`repo_path/content`)
+    pub fn content_path(&self) -> PathBuf {
+        self.dir.path().join("content")
+    }
+
+    /// Add a standalone post
+    pub fn with_post(self, post: PostFixture) -> Self {
+        self.add_post(&post);
+        self
+    }
+
+    /// Add a series and its posts
+    pub fn with_series(self, series: SeriesFixture) -> Self {
+        self.add_series(&series);
+        self
+    }
+
+    /// Add (or overwrite) a standalone post in place
+    pub fn add_post(&self, post: &PostFixture) -> PathBuf {
+        post.write_to(&self.content_path())
+    }
+
+    /// Add (or overwrite) a series in place
+    pub fn add_series(&self, series: &SeriesFixture) -> PathBuf {
+        series.write_to(&self.content_path())
+    }
+
+    /// Write an arbitrary file relative to the content directory.
+    ///
+    /// Useful for invalid content that the builders refuse to produce.
+    pub fn write_file(&self, relative: impl AsRef<Path>, contents: impl AsRef<[u8]>) -> PathBuf {
+        let path = self.content_path().join(relative);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).expect("create parent dir");
+        }
+        fs::write(&path, contents).expect("write file");
+        path
+    }
+
+    /// Make the repository a git repository on branch `main`
+    pub fn git_init(&self) -> &Self {
+        self.git(&["init", "--quiet", "--initial-branch=main"]);
+        self
+    }
+
+    /// Let git-http-backend accept pushes to this repository, including to its
+    /// checked-out branch (riley_cms checks the working tree out after a push)
+    pub fn accept_pushes(&self) -> &Self {
+        self.git(&["config", "http.receivepack", "true"]);
+        self.git(&["config", "receive.denyCurrentBranch", "ignore"]);
+        self
+    }
+
+    /// Clone this repository into a new temporary repository, e.g. to push from
+    pub fn clone_repo(&self) -> TestContent {
+        let dir = TempDir::new().expect("create temp dir");
+        let output = self
+            .git_command()
+            .arg("clone")
+            .arg("--quiet")
+            .arg(self.path())
+            .arg(dir.path())
+            .output()
+            .expect("run git clone");
+        assert!(
+            output.status.success(),
+            "git clone failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        fs::create_dir_all(dir.path().join("content")).expect("create content dir");
+        Self { dir }
+    }
+
+    /// Clone this repository into a new temporary bare repository, e.g. to serve
+    /// with `content.source = "git"`
+    pub fn clone_bare(&self) -> TestContent {
+        let dir = TempDir::new().expect("create temp dir");
+        let output = self
+            .git_command()
+            .args(["clone", "--quiet", "--bare"])
+            .arg(self.path())
+            .arg(dir.path())
+            .output()
+            .expect("run git clone");
+        assert!(
+            output.status.success(),
+            "git clone failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        Self { dir }
+    }
+
+    /// Run `git push <url> <refspec>`, returning its output whether or not it succeeded.
+    ///
+    /// Blocks, so a server on the same runtime needs a multi-threaded test runtime.
+    pub fn push(&self, url: &str, refspec: &str) -> Output {
+        self.git_command()
+            .args(["push", url, refspec])
+            .output()
+            .expect("run git push")
+    }
+
+    /// Commit all changes as "Test Author" at `date` (RFC 3339), returning the commit SHA
+    pub fn commit(&self, message: &str, date: &str) -> String {
+        self.git(&["add", "-A"]);
+        let output = self
+            .git_command()
+            .args(["commit", "--quiet", "--allow-empty", "-m", message])
+            .env("GIT_AUTHOR_DATE", date)
+            .env("GIT_COMMITTER_DATE", date)
+            .output()
+            .expect("run git commit");
+        assert!(
+            output.status.success(),
+            "git commit failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        self.git(&["rev-parse", "HEAD"])
+    }
+
+    /// Run git in the repository, returning trimmed stdout. Panics on failure.
+    pub fn git(&self, args: &[&str]) -> String {
+        let output = self.git_command().args(args).output().expect("run git");
+        assert!(
+            output.status.success(),
+            "git {:?} failed: {}",
+            args,
+            String::from_utf8_lossy(&output.stderr)
+        );
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    }
+
+    /// A git command in the repository, isolated from the user's git config
+    fn git_command(&self) -> Command {
+        let mut command = Command::new("git");
+        command
+            .arg("-C")
+            .arg(self.path())
+            .env("GIT_CONFIG_NOSYSTEM", "1")
+            .env("GIT_CONFIG_GLOBAL", "/dev/null")
+            .env("GIT_AUTHOR_NAME", "Test Author")
+            .env("GIT_AUTHOR_EMAIL", "author@example.com")
+            .env("GIT_COMMITTER_NAME", "Test Author")
+            .env("GIT_COMMITTER_EMAIL", "author@example.com");
+        command
+    }
+
+    /// A config for this repository with default settings
+    pub fn config(&self) -> RileyCmsConfig {
+        self.config_with("")
+    }
+
+    /// Like [`config`](Self::config), with extra TOML appended (e.g. `[auth]` or `[webhooks]`)
+    pub fn config_with(&self, extra_toml: &str) -> RileyCmsConfig {
+        let toml = format!(
+            r#"
+[content]
+repo_path = '{}'
+content_dir = "content"
+
+[storage]
+public_url_base = "{}"
+
+{}
+"#,
+            self.path().display(),
+            TEST_PUBLIC_URL_BASE,
+            extra_toml
+        );
+        toml::from_str(&toml).expect("test config parses")
+    }
+
+    /// Build a `RileyCms` over this repository with a fresh [`MemoryStorage`]
+    pub async fn riley_cms(&self) -> RileyCms {
+        self.riley_cms_with(self.config()).await
+    }
+
+    /// Build a `RileyCms` from a config (usually from [`config_with`](Self::config_with)),
+    /// with its background tasks started as `serve` would
+    pub async fn riley_cms_with(&self, config: RileyCmsConfig) -> RileyCms {
+        let storage = Arc::new(MemoryStorage::new(TEST_PUBLIC_URL_BASE));
+        let riley_cms = RileyCms::with_storage(config, storage)
+            .await
+            .expect("RileyCms loads test content");
+        riley_cms.start_background_tasks();
+        riley_cms
+    }
+}
+
+impl Default for TestContent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use riley_cms_core::ListOptions;
+
+    #[test]
+    fn test_post_config_escapes_strings() {
+        let toml = PostFixture::new("quoted", r#"A "quoted" title"#)
+            .tags(&["rust"])
+            .order(2)
+            .live()
+            .config_toml();
+        let parsed: riley_cms_core::PostConfig = toml::from_str(&toml).unwrap();
+        assert_eq!(parsed.title, r#"A "quoted" title"#);
+        assert_eq!(parsed.order, Some(2));
+        assert!(parsed.goes_live_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_content_loads_into_riley_cms() {
+        let content = TestContent::new()
+            .with_post(PostFixture::new("live", "Live").live())
+            .with_post(PostFixture::new("draft", "Draft"))
+            .with_series(
+                SeriesFixture::new("series", "Series")
+                    .live()
+                    .post(PostFixture::new("part-one", "Part One").order(1).live()),
+            );
+
+        let riley_cms = content.riley_cms().await;
+
+        let posts = riley_cms.list_posts(&ListOptions::default()).await.unwrap();
+        assert_eq!(posts.total, 2);
+
+        let series = riley_cms.get_series("series").await.unwrap().unwrap();
+        assert_eq!(series.posts.len(), 1);
+        assert_eq!(series.posts[0].slug, "part-one");
+    }
+}
@@ -0,0 +1,33 @@
+//! # riley-cms-testing
+//!
+//! Test support for crates that embed riley_cms.
+//!
+//! - [`TestContent`]: a temporary content repository with builders for posts
+//!   and series ([`PostFixture`], [`SeriesFixture`]), optionally under git
+//! - [`MemoryStorage`]: an in-memory asset store, so tests never touch S3
+//! - [`WebhookReceiver`]: a local HTTP server that records webhook deliveries
+//!
+//! ## Example
+//!
+//! ```ignore
+//! use riley_cms_core::ListOptions;
+//! use riley_cms_testing::{PostFixture, TestContent};
+//!
+//! #[tokio::test]
+//! async fn lists_live_posts() {
+//!     let content = TestContent::new()
+//!         .with_post(PostFixture::new("hello", "Hello").live())
+//!         .with_post(PostFixture::new("draft", "Draft"));
+//!
+//!     let riley_cms = content.riley_cms().await;
+//!     let posts = riley_cms.list_posts(&ListOptions::default()).await.unwrap();
+//!     assert_eq!(posts.total, 1);
+//! }
+//! ```
+
+mod fixtures;
+mod webhook;
+
+pub use fixtures::{PostFixture, SeriesFixture, TestContent};
+pub use riley_cms_core::MemoryStorage;
+pub use webhook::{Delivery, WebhookReceiver};
@@ -0,0 +1,218 @@
+//! Local webhook receiver that records deliveries
+
+use axum::Router;
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::{HeaderMap, StatusCode};
+use axum::routing::post;
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU16, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{Notify, oneshot};
+
+/// A single recorded webhook request
+#[derive(Debug, Clone)]
+pub struct Delivery {
+    /// Request headers, with lowercased names
+    pub headers: BTreeMap<String, String>,
+    /// Raw request body
+    pub body: Vec<u8>,
+}
+
+impl Delivery {
+    /// Get a header by (case-insensitive) name
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .get(&name.to_ascii_lowercase())
+            .map(String::as_str)
+    }
+
+    /// Parse the body as JSON
+    pub fn json(&self) -> serde_json::Value {
+        serde_json::from_slice(&self.body).expect("webhook body is JSON")
+    }
+}
+
+#[derive(Default)]
+struct ReceiverState {
+    deliveries: Mutex<Vec<Delivery>>,
+    status: AtomicU16,
+    notify: Notify,
+}
+
+/// An HTTP server on `127.0.0.1` that records every POST it receives.
+///
+/// riley_cms refuses to deliver webhooks to loopback addresses by default, so
+/// configs pointing at a receiver need `allow_private_ips = true`; see
+/// [`config_toml`](Self::config_toml). The server shuts down on drop.
+pub struct WebhookReceiver {
+    addr: SocketAddr,
+    state: Arc<ReceiverState>,
+    shutdown: Option<oneshot::Sender<()>>,
+}
+
+impl WebhookReceiver {
+    /// Start a receiver on an ephemeral port. Responds `200 OK` until told otherwise.
+    pub async fn start() -> Self {
+        let state = Arc::new(ReceiverState {
+            status: AtomicU16::new(StatusCode::OK.as_u16()),
+            ..Default::default()
+        });
+
+        let app = Router::new()
+            .route("/{*path}", post(record))
+            .route("/", post(record))
+            .with_state(state.clone());
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind webhook receiver");
+        let addr = listener.local_addr().expect("receiver address");
+
+        let (shutdown, rx) = oneshot::channel::<()>();
+        tokio::spawn(async move {
+            let _ = axum::serve(listener, app)
+                .with_graceful_shutdown(async {
+                    let _ = rx.await;
+                })
+                .await;
+        });
+
+        Self {
+            addr,
+            state,
+            shutdown: Some(shutdown),
+        }
+    }
+
+    /// URL to configure as a webhook target
+    pub fn url(&self) -> String {
+        format!("http://{}/webhook", self.addr)
+    }
+
+    /// A `[webhooks]` config section that delivers to this receiver
+    pub fn config_toml(&self, secret: Option<&str>) -> String {
+        let secret_line = secret
+            .map(|s| format!("secret = \"{}\"\n", s))
+            .unwrap_or_default();
+        format!(
+            "[webhooks]\non_content_update = [\"{}\"]\nallow_private_ips = true\n{}",
+            self.url(),
+            secret_line
+        )
+    }
+
+    /// Status code to answer subsequent deliveries with (e.g. 500 to exercise retries)
+    pub fn set_status(&self, status: StatusCode) {
+        self.state.status.store(status.as_u16(), Ordering::SeqCst);
+    }
+
+    /// All deliveries received so far
+    pub fn deliveries(&self) -> Vec<Delivery> {
+        self.state
+            .deliveries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Wait until at least `count` deliveries have arrived, or `timeout` elapses.
+    ///
+    /// Returns whatever has been received; callers assert on the length.
+    pub async fn wait_for(&self, count: usize, timeout: Duration) -> Vec<Delivery> {
+        let _ = tokio::time::timeout(timeout, async {
+            loop {
+                let notified = self.state.notify.notified();
+                if self.deliveries().len() >= count {
+                    return;
+                }
+                notified.await;
+            }
+        })
+        .await;
+        self.deliveries()
+    }
+}
+
+impl Drop for WebhookReceiver {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+    }
+}
+
+async fn record(
+    State(state): State<Arc<ReceiverState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> StatusCode {
+    let headers = headers
+        .iter()
+        .filter_map(|(name, value)| {
+            value
+                .to_str()
+                .ok()
+                .map(|v| (name.as_str().to_string(), v.to_string()))
+        })
+        .collect();
+
+    state
+        .deliveries
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .push(Delivery {
+            headers,
+            body: body.to_vec(),
+        });
+    state.notify.notify_waiters();
+
+    StatusCode::from_u16(state.status.load(Ordering::SeqCst)).unwrap_or(StatusCode::OK)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PostFixture, TestContent};
+    use riley_cms_core::ContentChanges;
+
+    #[tokio::test]
+    async fn test_receives_signed_content_update() {
+        let receiver = WebhookReceiver::start().await;
+        let content = TestContent::new().with_post(PostFixture::new("hello", "Hello").live());
+        let config = content.config_with(&receiver.config_toml(Some("test-secret")));
+        let riley_cms = content.riley_cms_with(config).await;
+
+        riley_cms.fire_webhooks(&ContentChanges::default()).await;
+
+        let deliveries = receiver.wait_for(1, Duration::from_secs(5)).await;
+        assert_eq!(deliveries.len(), 1);
+        assert_eq!(deliveries[0].json()["event"], "content_update");
+        riley_cms_core::verify_webhook_signature(
+            &deliveries[0].body,
+            deliveries[0].header("X-Riley-Cms-Timestamp").unwrap(),
+            deliveries[0].header("X-Riley-Cms-Signature").unwrap(),
+            &["test-secret"],
+            Duration::from_secs(300),
+        )
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_private_ips_rejected_by_default() {
+        let receiver = WebhookReceiver::start().await;
+        let content = TestContent::new();
+        let config = content.config_with(&format!(
+            "[webhooks]\non_content_update = [\"{}\"]\n",
+            receiver.url()
+        ));
+        let riley_cms = content.riley_cms_with(config).await;
+
+        riley_cms.fire_webhooks(&ContentChanges::default()).await;
+
+        let deliveries = receiver.wait_for(1, Duration::from_millis(500)).await;
+        assert!(deliveries.is_empty());
+    }
+}